@@ -0,0 +1,247 @@
+use anchor_lang::prelude::Pubkey;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[clap(name = "token-sale", about = "Operates the token sale")]
+pub struct Args {
+    /// The JSON RPC URL of the cluster
+    #[clap(long, short = 'u', default_value = "http://localhost:8899")]
+    pub url: String,
+    /// The keypair that signs and pays for the transactions
+    #[clap(long, short = 'k', default_value = "~/.config/solana/id.json")]
+    pub keypair: String,
+    /// The mint of the selling tokens, required by all the commands but `simulate`
+    #[clap(long, short = 'm')]
+    pub mint: Option<Pubkey>,
+    /// The sale of the mint to operate, `init` takes it from the config
+    #[clap(long, short = 's', default_value_t = 0)]
+    pub sale_id: u64,
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Starts the sale of the tokens from the signer's account
+    Init {
+        /// The sale config, TOML or JSON
+        #[clap(long)]
+        config: PathBuf,
+        /// The token account to take the tokens for sale from, the signer's associated one by default
+        #[clap(long)]
+        tokens_account: Option<Pubkey>,
+        /// Only validates the config and prints the schedule of the sale
+        #[clap(long)]
+        dry_run: bool,
+    },
+    /// Shows the state of the pool
+    Status,
+    /// Switches to the buying round if trading and vice versa
+    Switch,
+    #[clap(subcommand)]
+    Withdraw(Withdraw),
+    /// Hands the signer's mint authority to the pool of a sale initialized without tokens,
+    /// the pool then mints the tokens it sells
+    EnableMinting {
+        /// The most tokens the pool could mint, not capped by default
+        #[clap(long)]
+        max_supply: Option<u64>,
+        /// Revokes the mint authority upon termination instead of handing it to the admin
+        #[clap(long)]
+        revoke_mint_authority: bool,
+    },
+    /// Limits the purchases made by the bots at the openings of the rounds, zero lifts a limit
+    Protect {
+        /// The most tokens sold within a slot
+        #[clap(long, default_value_t = 0)]
+        max_tokens_per_slot: u64,
+        /// Seconds a buyer waits between the purchases
+        #[clap(long, default_value_t = 0)]
+        cooldown: u32,
+        /// Rejects the purchases invoked by other programs
+        #[clap(long)]
+        reject_cpi: bool,
+        /// The program still allowed to invoke the purchases
+        #[clap(long, requires = "reject-cpi")]
+        trusted_caller: Option<Pubkey>,
+    },
+    /// Makes the sale tiered: a buyer gets the allocation of the tier the stake reaches
+    Tiers {
+        /// The mint of the governance tokens the buyers stake
+        #[clap(long)]
+        stake_mint: Pubkey,
+        /// `MIN_STAKE:ALLOCATION` of every tier in minimal parts, ordered by the stake
+        #[clap(required = true)]
+        tiers: Vec<String>,
+    },
+    /// Locks the signer's governance tokens for an allocation tier until the end of the sale
+    Stake { amount: u64 },
+    /// Takes the staked tokens back after the end of the sale
+    Unstake {
+        /// The mint of the staked tokens, read from the pool by default
+        #[clap(long)]
+        stake_mint: Option<Pubkey>,
+    },
+    /// Refunds the rent of the signer's purchase record after the end of the sale
+    CloseBuyerAccount,
+    #[clap(subcommand)]
+    Lottery(Lottery),
+    #[clap(subcommand)]
+    Auction(Auction),
+    /// Disposes the unsold tokens and closes the pool after the end of the sale
+    Terminate,
+    /// Brings the pool and its orders to the layout of the deployed program
+    Migrate,
+    #[clap(subcommand)]
+    Orders(Orders),
+    #[clap(subcommand)]
+    Order(Order),
+    /// Buys tokens from the pool
+    Buy { amount: u64 },
+    /// Buys as many tokens from the pool as the SOL pays for
+    Spend { sol: String },
+    /// Projects the rounds, prices and proceeds of the sale offline
+    Simulate {
+        /// The sale config, TOML or JSON
+        #[clap(long)]
+        config: PathBuf,
+        /// The decimals of the mint
+        #[clap(long)]
+        decimals: u8,
+        /// Whole tokens the buyers want in every buying round
+        #[clap(long, required_unless_present = "budget-per-round", conflicts_with = "budget-per-round")]
+        tokens_per_round: Option<String>,
+        /// SOL the buyers spend in every buying round
+        #[clap(long)]
+        budget_per_round: Option<String>,
+    },
+}
+
+/// Takes the funds out of the pool
+#[derive(Debug, Subcommand)]
+pub enum Withdraw {
+    /// Sends the lamports of the pool to the treasury
+    Lamports,
+    /// Takes unsold tokens back from the pool between the buying rounds
+    UnsoldTokens {
+        amount: u64,
+        /// The token account to send the tokens to, the signer's associated one by default
+        #[clap(long)]
+        destination: Option<Pubkey>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Orders {
+    /// Lists the orders of the pool that still have tokens to sell
+    List,
+}
+
+/// Manages the orders of the trading round
+#[derive(Debug, Subcommand)]
+pub enum Order {
+    /// Places the signer's order
+    Place { amount: u64, price: u64 },
+    /// Buys tokens from the order of the seller
+    Fill { seller: Pubkey, amount: u64 },
+    /// Closes the signer's order and takes the tokens left back
+    Close,
+    /// Returns the tokens of an order left open after the end of the sale to the seller and closes it
+    Reclaim { seller: Pubkey },
+}
+
+/// Runs the fair launch of the sale, only the wallets whose tickets are drawn buy
+#[derive(Debug, Subcommand)]
+pub enum Lottery {
+    /// Opens the registration of the tickets and commits to the secret that draws the winners
+    Start {
+        /// RFC 3339 date the registration closes at
+        #[clap(long)]
+        registration_ends_at: String,
+        /// The tickets drawn
+        #[clap(long)]
+        winners: u32,
+        /// The tokens every winner could buy, in minimal parts
+        #[clap(long)]
+        allocation: u64,
+        /// 64 hex digits kept until the draw, e.g. from `openssl rand -hex 32`
+        #[clap(long)]
+        secret: String,
+    },
+    /// Registers the signer's ticket
+    Register,
+    /// Fixes the slot mixed into the draw once the registration is over, the draw has to follow within 512 slots
+    /// or the slot is picked again
+    Close,
+    /// Reveals the secret and draws the winners
+    Draw {
+        /// The secret committed by `lottery start`
+        #[clap(long)]
+        secret: String,
+    },
+    /// Lets everyone buy once the winners could no longer be drawn
+    Abandon,
+    /// Trades the signer's winning ticket for the allocation
+    Claim,
+    /// Shows the lottery and whether the signer's ticket won
+    Status,
+}
+
+/// The sealed-bid auction of the tokens, the highest revealed bids get them at their own prices
+#[derive(Debug, Subcommand)]
+pub enum Auction {
+    /// Starts the auction instead of the current round
+    Start {
+        /// Seconds the bids are committed for
+        #[clap(long)]
+        commit_duration: u32,
+        /// Seconds the bids are revealed for
+        #[clap(long)]
+        reveal_duration: u32,
+        /// The tokens of the vault sold, in minimal parts
+        #[clap(long)]
+        tokens: u64,
+    },
+    /// Commits the signer's sealed bid
+    Bid {
+        /// The tokens asked for, in minimal parts
+        #[clap(long)]
+        tokens: u64,
+        /// Lamports for the price units of the pool, no less than the token price
+        #[clap(long)]
+        price: u64,
+        /// SOL deposited, at least the cost of the bid. Deposit more to hide the bid.
+        #[clap(long)]
+        deposit: String,
+        /// 64 hex digits kept until the reveal, e.g. from `openssl rand -hex 32`
+        #[clap(long)]
+        salt: String,
+    },
+    /// Reveals the signer's bid with the same values it was committed with
+    Reveal {
+        #[clap(long)]
+        tokens: u64,
+        #[clap(long)]
+        price: u64,
+        #[clap(long)]
+        salt: String,
+    },
+    /// Gives the bidder the tokens won and refunds the rest of the deposit after the reveal
+    Settle {
+        /// The owner of the bid, the signer by default
+        #[clap(long)]
+        bidder: Option<Pubkey>,
+    },
+    /// Shows the ranked bids and the signer's bid
+    Status,
+}
+
+impl Args {
+    pub fn keypair_path(&self) -> PathBuf {
+        match (self.keypair.strip_prefix("~/"), std::env::var_os("HOME")) {
+            (Some(path), Some(home)) => PathBuf::from(home).join(path),
+            _ => PathBuf::from(&self.keypair),
+        }
+    }
+}
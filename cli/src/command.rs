@@ -0,0 +1,678 @@
+use crate::args::{Auction, Command, Lottery, Order, Orders, Withdraw};
+use crate::context::Context;
+use crate::error::Result;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_lang::Owners;
+use anchor_spl::token_interface::TokenAccount;
+use solana_sdk::account::from_account;
+use solana_sdk::clock::Clock;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::sysvar;
+use std::path::Path;
+use token_sale_client::account::{
+    fetch_active_orders, fetch_auction, fetch_bid, fetch_lottery, fetch_order, fetch_outdated_orders, fetch_pool,
+    fetch_ticket, registry_page_with_room,
+};
+use token_sale_client::auction::{bid_commitment, clearing_price, fills};
+use token_sale_client::config::{
+    format_date, lamports_from_sol, schedule, timestamp_from_date, tokens_from_ui, ui_amount, ui_price, validate,
+    SaleConfig,
+};
+use token_sale_client::lottery::{format_hash, parse_secret, secret_hash};
+use token_sale_client::simulator::{simulate as simulate_sale, BudgetDemand, FixedDemand};
+use token_sale_client::{instruction, pda, quote, Error, Round, Tier, Tokens, UnsoldTokensPolicy};
+
+pub fn run(ctx: &Context, command: &Command) -> Result<()> {
+    match command {
+        Command::Init { config, tokens_account, dry_run } => init(ctx, config, *tokens_account, *dry_run),
+        Command::Status => status(ctx),
+        Command::Switch => switch(ctx),
+        Command::Withdraw(Withdraw::Lamports) => withdraw_lamports(ctx),
+        Command::Withdraw(Withdraw::UnsoldTokens { amount, destination }) => {
+            withdraw_unsold_tokens(ctx, *amount, *destination)
+        }
+        Command::EnableMinting { max_supply, revoke_mint_authority } => {
+            enable_minting(ctx, *max_supply, *revoke_mint_authority)
+        }
+        Command::Protect { max_tokens_per_slot, cooldown, reject_cpi, trusted_caller } => {
+            protect(ctx, *max_tokens_per_slot, *cooldown, *reject_cpi, *trusted_caller)
+        }
+        Command::Tiers { stake_mint, tiers } => set_tiers(ctx, stake_mint, tiers),
+        Command::Stake { amount } => stake(ctx, *amount),
+        Command::Unstake { stake_mint } => unstake(ctx, *stake_mint),
+        Command::CloseBuyerAccount => {
+            send(ctx, &[instruction::close_buyer_account(&ctx.mint, ctx.sale_id, &ctx.signer_key())])
+        }
+        Command::Lottery(Lottery::Start { registration_ends_at, winners, allocation, secret }) => {
+            start_lottery(ctx, registration_ends_at, *winners, *allocation, secret)
+        }
+        Command::Lottery(Lottery::Register) => {
+            send(ctx, &[instruction::register_ticket(&ctx.mint, ctx.sale_id, &ctx.signer_key())])
+        }
+        Command::Lottery(Lottery::Close) => send(ctx, &[instruction::close_registration(&ctx.mint, ctx.sale_id)]),
+        Command::Lottery(Lottery::Draw { secret }) => draw_lottery(ctx, secret),
+        Command::Lottery(Lottery::Abandon) => send(ctx, &[instruction::abandon_lottery(&ctx.mint, ctx.sale_id)]),
+        Command::Lottery(Lottery::Claim) => {
+            send(ctx, &[instruction::claim_allocation(&ctx.mint, ctx.sale_id, &ctx.signer_key())])
+        }
+        Command::Lottery(Lottery::Status) => lottery_status(ctx),
+        Command::Auction(Auction::Start { commit_duration, reveal_duration, tokens }) => {
+            start_auction(ctx, *commit_duration, *reveal_duration, *tokens)
+        }
+        Command::Auction(Auction::Bid { tokens, price, deposit, salt }) => {
+            commit_bid(ctx, *tokens, *price, deposit, salt)
+        }
+        Command::Auction(Auction::Reveal { tokens, price, salt }) => reveal_bid(ctx, *tokens, *price, salt),
+        Command::Auction(Auction::Settle { bidder }) => settle_bid(ctx, *bidder),
+        Command::Auction(Auction::Status) => auction_status(ctx),
+        Command::Terminate => terminate(ctx),
+        Command::Migrate => migrate(ctx),
+        Command::Orders(Orders::List) => list_orders(ctx),
+        Command::Order(Order::Place { amount, price }) => place_order(ctx, *amount, *price),
+        Command::Order(Order::Fill { seller, amount }) => fill_order(ctx, seller, *amount),
+        Command::Order(Order::Close) => close_order(ctx),
+        Command::Order(Order::Reclaim { seller }) => reclaim_order(ctx, seller),
+        Command::Buy { amount } => buy(ctx, *amount),
+        Command::Spend { sol } => spend(ctx, sol),
+        Command::Simulate { .. } => run_offline(command),
+    }
+}
+
+/// Runs the commands that do not need a cluster
+pub fn run_offline(command: &Command) -> Result<()> {
+    match command {
+        Command::Simulate { config, decimals, tokens_per_round, budget_per_round } => {
+            simulate(config, *decimals, tokens_per_round.as_deref(), budget_per_round.as_deref())
+        }
+        _ => unreachable!("{:?} needs a cluster", command),
+    }
+}
+
+fn send(ctx: &Context, instructions: &[Instruction]) -> Result<()> {
+    let signature = ctx.send(instructions)?;
+    println!("Signature: {}", signature);
+    Ok(())
+}
+
+/// Creates the signer's token account if it does not exist yet
+fn create_signer_token_account(ctx: &Context) -> Result<Vec<Instruction>> {
+    if ctx.account_exists(&ctx.signer_token_account())? {
+        return Ok(vec![]);
+    }
+    let signer = ctx.signer_key();
+    Ok(vec![spl_associated_token_account::instruction::create_associated_token_account(
+        &signer,
+        &signer,
+        &ctx.mint,
+        &ctx.token_program,
+    )])
+}
+
+fn init(ctx: &Context, config: &Path, tokens_account: Option<Pubkey>, dry_run: bool) -> Result<()> {
+    let tokens_account = tokens_account.unwrap_or_else(|| ctx.signer_token_account());
+    let tokens_for_sale = ctx.rpc.get_token_account_balance(&tokens_account)?;
+    let decimals = tokens_for_sale.decimals;
+    let tokens_for_sale = tokens_for_sale.amount.parse::<u64>().unwrap_or_default();
+    let now = cluster_time(ctx)?;
+
+    let config = SaleConfig::load(config)?;
+    let args = config.initialize_args(decimals)?;
+    let unsold_tokens_destination = config.unsold_tokens_destination()?;
+    print_schedule(&args, decimals)?;
+    let destination_mint = match args.unsold_tokens_policy {
+        UnsoldTokensPolicy::SendToDestination => token_account_mint(ctx, &unsold_tokens_destination)?,
+        UnsoldTokensPolicy::Burn | UnsoldTokensPolicy::ReturnToAdmin => None,
+    };
+    validate(&args, now, tokens_for_sale, &ctx.mint, destination_mint)?;
+    if dry_run {
+        return Ok(());
+    }
+
+    let pool = pda::pool_address(&ctx.mint, args.sale_id);
+    let initialize = instruction::initialize(
+        &ctx.signer_key(),
+        &tokens_account,
+        &ctx.mint,
+        &ctx.token_program,
+        &unsold_tokens_destination,
+        args,
+    );
+    send(ctx, &[initialize])?;
+    println!("Pool: {}", pool);
+    Ok(())
+}
+
+/// The mint of the token account, `None` if there is no token account at the address
+fn token_account_mint(ctx: &Context, address: &Pubkey) -> Result<Option<Pubkey>> {
+    let account = ctx.rpc.get_account_with_commitment(address, ctx.rpc.commitment())?.value;
+    Ok(account
+        .filter(|account| TokenAccount::owners().contains(&account.owner))
+        .and_then(|account| TokenAccount::try_deserialize(&mut account.data.as_slice()).ok())
+        .map(|token_account| token_account.mint))
+}
+
+/// The time the program sees
+fn cluster_time(ctx: &Context) -> Result<i64> {
+    let account = ctx.rpc.get_account(&sysvar::clock::ID)?;
+    let clock: Clock = from_account(&account).expect("Deserializes the clock sysvar");
+    Ok(clock.unix_timestamp)
+}
+
+fn print_schedule(args: &instruction::InitializeArgs, decimals: u8) -> Result<()> {
+    let rounds = schedule(args)?;
+
+    println!("Selling {} tokens until {}", ui_amount(args.amount_to_sell.into(), decimals), format_date(args.end_at));
+    println!("{:<8} {:<22} {:<22} {:>24}", "Round", "Starts at", "Ends at", "SOL per token");
+    for round in rounds {
+        println!(
+            "{:<8} {:<22} {:<22} {:>24}",
+            format!("{:?}", round.round),
+            format_date(round.starts_at),
+            format_date(round.ends_at),
+            ui_price(round.token_price, args.price_units, decimals),
+        );
+    }
+    Ok(())
+}
+
+fn status(ctx: &Context) -> Result<()> {
+    let pool_address = ctx.pool();
+    let pool = fetch_pool(&ctx.rpc, &pool_address)?;
+    let vault_selling = ctx.rpc.get_token_account_balance(&pool.vault_selling)?;
+    let pool_lamports = ctx.rpc.get_balance(&pool_address)?;
+
+    println!("Pool:              {}", pool_address);
+    println!("Admin:             {}", pool.admin);
+    println!("Treasury:          {}", pool.treasury);
+    println!("Operator:          {}", pool.operator);
+    println!("Paused:            {}", pool.paused);
+    println!("Current round:     {:?}", pool.current_round);
+    println!("Round started at:  {}", pool.round_start_at);
+    println!("Round ends at:     {}", pool.round_start_at + pool.round_duration() as i64);
+    println!("Sale ends at:      {}", pool.end_at);
+    println!("Token price:       {} lamports for {} minimal parts", pool.token_price, pool.price_units.max(1));
+    match pool.try_next_token_price() {
+        Some(price) => println!("Next token price:  {} lamports", price),
+        None => println!("Next token price:  overflows"),
+    }
+    println!("Total supply:      {}", u64::from(pool.total_supply));
+    println!("Tokens sold:       {}", u64::from(pool.tokens_sold));
+    println!("Vault selling:     {} ({})", vault_selling.amount, vault_selling.ui_amount_string);
+    println!("Pool lamports:     {}", pool_lamports);
+    println!("Open orders:       {}", pool.open_orders);
+    println!("Unsold tokens:     {:?}", pool.unsold_tokens_policy);
+    if pool.mint_on_demand {
+        match pool.max_supply {
+            Some(max_supply) => println!("Minting:           up to {} tokens", u64::from(max_supply)),
+            None => println!("Minting:           not capped"),
+        }
+    }
+    if let Some(stake_mint) = pool.stake_mint {
+        println!("Stake mint:        {}", stake_mint);
+        for tier in pool.tiers.iter().filter(|tier| tier.allocation != Tokens::new(0)) {
+            let (min_stake, allocation) = (u64::from(tier.min_stake), u64::from(tier.allocation));
+            println!("Tier:              {} tokens for a stake of {}", allocation, min_stake);
+        }
+    }
+    if pool.lottery != Pubkey::default() {
+        println!("Lottery:           {}", pool.lottery);
+    }
+    if pool.auction_tokens != Tokens::new(0) || pool.open_bids > 0 {
+        println!("Auction tokens:    {}", u64::from(pool.auction_tokens));
+        println!("Open bids:         {}", pool.open_bids);
+    }
+    if pool.max_tokens_per_slot != Tokens::new(0) {
+        println!("Tokens per slot:   {}", u64::from(pool.max_tokens_per_slot));
+    }
+    if pool.buy_cooldown > 0 {
+        println!("Buy cooldown:      {} seconds", pool.buy_cooldown);
+    }
+    if pool.reject_cpi {
+        match pool.trusted_caller {
+            Some(program) => println!("Callers allowed:   the transaction and {}", program),
+            None => println!("Callers allowed:   the transaction only"),
+        }
+    }
+    Ok(())
+}
+
+fn switch(ctx: &Context) -> Result<()> {
+    let pool = fetch_pool(&ctx.rpc, &ctx.pool())?;
+    let instruction = match pool.current_round {
+        Round::Buying => instruction::switch_to_trading(&ctx.mint, ctx.sale_id, &ctx.signer_key()),
+        Round::Trading => instruction::switch_to_buying(&ctx.mint, ctx.sale_id, &ctx.signer_key()),
+        Round::Commit => instruction::switch_to_reveal(&ctx.mint, ctx.sale_id),
+        Round::Reveal => instruction::switch_to_trading(&ctx.mint, ctx.sale_id, &ctx.signer_key()),
+    };
+    send(ctx, &[instruction])
+}
+
+fn withdraw_lamports(ctx: &Context) -> Result<()> {
+    let pool = fetch_pool(&ctx.rpc, &ctx.pool())?;
+    send(ctx, &[instruction::withdraw_lamports(&ctx.mint, ctx.sale_id, &ctx.signer_key(), &pool.treasury)])
+}
+
+fn withdraw_unsold_tokens(ctx: &Context, amount: u64, destination: Option<Pubkey>) -> Result<()> {
+    let (mut instructions, destination) = match destination {
+        Some(destination) => (vec![], destination),
+        None => (create_signer_token_account(ctx)?, ctx.signer_token_account()),
+    };
+    instructions.push(instruction::withdraw_unsold_tokens(
+        &ctx.mint,
+        ctx.sale_id,
+        &ctx.token_program,
+        &ctx.signer_key(),
+        &destination,
+        Tokens::new(amount),
+    ));
+    send(ctx, &instructions)
+}
+
+fn enable_minting(ctx: &Context, max_supply: Option<u64>, revoke_mint_authority: bool) -> Result<()> {
+    let signer = ctx.signer_key();
+    let max_supply = max_supply.map(Tokens::new);
+    let instruction = instruction::enable_minting(
+        &ctx.mint,
+        ctx.sale_id,
+        &ctx.token_program,
+        &signer,
+        &signer,
+        max_supply,
+        revoke_mint_authority,
+    );
+    send(ctx, &[instruction])
+}
+
+fn protect(
+    ctx: &Context,
+    max_tokens_per_slot: u64,
+    cooldown: u32,
+    reject_cpi: bool,
+    trusted_caller: Option<Pubkey>,
+) -> Result<()> {
+    let instruction = instruction::set_buy_protection(
+        &ctx.mint,
+        ctx.sale_id,
+        &ctx.signer_key(),
+        Tokens::new(max_tokens_per_slot),
+        cooldown,
+        reject_cpi,
+        trusted_caller,
+    );
+    send(ctx, &[instruction])
+}
+
+fn set_tiers(ctx: &Context, stake_mint: &Pubkey, tiers: &[String]) -> Result<()> {
+    let tiers = tiers.iter().map(|tier| parse_tier(tier)).collect::<Result<Vec<_>>>()?;
+    send(ctx, &[instruction::set_tiers(&ctx.mint, ctx.sale_id, &ctx.signer_key(), stake_mint, tiers)])
+}
+
+/// Parses `MIN_STAKE:ALLOCATION`
+pub fn parse_tier(tier: &str) -> Result<Tier> {
+    let invalid = || Error::InvalidConfig(format!("the tier {} is not MIN_STAKE:ALLOCATION", tier));
+    let (min_stake, allocation) = tier.split_once(':').ok_or_else(invalid)?;
+    Ok(Tier {
+        min_stake: Tokens::new(min_stake.parse().map_err(|_| invalid())?),
+        allocation: Tokens::new(allocation.parse().map_err(|_| invalid())?),
+    })
+}
+
+fn start_lottery(ctx: &Context, registration_ends_at: &str, winners: u32, allocation: u64, secret: &str) -> Result<()> {
+    let instruction = instruction::start_lottery(
+        &ctx.mint,
+        ctx.sale_id,
+        &ctx.signer_key(),
+        timestamp_from_date(registration_ends_at)?,
+        winners,
+        Tokens::new(allocation),
+        secret_hash(&lottery_secret(secret)?),
+    );
+    send(ctx, &[instruction])
+}
+
+fn draw_lottery(ctx: &Context, secret: &str) -> Result<()> {
+    let secret = lottery_secret(secret)?;
+    send(ctx, &[instruction::draw_lottery(&ctx.mint, ctx.sale_id, &ctx.signer_key(), secret)])
+}
+
+fn lottery_secret(secret: &str) -> Result<[u8; 32]> {
+    Ok(parse_secret(secret).ok_or_else(|| Error::InvalidConfig("the secret is not 64 hex digits".to_owned()))?)
+}
+
+fn lottery_status(ctx: &Context) -> Result<()> {
+    let lottery = fetch_lottery(&ctx.rpc, &pda::lottery_address(&ctx.mint, ctx.sale_id))?;
+
+    println!("Registration ends: {}", format_date(lottery.registration_ends_at));
+    println!("Tickets:           {}", lottery.tickets);
+    println!("Winners:           {}", lottery.winners);
+    println!("Allocation:        {}", u64::from(lottery.allocation));
+    println!("Secret hash:       {}", format_hash(&lottery.secret_hash));
+    if let Some(draw_slot) = lottery.draw_slot {
+        println!("Draw slot:         {}", draw_slot);
+    }
+    if lottery.abandoned {
+        println!("Abandoned:         everyone buys");
+    }
+    if lottery.drawn {
+        println!("Slot hash:         {}", format_hash(&lottery.slot_hash));
+        println!("Seed:              {}", format_hash(&lottery.seed));
+    }
+    match fetch_ticket(&ctx.rpc, &pda::ticket_address(&ctx.mint, ctx.sale_id, &ctx.signer_key())) {
+        Ok(ticket) if lottery.drawn => {
+            let result = if lottery.is_winner(ticket.number) { "won" } else { "lost" };
+            println!("Your ticket:       {} ({})", ticket.number, result);
+        }
+        Ok(ticket) => println!("Your ticket:       {}", ticket.number),
+        Err(Error::AccountNotFound(_)) => {}
+        Err(err) => return Err(err.into()),
+    }
+    Ok(())
+}
+
+fn start_auction(ctx: &Context, commit_duration: u32, reveal_duration: u32, tokens: u64) -> Result<()> {
+    let instruction = instruction::start_auction(
+        &ctx.mint,
+        ctx.sale_id,
+        &ctx.token_program,
+        &ctx.signer_key(),
+        commit_duration,
+        reveal_duration,
+        Tokens::new(tokens),
+    );
+    send(ctx, &[instruction])
+}
+
+fn commit_bid(ctx: &Context, tokens: u64, price: u64, deposit: &str, salt: &str) -> Result<()> {
+    let signer = ctx.signer_key();
+    let commitment = bid_commitment(&signer, Tokens::new(tokens), price, &bid_salt(salt)?);
+    let deposit = lamports_from_sol(deposit)?;
+    send(ctx, &[instruction::commit_bid(&ctx.mint, ctx.sale_id, &signer, commitment, deposit)])
+}
+
+fn reveal_bid(ctx: &Context, tokens: u64, price: u64, salt: &str) -> Result<()> {
+    let salt = bid_salt(salt)?;
+    send(ctx, &[instruction::reveal_bid(&ctx.mint, ctx.sale_id, &ctx.signer_key(), Tokens::new(tokens), price, salt)])
+}
+
+fn bid_salt(salt: &str) -> Result<[u8; 32]> {
+    Ok(parse_secret(salt).ok_or_else(|| Error::InvalidConfig("the salt is not 64 hex digits".to_owned()))?)
+}
+
+fn settle_bid(ctx: &Context, bidder: Option<Pubkey>) -> Result<()> {
+    let (mut instructions, bidder) = match bidder {
+        Some(bidder) => (vec![], bidder),
+        None => (create_signer_token_account(ctx)?, ctx.signer_key()),
+    };
+    let bidder_token_account = ctx.token_account(&bidder);
+    let token_program = &ctx.token_program;
+    instructions.push(instruction::settle_bid(&ctx.mint, ctx.sale_id, token_program, &bidder, &bidder_token_account));
+    send(ctx, &instructions)
+}
+
+fn auction_status(ctx: &Context) -> Result<()> {
+    let auction = fetch_auction(&ctx.rpc, &pda::auction_address(&ctx.mint, ctx.sale_id))?;
+    let fills = fills(&auction);
+
+    println!("Tokens:            {}", u64::from(auction.tokens));
+    println!("Ranked bids:       {}", auction.bids.len());
+    if let Some(price) = clearing_price(&auction) {
+        println!("Clearing price:    {} lamports", price);
+    }
+    println!("{:<44} {:>20} {:>20} {:>20}", "Bid", "Price", "Tokens", "Filled");
+    for ranked in &auction.bids {
+        let filled = fills.iter().find(|(bid, _)| *bid == ranked.bid).map(|(_, tokens)| u64::from(*tokens));
+        println!(
+            "{:<44} {:>20} {:>20} {:>20}",
+            ranked.bid.to_string(),
+            ranked.price,
+            u64::from(ranked.tokens),
+            filled.unwrap_or_default(),
+        );
+    }
+    match fetch_bid(&ctx.rpc, &pda::bid_address(&ctx.mint, ctx.sale_id, &ctx.signer_key())) {
+        Ok(bid) if bid.revealed => {
+            let (tokens, deposit) = (u64::from(bid.tokens), u64::from(bid.deposit));
+            println!("Your bid:          {} tokens at {} lamports, {} lamports deposited", tokens, bid.price, deposit);
+        }
+        Ok(bid) => println!("Your bid:          not revealed, {} lamports deposited", u64::from(bid.deposit)),
+        Err(Error::AccountNotFound(_)) => {}
+        Err(err) => return Err(err.into()),
+    }
+    Ok(())
+}
+
+fn stake(ctx: &Context, amount: u64) -> Result<()> {
+    let pool = fetch_pool(&ctx.rpc, &ctx.pool())?;
+    let stake_mint = pool.stake_mint.ok_or_else(|| Error::InvalidConfig("the sale is not tiered".to_owned()))?;
+    let signer = ctx.signer_key();
+    let staker_tokens = get_associated_token_address(&signer, &stake_mint);
+    let instruction =
+        instruction::stake(&ctx.mint, ctx.sale_id, &signer, &staker_tokens, &stake_mint, Tokens::new(amount));
+    send(ctx, &[instruction])
+}
+
+fn unstake(ctx: &Context, stake_mint: Option<Pubkey>) -> Result<()> {
+    let stake_mint = match stake_mint {
+        Some(stake_mint) => stake_mint,
+        None => fetch_pool(&ctx.rpc, &ctx.pool())?
+            .stake_mint
+            .ok_or_else(|| Error::InvalidConfig("the sale is not tiered".to_owned()))?,
+    };
+    let signer = ctx.signer_key();
+    let staker_tokens = get_associated_token_address(&signer, &stake_mint);
+    send(ctx, &[instruction::unstake(&ctx.mint, ctx.sale_id, &signer, &staker_tokens, &stake_mint)])
+}
+
+fn terminate(ctx: &Context) -> Result<()> {
+    let pool = fetch_pool(&ctx.rpc, &ctx.pool())?;
+    let receiver = pool.unsold_tokens_receiver(&ctx.token_program);
+    let terminate = instruction::terminate(
+        &ctx.mint,
+        ctx.sale_id,
+        &ctx.token_program,
+        &ctx.signer_key(),
+        receiver,
+        pool.last_registry_page,
+    );
+    send(ctx, &[terminate])
+}
+
+/// The orders passed to one `migrate_pool` so the transaction fits into a packet
+const ORDERS_PER_MIGRATION: usize = 20;
+
+fn migrate(ctx: &Context) -> Result<()> {
+    let orders = fetch_outdated_orders(&ctx.rpc, &ctx.mint, ctx.sale_id)?;
+    println!("Migrating the pool and {} orders", orders.len());
+    let migrate_pool = |registry_page, orders| {
+        instruction::migrate_pool(&ctx.mint, ctx.sale_id, &ctx.token_program, &ctx.signer_key(), registry_page, orders)
+    };
+    send(ctx, &[migrate_pool(0, &[])])?;
+    for orders in orders.chunks(ORDERS_PER_MIGRATION) {
+        let registry_page = registry_page_with_room(&ctx.rpc, &ctx.mint, ctx.sale_id, orders.len())?;
+        send(ctx, &[migrate_pool(registry_page, orders)])?;
+    }
+    Ok(())
+}
+
+fn list_orders(ctx: &Context) -> Result<()> {
+    let pool = fetch_pool(&ctx.rpc, &ctx.pool())?;
+    let orders = fetch_active_orders(&ctx.rpc, &ctx.mint, ctx.sale_id)?;
+    println!("{:<44} {:<44} {:>20} {:>20}", "Order", "Seller", "Tokens", "Asked price");
+    for (address, order) in orders {
+        println!(
+            "{:<44} {:<44} {:>20} {:>20}",
+            address.to_string(),
+            order.owner.to_string(),
+            u64::from(order.token_amount),
+            order.token_price,
+        );
+    }
+    println!(
+        "Orders are filled at the pool price of {} lamports for {} minimal parts",
+        pool.token_price,
+        pool.price_units.max(1)
+    );
+    Ok(())
+}
+
+fn place_order(ctx: &Context, amount: u64, price: u64) -> Result<()> {
+    let seller = ctx.signer_key();
+    let token_account = ctx.signer_token_account();
+    let registry_page = registry_page_with_room(&ctx.rpc, &ctx.mint, ctx.sale_id, 1)?;
+    let place_order = instruction::place_order(
+        &ctx.mint,
+        ctx.sale_id,
+        &ctx.token_program,
+        &seller,
+        &token_account,
+        instruction::PlaceOrderArgs { amount_to_sell: Tokens::new(amount), price_for_token: price, registry_page },
+    );
+    send(ctx, &[place_order])?;
+    println!("Order: {}", pda::order_address(&ctx.mint, ctx.sale_id, &seller));
+    Ok(())
+}
+
+fn fill_order(ctx: &Context, seller: &Pubkey, amount: u64) -> Result<()> {
+    let pool = fetch_pool(&ctx.rpc, &ctx.pool())?;
+    if let Some(lamports) = quote::quote_redeem(&pool, Tokens::new(amount)) {
+        println!("Paying {} lamports", u64::from(lamports));
+    }
+
+    // Fails if the price changes after the quote
+    let args = instruction::RedeemOrderArgs {
+        tokens_amount: Tokens::new(amount),
+        max_price_per_token: pool.token_price,
+        expected_round: Some(pool.round_number),
+    };
+    let signer = ctx.signer_key();
+    let signer_token_account = ctx.signer_token_account();
+    let redeem_order = order_instruction(ctx, seller, |registry_page| {
+        instruction::redeem_order(
+            &ctx.mint,
+            ctx.sale_id,
+            &ctx.token_program,
+            seller,
+            registry_page,
+            &signer,
+            &signer_token_account,
+            args,
+        )
+    })?;
+    let mut instructions = create_signer_token_account(ctx)?;
+    instructions.push(redeem_order);
+    send(ctx, &instructions)
+}
+
+fn close_order(ctx: &Context) -> Result<()> {
+    let owner = ctx.signer_key();
+    let owner_token_account = ctx.token_account(&owner);
+    let close_order = order_instruction(ctx, &owner, |registry_page| {
+        let token_program = &ctx.token_program;
+        instruction::close_order(&ctx.mint, ctx.sale_id, token_program, &owner, registry_page, &owner_token_account)
+    })?;
+    send(ctx, &[close_order])
+}
+
+fn reclaim_order(ctx: &Context, seller: &Pubkey) -> Result<()> {
+    let reclaim_order = order_instruction(ctx, seller, |registry_page| {
+        instruction::reclaim_order(&ctx.mint, ctx.sale_id, &ctx.token_program, seller, registry_page, &ctx.signer_key())
+    })?;
+    send(ctx, &[reclaim_order])
+}
+
+/// Builds the instruction for the page of the order registry listing the order, fails early if the seller
+/// has no order in the pool. The orders of the first sale of the mint placed before the pool was in the seeds
+/// are still at their former address, the instruction is pointed to them.
+fn order_instruction(
+    ctx: &Context,
+    seller: &Pubkey,
+    build: impl FnOnce(u32) -> Instruction,
+) -> Result<Instruction> {
+    let order = fetch_order(&ctx.rpc, &pda::order_address(&ctx.mint, ctx.sale_id, seller));
+    if order.is_err() {
+        let legacy_order = fetch_order(&ctx.rpc, &pda::legacy_order_address(seller));
+        if let Ok(legacy_order) = legacy_order {
+            if legacy_order.pool == ctx.pool() {
+                let order_instruction = build(legacy_order.registry_page);
+                return Ok(instruction::for_legacy_order(order_instruction, &ctx.mint, ctx.sale_id, seller));
+            }
+        }
+    }
+    Ok(build(order?.registry_page))
+}
+
+fn buy(ctx: &Context, amount: u64) -> Result<()> {
+    let pool = fetch_pool(&ctx.rpc, &ctx.pool())?;
+    let lamports = quote::quote_buy(&pool, Tokens::new(amount))
+        .ok_or_else(|| Error::Overflow("the price of the tokens".to_owned()))?;
+    println!("Paying {} lamports", u64::from(lamports));
+
+    // Fails if the price changes after the quote
+    let args = instruction::BuyArgs {
+        amount_to_buy: Tokens::new(amount),
+        max_total_lamports: lamports,
+        expected_round: Some(pool.round_number),
+    };
+    let mut instructions = create_signer_token_account(ctx)?;
+    instructions.push(instruction::buy(
+        &ctx.mint,
+        ctx.sale_id,
+        &ctx.token_program,
+        &ctx.signer_key(),
+        &ctx.signer_token_account(),
+        args,
+    ));
+    send(ctx, &instructions)
+}
+
+fn spend(ctx: &Context, sol: &str) -> Result<()> {
+    let max_lamports = lamports_from_sol(sol)?;
+    let pool = fetch_pool(&ctx.rpc, &ctx.pool())?;
+    if let Some(tokens) = quote::tokens_for_lamports(&pool, max_lamports) {
+        println!("Buying up to {} tokens", u64::from(tokens));
+    }
+
+    let mut instructions = create_signer_token_account(ctx)?;
+    instructions.push(instruction::buy_with_lamports(
+        &ctx.mint,
+        ctx.sale_id,
+        &ctx.token_program,
+        &ctx.signer_key(),
+        &ctx.signer_token_account(),
+        max_lamports,
+    ));
+    send(ctx, &instructions)
+}
+
+fn simulate(config: &Path, decimals: u8, tokens_per_round: Option<&str>, budget_per_round: Option<&str>) -> Result<()> {
+    let args = SaleConfig::load(config)?.initialize_args(decimals)?;
+    let simulation = match (tokens_per_round, budget_per_round) {
+        (Some(tokens), _) => simulate_sale(&args, &mut FixedDemand(tokens_from_ui(tokens, decimals)?))?,
+        (None, Some(budget)) => simulate_sale(&args, &mut BudgetDemand(lamports_from_sol(budget)?))?,
+        (None, None) => unreachable!("clap requires one of the demands"),
+    };
+
+    println!("{:<6} {:<22} {:<22} {:>18} {:>18} {:>18}", "Round", "Starts at", "Ends at", "SOL per token", "Tokens sold", "Proceeds, SOL");
+    for (index, round) in simulation.rounds.iter().enumerate() {
+        println!(
+            "{:<6} {:<22} {:<22} {:>18} {:>18} {:>18}",
+            index,
+            format_date(round.starts_at),
+            format_date(round.ends_at),
+            ui_price(round.token_price, args.price_units, decimals),
+            ui_amount(round.tokens_sold.into(), decimals),
+            ui_amount(round.proceeds.into(), 9),
+        );
+    }
+    println!("Tokens sold:   {}", ui_amount(simulation.tokens_sold.into(), decimals));
+    println!("Unsold tokens: {}", ui_amount(simulation.unsold_tokens.into(), decimals));
+    println!("Proceeds:      {} SOL", ui_amount(simulation.proceeds.into(), 9));
+    match simulation.sold_out_in_round {
+        Some(round) => println!("Sold out in the buying round {}", round),
+        None => println!("Not sold out"),
+    }
+    println!("The sale ends at {} during a {:?} round", format_date(args.end_at), simulation.ended_during);
+    Ok(())
+}
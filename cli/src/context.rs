@@ -0,0 +1,68 @@
+use crate::error::{Error, Result};
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use std::path::Path;
+use token_sale_client::account::fetch_token_program;
+use token_sale_client::pda;
+
+/// The cluster and the keypair the commands are run with.
+/// The keypair pays for the transactions and signs them in whatever role the command requires.
+pub struct Context {
+    pub rpc: RpcClient,
+    pub signer: Keypair,
+    /// The mint of the selling tokens
+    pub mint: Pubkey,
+    /// Tells apart the sales of the mint
+    pub sale_id: u64,
+    /// The program of the mint, SPL Token or Token-2022
+    pub token_program: Pubkey,
+}
+
+impl Context {
+    pub fn new(url: &str, keypair: &Path, mint: Option<Pubkey>, sale_id: u64) -> Result<Self> {
+        let mint = mint.ok_or(Error::MissingMint)?;
+        let signer = read_keypair_file(keypair).map_err(|err| Error::ReadKeypair(keypair.to_owned(), err.to_string()))?;
+        let rpc = RpcClient::new_with_commitment(url.to_owned(), CommitmentConfig::confirmed());
+        let token_program = fetch_token_program(&rpc, &mint)?;
+        Ok(Context { rpc, signer, mint, sale_id, token_program })
+    }
+
+    /// The pool of the sale
+    pub fn pool(&self) -> Pubkey {
+        pda::pool_address(&self.mint, self.sale_id)
+    }
+
+    pub fn signer_key(&self) -> Pubkey {
+        self.signer.pubkey()
+    }
+
+    /// The associated token account of the signer for the selling tokens
+    pub fn signer_token_account(&self) -> Pubkey {
+        self.token_account(&self.signer.pubkey())
+    }
+
+    /// The associated token account of the owner for the selling tokens
+    pub fn token_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.mint, &self.token_program)
+    }
+
+    pub fn send(&self, instructions: &[Instruction]) -> Result<Signature> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.signer.pubkey()),
+            &[&self.signer],
+            blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&tx)?)
+    }
+
+    pub fn account_exists(&self, address: &Pubkey) -> Result<bool> {
+        Ok(self.rpc.get_account_with_commitment(address, self.rpc.commitment())?.value.is_some())
+    }
+}
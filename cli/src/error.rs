@@ -0,0 +1,19 @@
+use std::path::PathBuf;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Cannot read the keypair from {0}: {1}")]
+    ReadKeypair(PathBuf, String),
+    #[error("The mint of the sale is required, pass it with --mint")]
+    MissingMint,
+    #[error(transparent)]
+    Client(#[from] token_sale_client::Error),
+}
+
+impl From<solana_client::client_error::ClientError> for Error {
+    fn from(err: solana_client::client_error::ClientError) -> Self {
+        Error::Client(err.into())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,9 @@
+//! The command-line tool operating the token sale through the RPC of a cluster:
+//! `solana-test-validator` by default.
+
+pub mod args;
+pub mod command;
+pub mod context;
+pub mod error;
+
+pub use error::{Error, Result};
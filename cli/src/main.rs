@@ -0,0 +1,18 @@
+use clap::Parser;
+use token_sale_cli::args::{Args, Command};
+use token_sale_cli::command;
+use token_sale_cli::context::Context;
+
+fn main() {
+    let args = Args::parse();
+    let result = match &args.command {
+        Command::Simulate { .. } => command::run_offline(&args.command),
+        _ => Context::new(&args.url, &args.keypair_path(), args.mint, args.sale_id)
+            .and_then(|ctx| command::run(&ctx, &args.command)),
+    };
+
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
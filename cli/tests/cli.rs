@@ -0,0 +1,75 @@
+use clap::Parser;
+use token_sale_cli::args::{Args, Auction, Command, Lottery, Order, Withdraw};
+use token_sale_cli::command::parse_tier;
+use token_sale_client::{Tier, Tokens};
+
+const MINT: &str = "So11111111111111111111111111111111111111112";
+
+#[test]
+fn parses_the_commands() {
+    let args = Args::try_parse_from(["token-sale", "--mint", MINT, "-u", "http://rpc", "buy", "5"]).unwrap();
+    assert_eq!(args.url, "http://rpc");
+    assert_eq!(args.sale_id, 0);
+    assert!(matches!(args.command, Command::Buy { amount: 5 }));
+
+    let args = Args::try_parse_from(["token-sale", "-m", MINT, "spend", "1.5"]).unwrap();
+    assert!(matches!(args.command, Command::Spend { ref sol } if sol == "1.5"));
+
+    let args = Args::try_parse_from(["token-sale", "-m", MINT, "--sale-id", "2", "status"]).unwrap();
+    assert_eq!(args.sale_id, 2);
+
+    let args = Args::try_parse_from(["token-sale", "-m", MINT, "order", "fill", MINT, "3"]).unwrap();
+    assert!(matches!(args.command, Command::Order(Order::Fill { amount: 3, .. })));
+
+    let args = Args::try_parse_from(["token-sale", "-m", MINT, "withdraw", "unsold-tokens", "10"]).unwrap();
+    assert!(matches!(args.command, Command::Withdraw(Withdraw::UnsoldTokens { amount: 10, destination: None })));
+
+    let args = Args::try_parse_from(["token-sale", "-m", MINT, "enable-minting", "--max-supply", "500"]).unwrap();
+    assert!(matches!(args.command, Command::EnableMinting { max_supply: Some(500), revoke_mint_authority: false }));
+
+    let args = Args::try_parse_from(["token-sale", "-m", MINT, "protect", "--cooldown", "30", "--reject-cpi"]).unwrap();
+    assert!(matches!(
+        args.command,
+        Command::Protect { max_tokens_per_slot: 0, cooldown: 30, reject_cpi: true, trusted_caller: None }
+    ));
+    assert!(Args::try_parse_from(["token-sale", "-m", MINT, "protect", "--trusted-caller", MINT]).is_err());
+
+    let tiers = ["token-sale", "-m", MINT, "tiers", "--stake-mint", MINT, "100:10", "500:60"];
+    let args = Args::try_parse_from(tiers).unwrap();
+    assert!(matches!(args.command, Command::Tiers { ref tiers, .. } if tiers.len() == 2));
+    assert!(Args::try_parse_from(["token-sale", "-m", MINT, "tiers", "--stake-mint", MINT]).is_err());
+
+    let secret = "00".repeat(32);
+    let mut start = vec!["token-sale", "-m", MINT, "lottery", "start", "--registration-ends-at", "2030-01-01T00:00:00Z"];
+    start.extend(["--winners", "2", "--allocation", "10", "--secret", &secret]);
+    let args = Args::try_parse_from(start).unwrap();
+    assert!(matches!(args.command, Command::Lottery(Lottery::Start { winners: 2, allocation: 10, .. })));
+    assert!(Args::try_parse_from(["token-sale", "-m", MINT, "lottery", "draw"]).is_err());
+
+    let mut bid = vec!["token-sale", "-m", MINT, "auction", "bid", "--tokens", "10", "--price", "1000"];
+    bid.extend(["--deposit", "0.5", "--salt", &secret]);
+    let args = Args::try_parse_from(bid).unwrap();
+    assert!(matches!(args.command, Command::Auction(Auction::Bid { tokens: 10, price: 1_000, .. })));
+    let args = Args::try_parse_from(["token-sale", "-m", MINT, "auction", "settle"]).unwrap();
+    assert!(matches!(args.command, Command::Auction(Auction::Settle { bidder: None })));
+
+    let args = Args::try_parse_from(["token-sale", "-m", MINT, "init", "--config", "sale.toml", "--dry-run"]).unwrap();
+    assert!(matches!(args.command, Command::Init { dry_run: true, tokens_account: None, .. }));
+
+    let args = Args::try_parse_from(["token-sale", "simulate", "--config", "sale.toml", "--decimals", "6", "--budget-per-round", "10"]).unwrap();
+    assert!(args.mint.is_none());
+    assert!(matches!(args.command, Command::Simulate { decimals: 6, tokens_per_round: None, .. }));
+    assert!(Args::try_parse_from(["token-sale", "simulate", "--config", "sale.toml", "--decimals", "6"]).is_err());
+
+    assert!(Args::try_parse_from(["token-sale", "-m", "not a key", "status"]).is_err());
+}
+
+#[test]
+fn parses_the_tiers() {
+    let tier = parse_tier("100:10").unwrap();
+    assert_eq!(tier, Tier { min_stake: Tokens::new(100), allocation: Tokens::new(10) });
+
+    for tier in ["100", "100:", "a:10", "100:10:1"] {
+        assert!(parse_tier(tier).is_err(), "{}", tier);
+    }
+}
@@ -0,0 +1,209 @@
+#![cfg(feature = "test-bpf")]
+//! Runs the commands against the program in program-test,
+//! the RPC client of the command context is answered from the bank of the test.
+//! Like the tests of the program, they need its BPF build: `cargo test-bpf`.
+
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use solana_account_decoder::parse_token::token_amount_to_ui_amount;
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::client_error::{ClientError, Result as ClientResult};
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_client::rpc_request::{RpcError, RpcRequest};
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account;
+use solana_sdk::clock::Clock;
+use solana_sdk::program_option::COption;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+use std::path::PathBuf;
+use std::str::FromStr;
+use tokio::runtime::Runtime;
+use tokio::sync::Mutex;
+use token_sale_cli::args::Command;
+use token_sale_cli::command;
+use token_sale_cli::context::Context;
+use token_sale_client::account::fetch_pool;
+use token_sale_client::config::{timestamp_from_date, SaleConfig};
+use token_sale_client::{Round, Tokens};
+
+/// The time of the cluster, before the first round of `sale.example.toml`
+const START: i64 = 1_650_000_000;
+const DECIMALS: u8 = 6;
+/// The whole tokens sold by `sale.example.toml`
+const TOKENS_FOR_SALE: u64 = 1_000_000 * 1_000_000;
+
+/// Answers the requests the commands send from the bank of program-test.
+/// The bank is served by the tasks of `runtime` while the RPC client awaits the answers in its own runtime.
+struct BanksSender {
+    ctx: Mutex<ProgramTestContext>,
+    _runtime: Runtime,
+}
+
+impl BanksSender {
+    async fn account(&self, address: &Value) -> ClientResult<Option<Account>> {
+        let address = pubkey(address)?;
+        let mut ctx = self.ctx.lock().await;
+        ctx.banks_client.get_account(address).await.map_err(for_user)
+    }
+
+    async fn ui_account(&self, address: &Value) -> ClientResult<Value> {
+        let account = self.account(address).await?;
+        let account = account.map(|account| {
+            UiAccount::encode(&pubkey(address).unwrap(), &account, UiAccountEncoding::Base64, None, None)
+        });
+        Ok(json!(account))
+    }
+
+    async fn process(&self, transaction: &Value) -> ClientResult<Value> {
+        let data = base64::decode(transaction.as_str().unwrap_or_default()).map_err(for_user)?;
+        let transaction: Transaction = bincode::deserialize(&data).map_err(for_user)?;
+        let signature = transaction.signatures[0];
+        let mut ctx = self.ctx.lock().await;
+        match ctx.banks_client.process_transaction(transaction).await {
+            Ok(()) => Ok(json!(signature.to_string())),
+            Err(BanksClientError::TransactionError(err)) => Err(err.into()),
+            Err(err) => Err(for_user(err)),
+        }
+    }
+}
+
+#[async_trait]
+impl RpcSender for BanksSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let value = match request {
+            RpcRequest::GetVersion => return Ok(json!({ "solana-core": "1.17.3" })),
+            RpcRequest::SendTransaction => return self.process(&params[0]).await,
+            RpcRequest::GetLatestBlockhash => {
+                let mut ctx = self.ctx.lock().await;
+                let blockhash = ctx.banks_client.get_latest_blockhash().await.map_err(for_user)?;
+                json!({ "blockhash": blockhash.to_string(), "lastValidBlockHeight": u64::MAX })
+            }
+            RpcRequest::IsBlockhashValid => json!(true),
+            // The transactions are processed as soon as they are sent
+            RpcRequest::GetSignatureStatuses => {
+                let signatures = params[0].as_array().map(Vec::len).unwrap_or_default();
+                let status = json!({
+                    "slot": 0,
+                    "confirmations": null,
+                    "status": { "Ok": null },
+                    "err": null,
+                    "confirmationStatus": "finalized",
+                });
+                json!(vec![status; signatures])
+            }
+            RpcRequest::GetAccountInfo => self.ui_account(&params[0]).await?,
+            RpcRequest::GetMultipleAccounts => {
+                let mut accounts = vec![];
+                for address in params[0].as_array().cloned().unwrap_or_default() {
+                    accounts.push(self.ui_account(&address).await?);
+                }
+                json!(accounts)
+            }
+            RpcRequest::GetBalance => {
+                json!(self.account(&params[0]).await?.map(|account| account.lamports).unwrap_or_default())
+            }
+            RpcRequest::GetTokenAccountBalance => {
+                let account = self.account(&params[0]).await?.ok_or_else(|| for_user("no token account"))?;
+                let token_account = TokenAccount::unpack(&account.data).map_err(for_user)?;
+                let mint = json!(token_account.mint.to_string());
+                let mint = self.account(&mint).await?.ok_or_else(|| for_user("no mint"))?;
+                let decimals = Mint::unpack(&mint.data).map_err(for_user)?.decimals;
+                json!(token_amount_to_ui_amount(token_account.amount, decimals))
+            }
+            request => return Err(for_user(format!("{} is not answered by the test", request))),
+        };
+        Ok(json!({ "context": { "slot": 0 }, "value": value }))
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "program-test".to_owned()
+    }
+}
+
+fn pubkey(address: &Value) -> ClientResult<Pubkey> {
+    Pubkey::from_str(address.as_str().unwrap_or_default()).map_err(for_user)
+}
+
+fn for_user(err: impl ToString) -> ClientError {
+    RpcError::ForUser(err.to_string()).into()
+}
+
+fn packed<T: Pack>(state: T, owner: &Pubkey) -> Account {
+    let mut data = vec![0; T::LEN];
+    state.pack_into_slice(&mut data);
+    Account { lamports: 1_000_000_000, data, owner: *owner, executable: false, rent_epoch: 0 }
+}
+
+/// Anchor ties the lifetime of the accounts to the one of their slice, which the processors of program-test do not
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    token_sale::entry(program_id, Box::leak(Box::new(accounts.to_vec())), data)
+}
+
+/// The signer holds the tokens for sale in its associated token account
+fn cluster(signer: &Keypair, mint: &Pubkey) -> RpcClient {
+    let mut program_test = ProgramTest::new("token_sale", token_sale::id(), processor!(process_instruction));
+    let signer_key = signer.pubkey();
+    program_test.add_account(signer_key, Account::new(10_000_000_000, 0, &solana_sdk::system_program::id()));
+    let mint_state = Mint {
+        mint_authority: COption::Some(signer_key),
+        supply: TOKENS_FOR_SALE,
+        decimals: DECIMALS,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    program_test.add_account(*mint, packed(mint_state, &spl_token::id()));
+    let token_account = TokenAccount {
+        mint: *mint,
+        owner: signer_key,
+        amount: TOKENS_FOR_SALE,
+        state: AccountState::Initialized,
+        ..TokenAccount::default()
+    };
+    let token_account_address = anchor_spl::associated_token::get_associated_token_address(&signer_key, mint);
+    program_test.add_account(token_account_address, packed(token_account, &spl_token::id()));
+
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+    let mut ctx = runtime.block_on(program_test.start_with_context());
+    let mut clock: Clock = runtime.block_on(ctx.banks_client.get_sysvar()).unwrap();
+    clock.slot += 2;
+    ctx.warp_to_slot(clock.slot).unwrap();
+    clock.unix_timestamp = START;
+    ctx.set_sysvar(&clock);
+
+    RpcClient::new_sender(BanksSender { ctx: Mutex::new(ctx), _runtime: runtime }, RpcClientConfig::default())
+}
+
+#[test]
+fn initializes_the_sale_from_the_config() {
+    let signer = Keypair::new();
+    let mint = Pubkey::new_unique();
+    let rpc = cluster(&signer, &mint);
+    let ctx = Context { rpc, signer, mint, sale_id: 0, token_program: spl_token::ID };
+    let config = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("sale.example.toml");
+
+    command::run(&ctx, &Command::Init { config: config.clone(), tokens_account: None, dry_run: false }).unwrap();
+    command::run(&ctx, &Command::Status).unwrap();
+
+    let sale = SaleConfig::load(&config).unwrap();
+    let pool = fetch_pool(&ctx.rpc, &ctx.pool()).unwrap();
+    assert_eq!(pool.admin, ctx.signer_key());
+    assert_eq!(pool.selling_mint, mint);
+    assert_eq!(pool.current_round, Round::Buying);
+    assert_eq!(pool.round_start_at, timestamp_from_date(&sale.round_start_at).unwrap());
+    assert_eq!(pool.end_at, timestamp_from_date(&sale.end_at).unwrap());
+    assert_eq!(pool.total_supply, Tokens::new(TOKENS_FOR_SALE));
+    let vault_selling = ctx.rpc.get_token_account_balance(&pool.vault_selling).unwrap();
+    assert_eq!(vault_selling.amount, TOKENS_FOR_SALE.to_string());
+    let signer_tokens = ctx.rpc.get_token_account_balance(&ctx.signer_token_account()).unwrap();
+    assert_eq!(signer_tokens.amount, "0");
+}
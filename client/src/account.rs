@@ -0,0 +1,196 @@
+use crate::error::{Error, Result};
+use crate::pda;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::{token, token_2022};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use token_sale::account::{AuctionAccount, BidAccount, LotteryAccount, Order, OrderRegistry, PoolAccount, TicketAccount};
+
+/// The offsets of `Order::token_vault` and `Order::pool` in the account data, including the discriminator
+const ORDER_VAULT_OFFSET: usize = 8 + 1 + 8 + 32 + 8 + 8;
+const ORDER_POOL_OFFSET: usize = 8 + ORDER_V0_SPACE;
+/// The layout of the orders placed before they kept their pool
+const ORDER_V0_SPACE: usize = 1 + 8 + 32 + 8 + 8 + 32 + 1;
+
+pub fn deserialize_pool(data: &[u8]) -> Result<PoolAccount> {
+    Ok(PoolAccount::try_deserialize(&mut &data[..])?)
+}
+
+pub fn deserialize_order(data: &[u8]) -> Result<Order> {
+    Ok(Order::try_deserialize(&mut &data[..])?)
+}
+
+pub fn deserialize_order_registry(data: &[u8]) -> Result<OrderRegistry> {
+    Ok(OrderRegistry::try_deserialize(&mut &data[..])?)
+}
+
+pub fn deserialize_lottery(data: &[u8]) -> Result<LotteryAccount> {
+    Ok(LotteryAccount::try_deserialize(&mut &data[..])?)
+}
+
+pub fn deserialize_ticket(data: &[u8]) -> Result<TicketAccount> {
+    Ok(TicketAccount::try_deserialize(&mut &data[..])?)
+}
+
+pub fn deserialize_auction(data: &[u8]) -> Result<AuctionAccount> {
+    Ok(AuctionAccount::try_deserialize(&mut &data[..])?)
+}
+
+pub fn deserialize_bid(data: &[u8]) -> Result<BidAccount> {
+    Ok(BidAccount::try_deserialize(&mut &data[..])?)
+}
+
+pub fn fetch_pool(rpc: &RpcClient, pool: &Pubkey) -> Result<PoolAccount> {
+    deserialize_pool(&fetch_data(rpc, pool)?)
+}
+
+pub fn fetch_order(rpc: &RpcClient, order: &Pubkey) -> Result<Order> {
+    deserialize_order(&fetch_data(rpc, order)?)
+}
+
+pub fn fetch_order_registry(rpc: &RpcClient, registry: &Pubkey) -> Result<OrderRegistry> {
+    deserialize_order_registry(&fetch_data(rpc, registry)?)
+}
+
+pub fn fetch_lottery(rpc: &RpcClient, lottery: &Pubkey) -> Result<LotteryAccount> {
+    deserialize_lottery(&fetch_data(rpc, lottery)?)
+}
+
+pub fn fetch_ticket(rpc: &RpcClient, ticket: &Pubkey) -> Result<TicketAccount> {
+    deserialize_ticket(&fetch_data(rpc, ticket)?)
+}
+
+pub fn fetch_auction(rpc: &RpcClient, auction: &Pubkey) -> Result<AuctionAccount> {
+    deserialize_auction(&fetch_data(rpc, auction)?)
+}
+
+pub fn fetch_bid(rpc: &RpcClient, bid: &Pubkey) -> Result<BidAccount> {
+    deserialize_bid(&fetch_data(rpc, bid)?)
+}
+
+/// The token program of the mint, or of the token account, that the builders of the `instruction` module take
+pub fn fetch_token_program(rpc: &RpcClient, mint: &Pubkey) -> Result<Pubkey> {
+    let account = rpc
+        .get_account_with_commitment(mint, rpc.commitment())?
+        .value
+        .ok_or(Error::AccountNotFound(*mint))?;
+    match account.owner {
+        owner if owner == token::ID || owner == token_2022::ID => Ok(owner),
+        _ => Err(Error::NotTokenAccount(*mint)),
+    }
+}
+
+/// The pages of the order registry of the pool, from the first one to `PoolAccount::last_registry_page`
+pub fn fetch_order_registry_pages(rpc: &RpcClient, selling_mint: &Pubkey, sale_id: u64) -> Result<Vec<OrderRegistry>> {
+    let pool = fetch_pool(rpc, &pda::pool_address(selling_mint, sale_id))?;
+    let pages: Vec<Pubkey> = (0..=pool.last_registry_page)
+        .map(|page| pda::order_registry_page_address(selling_mint, sale_id, page))
+        .collect();
+    fetch_multiple(rpc, &pages)?
+        .into_iter()
+        .map(|(_, data)| deserialize_order_registry(&data))
+        .collect()
+}
+
+/// The page of the order registry `place_order` or `migrate_pool` could list `orders` more orders in:
+/// the first one with room for them, or the next one once they are all full
+pub fn registry_page_with_room(rpc: &RpcClient, selling_mint: &Pubkey, sale_id: u64, orders: usize) -> Result<u32> {
+    let pages = fetch_order_registry_pages(rpc, selling_mint, sale_id)?;
+    let page = pages
+        .iter()
+        .position(|page| page.orders.len() + orders <= OrderRegistry::MAX_ORDERS)
+        .unwrap_or(pages.len());
+    Ok(page as u32)
+}
+
+/// The orders of the pool that still have tokens to sell, as listed by the pages of its order registry
+pub fn fetch_active_orders(rpc: &RpcClient, selling_mint: &Pubkey, sale_id: u64) -> Result<Vec<(Pubkey, Order)>> {
+    let addresses: Vec<Pubkey> = fetch_order_registry_pages(rpc, selling_mint, sale_id)?
+        .into_iter()
+        .flat_map(|page| page.orders)
+        .collect();
+    fetch_multiple(rpc, &addresses)?
+        .into_iter()
+        .map(|(address, data)| Ok((address, deserialize_order(&data)?)))
+        .collect()
+}
+
+/// All the orders placed in the pool that are not closed yet, including the sold out ones.
+/// Scans every account of the program, prefer `fetch_active_orders` to list the market.
+pub fn fetch_orders(rpc: &RpcClient, pool: &Pubkey) -> Result<Vec<(Pubkey, Order)>> {
+    order_accounts(rpc, vec![pool_filter(pool), RpcFilterType::DataSize((8 + Order::SPACE) as u64)])?
+        .into_iter()
+        .map(|(address, data)| Ok((address, deserialize_order(&data)?)))
+        .collect()
+}
+
+/// The orders of the sale that `migrate_pool` has to bring to the current layout.
+/// The orders placed before they kept their pool belong to the first sale of the mint of their vault.
+pub fn fetch_outdated_orders(rpc: &RpcClient, selling_mint: &Pubkey, sale_id: u64) -> Result<Vec<Pubkey>> {
+    let mut orders: Vec<Pubkey> = order_accounts(rpc, vec![pool_filter(&pda::pool_address(selling_mint, sale_id))])?
+        .into_iter()
+        .filter(|(_, data)| data.len() < 8 + Order::SPACE)
+        .map(|(address, _)| address)
+        .collect();
+
+    if sale_id == 0 {
+        let legacy_orders = order_accounts(rpc, vec![RpcFilterType::DataSize((8 + ORDER_V0_SPACE) as u64)])?;
+        orders.extend(
+            legacy_orders
+                .into_iter()
+                .filter(|(address, data)| {
+                    let vault = get_associated_token_address(address, selling_mint);
+                    data[ORDER_VAULT_OFFSET..ORDER_VAULT_OFFSET + 32] == vault.to_bytes()
+                })
+                .map(|(address, _)| address),
+        );
+    }
+    Ok(orders)
+}
+
+fn pool_filter(pool: &Pubkey) -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(ORDER_POOL_OFFSET, pool.to_bytes().to_vec()))
+}
+
+fn order_accounts(rpc: &RpcClient, mut filters: Vec<RpcFilterType>) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, Order::discriminator().to_vec())));
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        with_context: None,
+    };
+
+    Ok(rpc
+        .get_program_accounts_with_config(&token_sale::ID, config)?
+        .into_iter()
+        .map(|(address, account)| (address, account.data))
+        .collect())
+}
+
+fn fetch_multiple(rpc: &RpcClient, addresses: &[Pubkey]) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    let mut accounts = Vec::with_capacity(addresses.len());
+    // The RPC limits the amount of accounts fetched at once
+    for chunk in addresses.chunks(100) {
+        for (address, account) in chunk.iter().zip(rpc.get_multiple_accounts(chunk)?) {
+            let account = account.ok_or(Error::AccountNotFound(*address))?;
+            accounts.push((*address, account.data));
+        }
+    }
+    Ok(accounts)
+}
+
+fn fetch_data(rpc: &RpcClient, address: &Pubkey) -> Result<Vec<u8>> {
+    let account = rpc
+        .get_account_with_commitment(address, rpc.commitment())?
+        .value
+        .ok_or(Error::AccountNotFound(*address))?;
+    Ok(account.data)
+}
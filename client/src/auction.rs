@@ -0,0 +1,23 @@
+//! Preparing the sealed bids and checking the fills of an auction off-chain with the functions of the program.
+
+use anchor_lang::prelude::Pubkey;
+use token_sale::account::AuctionAccount;
+use token_sale::currency::Tokens;
+
+pub use token_sale::auction::{bid_commitment, filled, rank, RankedBid};
+
+/// The tokens every ranked bid gets, from the highest bid, the bids that get nothing are left out
+pub fn fills(auction: &AuctionAccount) -> Vec<(Pubkey, Tokens)> {
+    auction
+        .bids
+        .iter()
+        .map(|ranked| (ranked.bid, filled(&auction.bids, auction.tokens, &ranked.bid)))
+        .filter(|(_, tokens)| *tokens > Tokens::new(0))
+        .collect()
+}
+
+/// The lowest price that got any tokens, `None` while no bid does
+pub fn clearing_price(auction: &AuctionAccount) -> Option<u64> {
+    let last = fills(auction).last()?.0;
+    auction.bids.iter().find(|ranked| ranked.bid == last).map(|ranked| ranked.price)
+}
@@ -0,0 +1,305 @@
+//! The sale parameters in human units, read from TOML or JSON:
+//!
+//! ```toml
+//! round_start_at = "2022-05-01T12:00:00Z"
+//! end_at = "2022-06-01T12:00:00Z"
+//! buying_duration = "1day"
+//! trading_duration = "12h"
+//! token_price = "0.001"      # SOL for a whole token
+//! amount_to_sell = "1000000" # whole tokens
+//! coeff_a = 1.5
+//! coeff_b = "0.0001"         # SOL for a whole token
+//! unsold_tokens_policy = "burn"
+//! ```
+//!
+//! The program counts the tokens in the minimal parts of the token and prices a whole token,
+//! `price_units` of the minimal parts, so the config is converted using the decimals of the mint.
+
+use crate::error::{Error, Result};
+use anchor_lang::prelude::{Pubkey, Result as ProgramResult};
+use anchor_lang::{err, require};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, UNIX_EPOCH};
+use token_sale::access_control::{valid_price_coefficients, valid_schedule};
+use token_sale::currency::{try_next_token_price, Lamports, Tokens};
+use token_sale::error::ErrorCode;
+use token_sale::round::Round;
+use token_sale::unsold_tokens_policy::UnsoldTokensPolicy;
+
+use crate::instruction::InitializeArgs;
+
+const SOL_DECIMALS: u8 = 9;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SaleConfig {
+    /// Tells apart the sales of the same mint, the first sale is 0
+    #[serde(default)]
+    pub sale_id: u64,
+    /// RFC 3339 date the first buying round starts at
+    pub round_start_at: String,
+    /// RFC 3339 date the sale could be terminated after
+    pub end_at: String,
+    /// The duration of a buying round, like "2h 30min"
+    pub buying_duration: String,
+    /// The duration of a trading round, like "2h 30min"
+    pub trading_duration: String,
+    /// SOL for a whole token in the first buying round
+    pub token_price: String,
+    /// Whole tokens put up for sale
+    pub amount_to_sell: String,
+    /// The next buying round price is `token_price * coeff_a + coeff_b`
+    pub coeff_a: f32,
+    /// SOL for a whole token
+    #[serde(default = "zero")]
+    pub coeff_b: String,
+    #[serde(default)]
+    pub unsold_tokens_policy: Policy,
+    /// The token account receiving the unsold tokens with the `send-to-destination` policy
+    #[serde(default)]
+    pub unsold_tokens_destination: Option<String>,
+}
+
+fn zero() -> String {
+    "0".to_owned()
+}
+
+/// `UnsoldTokensPolicy` as it is written in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Policy {
+    #[default]
+    Burn,
+    ReturnToAdmin,
+    SendToDestination,
+}
+
+impl From<Policy> for UnsoldTokensPolicy {
+    fn from(policy: Policy) -> Self {
+        match policy {
+            Policy::Burn => UnsoldTokensPolicy::Burn,
+            Policy::ReturnToAdmin => UnsoldTokensPolicy::ReturnToAdmin,
+            Policy::SendToDestination => UnsoldTokensPolicy::SendToDestination,
+        }
+    }
+}
+
+impl SaleConfig {
+    /// Reads the config, the format is chosen by the extension of the file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| Error::InvalidConfig(format!("cannot read {}: {}", path.display(), err)))?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml(&content),
+            Some("json") => Self::from_json(&content),
+            _ => Err(Error::InvalidConfig(format!("{} is neither .toml nor .json", path.display()))),
+        }
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content).map_err(|err| Error::InvalidConfig(err.to_string()))
+    }
+
+    pub fn from_json(content: &str) -> Result<Self> {
+        serde_json::from_str(content).map_err(|err| Error::InvalidConfig(err.to_string()))
+    }
+
+    /// The token account passed to `initialize` for the unsold tokens, the default address if the policy needs none
+    pub fn unsold_tokens_destination(&self) -> Result<Pubkey> {
+        match (&self.unsold_tokens_destination, self.unsold_tokens_policy) {
+            (Some(destination), _) => Pubkey::from_str(destination)
+                .map_err(|_| invalid("unsold_tokens_destination", "not a public key")),
+            (None, Policy::SendToDestination) => {
+                Err(invalid("unsold_tokens_destination", "required by the send-to-destination policy"))
+            }
+            (None, _) => Ok(Pubkey::default()),
+        }
+    }
+
+    /// Converts the config to the units of the program for the mint with the given decimals
+    pub fn initialize_args(&self, decimals: u8) -> Result<InitializeArgs> {
+        let price_units = 10u64
+            .checked_pow(decimals as u32)
+            .ok_or_else(|| invalid("token_price", "a whole token of the mint is too large to be priced"))?;
+
+        Ok(InitializeArgs {
+            sale_id: self.sale_id,
+            round_start_at: parse_date("round_start_at", &self.round_start_at)?,
+            end_at: parse_date("end_at", &self.end_at)?,
+            buying_duration: parse_duration("buying_duration", &self.buying_duration)?,
+            trading_duration: parse_duration("trading_duration", &self.trading_duration)?,
+            token_price: parse_price("token_price", &self.token_price)?,
+            price_units,
+            amount_to_sell: Tokens::new(parse_amount("amount_to_sell", &self.amount_to_sell, decimals)?),
+            coeff_a: self.coeff_a,
+            coeff_b: parse_price("coeff_b", &self.coeff_b)?
+                .try_into()
+                .map_err(|_| invalid("coeff_b", "too large"))?,
+            unsold_tokens_policy: self.unsold_tokens_policy.into(),
+        })
+    }
+}
+
+/// Checks the parameters the same way `initialize` does at the time `now`
+/// when `tokens_for_sale` are in the `tokens_for_distribution` account.
+/// `destination_mint` is the mint of the unsold tokens destination, `None` if it is not a token account.
+pub fn validate(
+    args: &InitializeArgs,
+    now: i64,
+    tokens_for_sale: u64,
+    selling_mint: &Pubkey,
+    destination_mint: Option<Pubkey>,
+) -> Result<()> {
+    let initialize = || -> ProgramResult<()> {
+        require!(args.token_price != 0, ErrorCode::TokenPriceZero);
+        require!(args.price_units != 0, ErrorCode::PriceUnitsZero);
+        require!(args.amount_to_sell <= Tokens::new(tokens_for_sale), ErrorCode::NotEnoughTokensForSale);
+        require!(args.round_start_at >= now, ErrorCode::FirstRoundAlreadyStarted);
+        valid_schedule(now, args.end_at, args.buying_duration, args.trading_duration)?;
+        valid_price_coefficients(args.coeff_a, args.coeff_b)?;
+        if args.unsold_tokens_policy == UnsoldTokensPolicy::SendToDestination {
+            require!(destination_mint == Some(*selling_mint), ErrorCode::InvalidUnsoldTokensDestination);
+        }
+        Ok(())
+    };
+    rejected(initialize())
+}
+
+fn rejected<T>(result: ProgramResult<T>) -> Result<T> {
+    result.map_err(|err| Error::Rejected(Box::new(err)))
+}
+
+/// A round of the sale when the operator switches the rounds right as they end
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledRound {
+    pub round: Round,
+    pub starts_at: i64,
+    /// The round is cut short by the end of the sale
+    pub ends_at: i64,
+    /// The price the tokens are bought from the pool and the orders are filled at, in lamports for `price_units`
+    pub token_price: u64,
+}
+
+/// The rounds of the sale from the first buying round until the end of the sale
+pub fn schedule(args: &InitializeArgs) -> Result<Vec<ScheduledRound>> {
+    let mut rounds = vec![];
+    let mut starts_at = args.round_start_at;
+    let mut token_price = args.token_price;
+    // The auctions are started by the operator at will, so only the buying and the trading rounds are scheduled
+    let mut round = Round::Buying;
+
+    while starts_at < args.end_at {
+        let duration = match round {
+            Round::Buying => args.buying_duration,
+            _ => args.trading_duration,
+        };
+        let ends_at = (starts_at + duration as i64).min(args.end_at);
+        rounds.push(ScheduledRound { round: round.clone(), starts_at, ends_at, token_price });
+
+        round = match round {
+            Round::Buying => Round::Trading,
+            _ => {
+                token_price = match try_next_token_price(token_price, args.coeff_a, args.coeff_b) {
+                    Some(price) => price,
+                    None => return rejected(err!(ErrorCode::InvalidPriceCoefficients)),
+                };
+                Round::Buying
+            }
+        };
+        if duration == 0 {
+            break;
+        }
+        starts_at = ends_at;
+    }
+
+    Ok(rounds)
+}
+
+/// SOL for a whole token of the price in lamports for `price_units` minimal parts of the token
+pub fn ui_price(token_price: u64, price_units: u64, decimals: u8) -> String {
+    let lamports_per_token = token_price as u128 * 10u128.pow(decimals as u32) / price_units.max(1) as u128;
+    format_decimal(lamports_per_token, SOL_DECIMALS)
+}
+
+/// Whole tokens of the amount in minimal parts
+pub fn ui_amount(amount: u64, decimals: u8) -> String {
+    format_decimal(amount as u128, decimals)
+}
+
+pub fn format_date(timestamp: i64) -> String {
+    match u64::try_from(timestamp) {
+        Ok(secs) => humantime::format_rfc3339_seconds(UNIX_EPOCH + Duration::from_secs(secs)).to_string(),
+        Err(_) => timestamp.to_string(),
+    }
+}
+
+/// Whole tokens to minimal parts
+pub fn tokens_from_ui(value: &str, decimals: u8) -> Result<Tokens> {
+    parse_amount("amount", value, decimals).map(Tokens::new)
+}
+
+/// RFC 3339 date to UNIX timestamp
+pub fn timestamp_from_date(value: &str) -> Result<i64> {
+    parse_date("date", value)
+}
+
+pub fn lamports_from_sol(value: &str) -> Result<Lamports> {
+    parse_amount("SOL", value, SOL_DECIMALS).map(Lamports::new)
+}
+
+fn invalid(field: &str, reason: &str) -> Error {
+    Error::InvalidConfig(format!("{}: {}", field, reason))
+}
+
+fn parse_date(field: &str, value: &str) -> Result<i64> {
+    let time = humantime::parse_rfc3339_weak(value).map_err(|err| invalid(field, &err.to_string()))?;
+    let secs = time.duration_since(UNIX_EPOCH).map_err(|_| invalid(field, "before 1970"))?.as_secs();
+    i64::try_from(secs).map_err(|_| invalid(field, "too late"))
+}
+
+fn parse_duration(field: &str, value: &str) -> Result<u32> {
+    let duration = humantime::parse_duration(value).map_err(|err| invalid(field, &err.to_string()))?;
+    u32::try_from(duration.as_secs()).map_err(|_| invalid(field, "too long"))
+}
+
+/// Whole tokens to minimal parts
+fn parse_amount(field: &str, value: &str, decimals: u8) -> Result<u64> {
+    let amount = parse_decimal(value, decimals).ok_or_else(|| {
+        invalid(field, &format!("expected a decimal number with at most {} decimals", decimals))
+    })?;
+    u64::try_from(amount).map_err(|_| invalid(field, "too large"))
+}
+
+/// SOL for a whole token to lamports for a whole token
+fn parse_price(field: &str, value: &str) -> Result<u64> {
+    let lamports_per_token = parse_decimal(value, SOL_DECIMALS)
+        .ok_or_else(|| invalid(field, "expected a decimal number of SOL with at most 9 decimals"))?;
+    u64::try_from(lamports_per_token).map_err(|_| invalid(field, "too large"))
+}
+
+/// "12.5" with 3 decimals is 12500, `None` when there are more decimals than allowed
+fn parse_decimal(value: &str, decimals: u8) -> Option<u128> {
+    let (integer, fraction) = value.trim().split_once('.').unwrap_or((value.trim(), ""));
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if integer.is_empty() || !is_digits(integer) || !is_digits(fraction) || fraction.len() > decimals as usize {
+        return None;
+    }
+
+    let scale = 10u128.checked_pow(decimals as u32)?;
+    let fraction_scale = 10u128.pow((decimals as usize - fraction.len()) as u32);
+    let fraction = if fraction.is_empty() { 0 } else { fraction.parse::<u128>().ok()? };
+    integer.parse::<u128>().ok()?.checked_mul(scale)?.checked_add(fraction * fraction_scale)
+}
+
+fn format_decimal(value: u128, decimals: u8) -> String {
+    let scale = 10u128.pow(decimals as u32);
+    let fraction = format!("{:0width$}", value % scale, width = decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        (value / scale).to_string()
+    } else {
+        format!("{}.{}", value / scale, fraction)
+    }
+}
@@ -0,0 +1,36 @@
+use solana_client::client_error::ClientError;
+
+/// The errors of the RPC client and anchor are large, so they are boxed to keep `Result` small
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Rpc(Box<ClientError>),
+    #[error("Account {0} not found")]
+    AccountNotFound(anchor_lang::prelude::Pubkey),
+    #[error("Cannot deserialize the account: {0}")]
+    Deserialize(Box<anchor_lang::error::Error>),
+    #[error("Invalid sale config: {0}")]
+    InvalidConfig(String),
+    #[error("The program would reject the sale: {0}")]
+    Rejected(Box<anchor_lang::error::Error>),
+    #[error("The view instruction failed: {0}")]
+    View(String),
+    #[error("Overflow of {0}")]
+    Overflow(String),
+    #[error("Account {0} does not belong to SPL Token or Token-2022")]
+    NotTokenAccount(anchor_lang::prelude::Pubkey),
+}
+
+impl From<ClientError> for Error {
+    fn from(err: ClientError) -> Self {
+        Error::Rpc(Box::new(err))
+    }
+}
+
+impl From<anchor_lang::error::Error> for Error {
+    fn from(err: anchor_lang::error::Error) -> Self {
+        Error::Deserialize(Box::new(err))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
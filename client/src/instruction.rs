@@ -0,0 +1,817 @@
+//! Builders of the instructions of the program.
+//! The pool is identified by its `selling_mint` and `sale_id`, the rest of the program accounts are derived from them.
+//! The builders that touch the tokens also take the `token_program` of the selling mint, SPL Token or Token-2022.
+
+use crate::pda::{
+    allocation_address, auction_address, bid_address, buyer_account_address, legacy_order_address, lottery_address,
+    order_address, order_registry_address, order_registry_page_address, order_token_vault_address, pool_address,
+    stake_account_address, stake_vault_address, ticket_address, vault_selling_address,
+};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::{associated_token, token};
+use token_sale::currency::{Lamports, Tokens};
+use token_sale::tier::Tier;
+use token_sale::unsold_tokens_policy::UnsoldTokensPolicy;
+use token_sale::{accounts, instruction as args};
+
+pub use token_sale::instruction::{
+    Buy as BuyArgs, Initialize as InitializeArgs, PlaceOrder as PlaceOrderArgs, RedeemOrder as RedeemOrderArgs,
+    UpdateConfig as UpdateConfigArgs,
+};
+
+fn instruction(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: token_sale::ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+/// Creates the pool and moves `amount_to_sell` tokens from `tokens_for_distribution` into it.
+/// The distribution authority gets all the roles.
+/// `unsold_tokens_destination` is a token account of the selling mint, ignored unless the policy sends to it.
+pub fn initialize(
+    distribution_authority: &Pubkey,
+    tokens_for_distribution: &Pubkey,
+    selling_mint: &Pubkey,
+    token_program: &Pubkey,
+    unsold_tokens_destination: &Pubkey,
+    args: InitializeArgs,
+) -> Instruction {
+    let sale_id = args.sale_id;
+    instruction(
+        accounts::Initialize {
+            pool_account: pool_address(selling_mint, sale_id),
+            distribution_authority: *distribution_authority,
+            tokens_for_distribution: *tokens_for_distribution,
+            selling_mint: *selling_mint,
+            vault_selling: vault_selling_address(selling_mint, sale_id, token_program),
+            order_registry: order_registry_address(selling_mint, sale_id),
+            unsold_tokens_destination: *unsold_tokens_destination,
+            clock: sysvar::clock::ID,
+            token_program: *token_program,
+            rent: sysvar::rent::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        args,
+    )
+}
+
+/// Buys `args.amount_to_buy` tokens, failing if they cost more than `args.max_total_lamports`
+/// or the round switched from `args.expected_round`
+pub fn buy(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    buyer: &Pubkey,
+    buyer_token_account: &Pubkey,
+    args: BuyArgs,
+) -> Instruction {
+    instruction(
+        accounts::BuyTokens {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            vault_selling: vault_selling_address(selling_mint, sale_id, token_program),
+            buyer: *buyer,
+            buyer_token_account: *buyer_token_account,
+            buyer_account: buyer_account_address(selling_mint, sale_id, buyer),
+            stake_account: stake_account_address(selling_mint, sale_id, buyer),
+            allocation_account: allocation_address(selling_mint, sale_id, buyer),
+            instructions: sysvar::instructions::ID,
+            token_program: *token_program,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+        },
+        args,
+    )
+}
+
+/// Buys as many tokens as `max_lamports` pay for, charging only their price
+pub fn buy_with_lamports(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    buyer: &Pubkey,
+    buyer_token_account: &Pubkey,
+    max_lamports: Lamports,
+) -> Instruction {
+    instruction(
+        accounts::BuyTokens {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            vault_selling: vault_selling_address(selling_mint, sale_id, token_program),
+            buyer: *buyer,
+            buyer_token_account: *buyer_token_account,
+            buyer_account: buyer_account_address(selling_mint, sale_id, buyer),
+            stake_account: stake_account_address(selling_mint, sale_id, buyer),
+            allocation_account: allocation_address(selling_mint, sale_id, buyer),
+            instructions: sysvar::instructions::ID,
+            token_program: *token_program,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+        },
+        args::BuyWithLamports { max_lamports },
+    )
+}
+
+pub fn switch_to_trading(selling_mint: &Pubkey, sale_id: u64, operator: &Pubkey) -> Instruction {
+    instruction(
+        accounts::SwitchToTrading {
+            pool_account: pool_address(selling_mint, sale_id),
+            operator: *operator,
+            clock: sysvar::clock::ID,
+        },
+        args::SwitchToTrading {},
+    )
+}
+
+pub fn switch_to_buying(selling_mint: &Pubkey, sale_id: u64, operator: &Pubkey) -> Instruction {
+    instruction(
+        accounts::SwitchToBuying {
+            pool_account: pool_address(selling_mint, sale_id),
+            operator: *operator,
+            clock: sysvar::clock::ID,
+        },
+        args::SwitchToBuying {},
+    )
+}
+
+/// Lists the order in the page `args.registry_page` of the order registry, see `account::registry_page_with_room`
+pub fn place_order(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    seller: &Pubkey,
+    seller_token_account: &Pubkey,
+    args: PlaceOrderArgs,
+) -> Instruction {
+    let order = order_address(selling_mint, sale_id, seller);
+    let registry_page = args.registry_page;
+    instruction(
+        accounts::PlaceOrder {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            seller: *seller,
+            seller_token_account: *seller_token_account,
+            order,
+            order_token_vault: order_token_vault_address(&order, selling_mint, token_program),
+            order_registry: order_registry_page_address(selling_mint, sale_id, registry_page),
+            token_program: *token_program,
+            rent: sysvar::rent::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+        },
+        args,
+    )
+}
+
+/// Buys the tokens from the order of `order_owner`, failing if the pool price is above `args.max_price_per_token`
+/// or the round switched from `args.expected_round`.
+/// `registry_page` is the `Order::registry_page` of the order, as for `close_order` and `reclaim_order`.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_order(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    order_owner: &Pubkey,
+    registry_page: u32,
+    buyer: &Pubkey,
+    buyer_token_account: &Pubkey,
+    args: RedeemOrderArgs,
+) -> Instruction {
+    let order = order_address(selling_mint, sale_id, order_owner);
+    instruction(
+        accounts::RedeemOrder {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            buyer: *buyer,
+            buyer_token_account: *buyer_token_account,
+            order,
+            order_owner: *order_owner,
+            order_token_vault: order_token_vault_address(&order, selling_mint, token_program),
+            order_registry: order_registry_page_address(selling_mint, sale_id, registry_page),
+            token_program: *token_program,
+            clock: sysvar::clock::ID,
+            system_program: system_program::ID,
+        },
+        args,
+    )
+}
+
+/// Returns the tokens left in the order to `owner_token_account` and closes the order
+pub fn close_order(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    order_owner: &Pubkey,
+    registry_page: u32,
+    owner_token_account: &Pubkey,
+) -> Instruction {
+    let order = order_address(selling_mint, sale_id, order_owner);
+    instruction(
+        accounts::CloseOrder {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            order,
+            order_token_vault: order_token_vault_address(&order, selling_mint, token_program),
+            order_owner: *order_owner,
+            owner_token_vault: *owner_token_account,
+            order_registry: order_registry_page_address(selling_mint, sale_id, registry_page),
+            token_program: *token_program,
+            system_program: system_program::ID,
+        },
+        args::CloseOrder {},
+    )
+}
+
+/// Once the sale is over, returns the tokens left in the order of `order_owner` to its associated token account,
+/// created at the expense of `reclaimer` if needed, and closes the order
+pub fn reclaim_order(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    order_owner: &Pubkey,
+    registry_page: u32,
+    reclaimer: &Pubkey,
+) -> Instruction {
+    let order = order_address(selling_mint, sale_id, order_owner);
+    instruction(
+        accounts::ReclaimOrder {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            order,
+            order_token_vault: order_token_vault_address(&order, selling_mint, token_program),
+            order_owner: *order_owner,
+            owner_token_vault: associated_token::get_associated_token_address_with_program_id(order_owner, selling_mint, token_program),
+            reclaimer: *reclaimer,
+            order_registry: order_registry_page_address(selling_mint, sale_id, registry_page),
+            token_program: *token_program,
+            associated_token_program: associated_token::ID,
+            rent: sysvar::rent::ID,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+        },
+        args::ReclaimOrder {},
+    )
+}
+
+/// Points the order instruction built for `seller` to the order placed before the pool was in the seeds,
+/// see `pda::legacy_order_address`
+pub fn for_legacy_order(
+    mut instruction: Instruction,
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    seller: &Pubkey,
+) -> Instruction {
+    let order = order_address(selling_mint, sale_id, seller);
+    let order_token_vault = order_token_vault_address(&order, selling_mint, &token::ID);
+    let legacy_order = legacy_order_address(seller);
+    for account in &mut instruction.accounts {
+        if account.pubkey == order {
+            account.pubkey = legacy_order;
+        } else if account.pubkey == order_token_vault {
+            account.pubkey = order_token_vault_address(&legacy_order, selling_mint, &token::ID);
+        }
+    }
+    instruction
+}
+
+pub fn deposit_tokens(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    admin: &Pubkey,
+    admin_token_account: &Pubkey,
+    amount: Tokens,
+) -> Instruction {
+    instruction(
+        accounts::DepositTokens {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            vault_selling: vault_selling_address(selling_mint, sale_id, token_program),
+            admin: *admin,
+            admin_token_account: *admin_token_account,
+            token_program: *token_program,
+            clock: sysvar::clock::ID,
+        },
+        args::DepositTokens { amount },
+    )
+}
+
+/// Hands the mint authority from `mint_authority` to the pool, which then mints the tokens it sells
+pub fn enable_minting(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    admin: &Pubkey,
+    mint_authority: &Pubkey,
+    max_supply: Option<Tokens>,
+    revoke_mint_authority: bool,
+) -> Instruction {
+    instruction(
+        accounts::EnableMinting {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            admin: *admin,
+            mint_authority: *mint_authority,
+            token_program: *token_program,
+            clock: sysvar::clock::ID,
+        },
+        args::EnableMinting { max_supply, revoke_mint_authority },
+    )
+}
+
+pub fn withdraw_unsold_tokens(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    admin: &Pubkey,
+    destination_token_account: &Pubkey,
+    amount: Tokens,
+) -> Instruction {
+    instruction(
+        accounts::WithdrawUnsoldTokens {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            vault_selling: vault_selling_address(selling_mint, sale_id, token_program),
+            admin: *admin,
+            destination_token_account: *destination_token_account,
+            token_program: *token_program,
+            clock: sysvar::clock::ID,
+        },
+        args::WithdrawUnsoldTokens { amount },
+    )
+}
+
+pub fn withdraw_lamports(selling_mint: &Pubkey, sale_id: u64, admin: &Pubkey, treasury: &Pubkey) -> Instruction {
+    instruction(
+        accounts::WithdrawLamports {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            admin: *admin,
+            treasury: *treasury,
+            clock: sysvar::clock::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        args::WithdrawLamports {},
+    )
+}
+
+pub fn update_config(selling_mint: &Pubkey, sale_id: u64, admin: &Pubkey, args: UpdateConfigArgs) -> Instruction {
+    instruction(
+        accounts::UpdateConfig {
+            pool_account: pool_address(selling_mint, sale_id),
+            admin: *admin,
+            clock: sysvar::clock::ID,
+        },
+        args,
+    )
+}
+
+pub fn update_roles(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    admin: &Pubkey,
+    new_admin: &Pubkey,
+    treasury: &Pubkey,
+    operator: &Pubkey,
+) -> Instruction {
+    instruction(
+        accounts::UpdateRoles {
+            pool_account: pool_address(selling_mint, sale_id),
+            admin: *admin,
+        },
+        args::UpdateRoles { admin: *new_admin, treasury: *treasury, operator: *operator },
+    )
+}
+
+/// `unsold_tokens_destination` is a token account of the selling mint,
+/// ignored unless the policy is `UnsoldTokensPolicy::SendToDestination`
+pub fn set_unsold_tokens_policy(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    admin: &Pubkey,
+    unsold_tokens_policy: UnsoldTokensPolicy,
+    unsold_tokens_destination: &Pubkey,
+) -> Instruction {
+    instruction(
+        accounts::SetUnsoldTokensPolicy {
+            pool_account: pool_address(selling_mint, sale_id),
+            admin: *admin,
+            unsold_tokens_destination: *unsold_tokens_destination,
+        },
+        args::SetUnsoldTokensPolicy { unsold_tokens_policy },
+    )
+}
+
+pub fn set_paused(selling_mint: &Pubkey, sale_id: u64, admin: &Pubkey, paused: bool) -> Instruction {
+    instruction(
+        accounts::SetPaused {
+            pool_account: pool_address(selling_mint, sale_id),
+            admin: *admin,
+        },
+        args::SetPaused { paused },
+    )
+}
+
+/// Sets the limits of `buy`, see `PoolAccount::max_tokens_per_slot`, `buy_cooldown` and `reject_cpi`
+pub fn set_buy_protection(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    admin: &Pubkey,
+    max_tokens_per_slot: Tokens,
+    buy_cooldown: u32,
+    reject_cpi: bool,
+    trusted_caller: Option<Pubkey>,
+) -> Instruction {
+    instruction(
+        accounts::SetBuyProtection {
+            pool_account: pool_address(selling_mint, sale_id),
+            admin: *admin,
+        },
+        args::SetBuyProtection { max_tokens_per_slot, buy_cooldown, reject_cpi, trusted_caller },
+    )
+}
+
+/// Makes the sale tiered by the stakes of `stake_mint`, see `PoolAccount::allocation`
+pub fn set_tiers(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    admin: &Pubkey,
+    stake_mint: &Pubkey,
+    tiers: Vec<Tier>,
+) -> Instruction {
+    instruction(
+        accounts::SetTiers {
+            pool_account: pool_address(selling_mint, sale_id),
+            stake_mint: *stake_mint,
+            admin: *admin,
+            clock: sysvar::clock::ID,
+        },
+        args::SetTiers { tiers },
+    )
+}
+
+/// Locks `amount` tokens of `stake_mint` from the staker's token account until the end of the sale
+pub fn stake(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    staker: &Pubkey,
+    staker_token_account: &Pubkey,
+    stake_mint: &Pubkey,
+    amount: Tokens,
+) -> Instruction {
+    instruction(
+        accounts::Stake {
+            pool_account: pool_address(selling_mint, sale_id),
+            stake_mint: *stake_mint,
+            stake_account: stake_account_address(selling_mint, sale_id, staker),
+            stake_vault: stake_vault_address(selling_mint, sale_id, staker, stake_mint),
+            staker: *staker,
+            staker_token_account: *staker_token_account,
+            token_program: token::ID,
+            rent: sysvar::rent::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+        },
+        args::Stake { amount },
+    )
+}
+
+/// Returns the staked tokens to the staker's token account after the end of the sale
+pub fn unstake(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    staker: &Pubkey,
+    staker_token_account: &Pubkey,
+    stake_mint: &Pubkey,
+) -> Instruction {
+    instruction(
+        accounts::Unstake {
+            stake_account: stake_account_address(selling_mint, sale_id, staker),
+            stake_mint: *stake_mint,
+            stake_vault: stake_vault_address(selling_mint, sale_id, staker, stake_mint),
+            staker: *staker,
+            staker_token_account: *staker_token_account,
+            token_program: token::ID,
+            clock: sysvar::clock::ID,
+        },
+        args::Unstake {},
+    )
+}
+
+/// Refunds the rent of the buyer's purchase record after the end of the sale
+pub fn close_buyer_account(selling_mint: &Pubkey, sale_id: u64, buyer: &Pubkey) -> Instruction {
+    instruction(
+        accounts::CloseBuyerAccount {
+            buyer_account: buyer_account_address(selling_mint, sale_id, buyer),
+            pool_account: pool_address(selling_mint, sale_id),
+            buyer: *buyer,
+            clock: sysvar::clock::ID,
+        },
+        args::CloseBuyerAccount {},
+    )
+}
+
+/// Starts the lottery of the pool, `secret_hash` is `lottery::secret_hash` of the secret kept by the admin
+pub fn start_lottery(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    admin: &Pubkey,
+    registration_ends_at: i64,
+    winners: u32,
+    allocation: Tokens,
+    secret_hash: [u8; 32],
+) -> Instruction {
+    instruction(
+        accounts::StartLottery {
+            pool_account: pool_address(selling_mint, sale_id),
+            lottery_account: lottery_address(selling_mint, sale_id),
+            admin: *admin,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+        },
+        args::StartLottery { registration_ends_at, winners, allocation, secret_hash },
+    )
+}
+
+pub fn register_ticket(selling_mint: &Pubkey, sale_id: u64, owner: &Pubkey) -> Instruction {
+    instruction(
+        accounts::RegisterTicket {
+            pool_account: pool_address(selling_mint, sale_id),
+            lottery_account: lottery_address(selling_mint, sale_id),
+            ticket_account: ticket_address(selling_mint, sale_id, owner),
+            owner: *owner,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+        },
+        args::RegisterTicket {},
+    )
+}
+
+/// Could be sent by anyone once the registration is over, `draw_lottery` has to follow within 512 slots.
+/// The lottery could only be abandoned after them.
+pub fn close_registration(selling_mint: &Pubkey, sale_id: u64) -> Instruction {
+    instruction(
+        accounts::CloseRegistration {
+            lottery_account: lottery_address(selling_mint, sale_id),
+            clock: sysvar::clock::ID,
+        },
+        args::CloseRegistration {},
+    )
+}
+
+pub fn draw_lottery(selling_mint: &Pubkey, sale_id: u64, admin: &Pubkey, secret: [u8; 32]) -> Instruction {
+    instruction(
+        accounts::DrawLottery {
+            pool_account: pool_address(selling_mint, sale_id),
+            lottery_account: lottery_address(selling_mint, sale_id),
+            admin: *admin,
+            slot_hashes: sysvar::slot_hashes::ID,
+            clock: sysvar::clock::ID,
+        },
+        args::DrawLottery { secret },
+    )
+}
+
+/// Could be sent by anyone once the admin has not drawn the winners in time, everyone buys then
+pub fn abandon_lottery(selling_mint: &Pubkey, sale_id: u64) -> Instruction {
+    instruction(
+        accounts::AbandonLottery {
+            pool_account: pool_address(selling_mint, sale_id),
+            lottery_account: lottery_address(selling_mint, sale_id),
+            clock: sysvar::clock::ID,
+        },
+        args::AbandonLottery {},
+    )
+}
+
+/// Trades the owner's winning ticket for the allocation `buy` requires
+pub fn claim_allocation(selling_mint: &Pubkey, sale_id: u64, owner: &Pubkey) -> Instruction {
+    instruction(
+        accounts::ClaimAllocation {
+            pool_account: pool_address(selling_mint, sale_id),
+            lottery_account: lottery_address(selling_mint, sale_id),
+            ticket_account: ticket_address(selling_mint, sale_id, owner),
+            allocation_account: allocation_address(selling_mint, sale_id, owner),
+            owner: *owner,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+        },
+        args::ClaimAllocation {},
+    )
+}
+
+/// Starts the auction of `tokens` of the vault instead of the current round
+pub fn start_auction(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    operator: &Pubkey,
+    commit_duration: u32,
+    reveal_duration: u32,
+    tokens: Tokens,
+) -> Instruction {
+    instruction(
+        accounts::StartAuction {
+            pool_account: pool_address(selling_mint, sale_id),
+            vault_selling: vault_selling_address(selling_mint, sale_id, token_program),
+            auction_account: auction_address(selling_mint, sale_id),
+            operator: *operator,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+        },
+        args::StartAuction { commit_duration, reveal_duration, tokens },
+    )
+}
+
+/// Commits the sealed bid, `commitment` is `auction::bid_commitment` of the bid and a salt kept by the bidder
+pub fn commit_bid(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    bidder: &Pubkey,
+    commitment: [u8; 32],
+    deposit: Lamports,
+) -> Instruction {
+    instruction(
+        accounts::CommitBid {
+            pool_account: pool_address(selling_mint, sale_id),
+            bid_account: bid_address(selling_mint, sale_id, bidder),
+            bidder: *bidder,
+            system_program: system_program::ID,
+            clock: sysvar::clock::ID,
+        },
+        args::CommitBid { commitment, deposit },
+    )
+}
+
+pub fn switch_to_reveal(selling_mint: &Pubkey, sale_id: u64) -> Instruction {
+    instruction(
+        accounts::SwitchToReveal {
+            pool_account: pool_address(selling_mint, sale_id),
+            clock: sysvar::clock::ID,
+        },
+        args::SwitchToReveal {},
+    )
+}
+
+pub fn reveal_bid(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    bidder: &Pubkey,
+    tokens: Tokens,
+    price: u64,
+    salt: [u8; 32],
+) -> Instruction {
+    instruction(
+        accounts::RevealBid {
+            pool_account: pool_address(selling_mint, sale_id),
+            auction_account: auction_address(selling_mint, sale_id),
+            bid_account: bid_address(selling_mint, sale_id, bidder),
+            bidder: *bidder,
+            clock: sysvar::clock::ID,
+        },
+        args::RevealBid { tokens, price, salt },
+    )
+}
+
+/// Could be sent by anyone after the reveal, the bidder gets the tokens won on the token account
+pub fn settle_bid(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    bidder: &Pubkey,
+    bidder_token_account: &Pubkey,
+) -> Instruction {
+    instruction(
+        accounts::SettleBid {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            vault_selling: vault_selling_address(selling_mint, sale_id, token_program),
+            auction_account: auction_address(selling_mint, sale_id),
+            bid_account: bid_address(selling_mint, sale_id, bidder),
+            bidder: *bidder,
+            bidder_token_account: *bidder_token_account,
+            token_program: *token_program,
+            clock: sysvar::clock::ID,
+        },
+        args::SettleBid {},
+    )
+}
+
+/// Brings the pool and the given orders of the pool to the current layout.
+/// Pass the orders in batches if they do not fit into one transaction, the pool is migrated only once.
+/// The adopted orders are listed in the page `registry_page` of the order registry, see `OrderRegistry::MAX_ORDERS`.
+pub fn migrate_pool(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    admin: &Pubkey,
+    registry_page: u32,
+    orders: &[Pubkey],
+) -> Instruction {
+    let mut ix = instruction(
+        accounts::MigratePool {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            vault_selling: vault_selling_address(selling_mint, sale_id, token_program),
+            order_registry: order_registry_page_address(selling_mint, sale_id, registry_page),
+            admin: *admin,
+            token_program: *token_program,
+            system_program: system_program::ID,
+        },
+        args::MigratePool { _sale_id: sale_id, registry_page },
+    );
+    ix.accounts.extend(orders.iter().map(|order| AccountMeta::new(*order, false)));
+    ix
+}
+
+/// Simulate the view instructions, see the `view` module.
+pub fn quote_buy(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    amount: Tokens,
+) -> Instruction {
+    instruction(
+        accounts::QuoteBuy {
+            pool_account: pool_address(selling_mint, sale_id),
+            vault_selling: vault_selling_address(selling_mint, sale_id, token_program),
+        },
+        args::QuoteBuy { amount },
+    )
+}
+
+pub fn quote_redeem(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    seller: &Pubkey,
+    amount: Tokens,
+) -> Instruction {
+    let order = order_address(selling_mint, sale_id, seller);
+    instruction(
+        accounts::QuoteRedeem {
+            pool_account: pool_address(selling_mint, sale_id),
+            order,
+            token_vault: order_token_vault_address(&order, selling_mint, token_program),
+        },
+        args::QuoteRedeem { amount },
+    )
+}
+
+pub fn pool_status(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+) -> Instruction {
+    instruction(
+        accounts::ViewPoolStatus {
+            pool_account: pool_address(selling_mint, sale_id),
+            vault_selling: vault_selling_address(selling_mint, sale_id, token_program),
+            clock: sysvar::clock::ID,
+        },
+        args::PoolStatus {},
+    )
+}
+
+/// `unsold_tokens_receiver` has to be `PoolAccount::unsold_tokens_receiver`.
+/// When the unsold tokens are burned it is `None` and any account could be passed, the vault is used then.
+/// The pages of the order registry up to `PoolAccount::last_registry_page` are closed with the pool.
+pub fn terminate(
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    token_program: &Pubkey,
+    admin: &Pubkey,
+    unsold_tokens_receiver: Option<Pubkey>,
+    last_registry_page: u32,
+) -> Instruction {
+    let vault_selling = vault_selling_address(selling_mint, sale_id, token_program);
+    let mut ix = instruction(
+        accounts::Terminate {
+            pool_account: pool_address(selling_mint, sale_id),
+            selling_mint: *selling_mint,
+            vault_selling,
+            admin: *admin,
+            unsold_tokens_receiver: unsold_tokens_receiver.unwrap_or(vault_selling),
+            order_registry: order_registry_address(selling_mint, sale_id),
+            clock: sysvar::clock::ID,
+            token_program: *token_program,
+            system_program: system_program::ID,
+        },
+        args::Terminate {},
+    );
+    ix.accounts.extend(
+        (1..=last_registry_page)
+            .map(|page| AccountMeta::new(order_registry_page_address(selling_mint, sale_id, page), false)),
+    );
+    ix
+}
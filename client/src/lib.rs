@@ -0,0 +1,21 @@
+//! The client of the token sale program: the addresses of its accounts, the instructions,
+//! fetching the accounts and quoting the prices the program charges.
+
+pub mod account;
+pub mod auction;
+pub mod config;
+pub mod error;
+pub mod instruction;
+pub mod lottery;
+pub mod pda;
+pub mod quote;
+pub mod simulator;
+pub mod view;
+
+pub use error::{Error, Result};
+pub use token_sale::account::{AuctionAccount, LotteryAccount, Order, PoolAccount};
+pub use token_sale::currency::{Lamports, Tokens};
+pub use token_sale::round::Round;
+pub use token_sale::tier::Tier;
+pub use token_sale::unsold_tokens_policy::UnsoldTokensPolicy;
+pub use token_sale::ID as PROGRAM_ID;
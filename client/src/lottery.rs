@@ -0,0 +1,33 @@
+//! Verifying the draw of a lottery off-chain with the same functions the program draws with.
+
+use token_sale::account::LotteryAccount;
+
+pub use token_sale::lottery::{draw_seed, find_slot_hash, is_winner, secret_hash, shuffled_position};
+
+/// The numbers of the winning tickets, empty until the winners are drawn
+pub fn winning_tickets(lottery: &LotteryAccount) -> Vec<u32> {
+    (0..lottery.tickets).filter(|number| lottery.is_winner(*number)).collect()
+}
+
+/// Whether the secret is the committed one and the lottery was drawn from it and its `slot_hash`
+pub fn verify_draw(lottery: &LotteryAccount, secret: &[u8; 32]) -> bool {
+    lottery.drawn && secret_hash(secret) == lottery.secret_hash && draw_seed(secret, &lottery.slot_hash) == lottery.seed
+}
+
+/// Parses the secret of the lottery from 64 hex digits
+pub fn parse_secret(value: &str) -> Option<[u8; 32]> {
+    let digits = value.trim().as_bytes();
+    if digits.len() != 64 || !digits.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    let mut secret = [0; 32];
+    for (byte, pair) in secret.iter_mut().zip(digits.chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(secret)
+}
+
+/// Formats the secret, its hash or the seed as 64 hex digits
+pub fn format_hash(hash: &[u8; 32]) -> String {
+    hash.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
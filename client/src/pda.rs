@@ -0,0 +1,91 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::{get_associated_token_address, get_associated_token_address_with_program_id};
+use token_sale::account::{
+    AllocationAccount, AuctionAccount, BidAccount, BuyerAccount, LotteryAccount, Order, OrderRegistry, PoolAccount,
+    StakeAccount, TicketAccount,
+};
+
+/// The pool of the sale `sale_id` of the `selling_mint` tokens
+pub fn pool_address(selling_mint: &Pubkey, sale_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[selling_mint.as_ref(), &PoolAccount::sale_seed(sale_id)], &token_sale::ID).0
+}
+
+/// The vault the pool sells the tokens from, `token_program` owns the selling mint
+pub fn vault_selling_address(selling_mint: &Pubkey, sale_id: u64, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(&pool_address(selling_mint, sale_id), selling_mint, token_program)
+}
+
+/// The first page of the list of the active orders of the pool
+pub fn order_registry_address(selling_mint: &Pubkey, sale_id: u64) -> Pubkey {
+    order_registry_page_address(selling_mint, sale_id, 0)
+}
+
+/// The page `page` of the list of the active orders of the pool, see `OrderRegistry::page_seed`
+pub fn order_registry_page_address(selling_mint: &Pubkey, sale_id: u64, page: u32) -> Pubkey {
+    let pool = pool_address(selling_mint, sale_id);
+    let seeds = [OrderRegistry::PDA_SEED, pool.as_ref(), &OrderRegistry::page_seed(page)];
+    Pubkey::find_program_address(&seeds, &token_sale::ID).0
+}
+
+/// The record of the buyer's purchases in the pool, created on the first purchase if the pool `tracks_buyers`
+pub fn buyer_account_address(selling_mint: &Pubkey, sale_id: u64, buyer: &Pubkey) -> Pubkey {
+    let pool = pool_address(selling_mint, sale_id);
+    Pubkey::find_program_address(&[BuyerAccount::PDA_SEED, pool.as_ref(), buyer.as_ref()], &token_sale::ID).0
+}
+
+/// The governance tokens the staker locked for an allocation tier of the pool
+pub fn stake_account_address(selling_mint: &Pubkey, sale_id: u64, staker: &Pubkey) -> Pubkey {
+    let pool = pool_address(selling_mint, sale_id);
+    Pubkey::find_program_address(&[StakeAccount::PDA_SEED, pool.as_ref(), staker.as_ref()], &token_sale::ID).0
+}
+
+/// The vault the staked tokens are kept in
+pub fn stake_vault_address(selling_mint: &Pubkey, sale_id: u64, staker: &Pubkey, stake_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(&stake_account_address(selling_mint, sale_id, staker), stake_mint)
+}
+
+/// The lottery of the fair launch of the pool
+pub fn lottery_address(selling_mint: &Pubkey, sale_id: u64) -> Pubkey {
+    let pool = pool_address(selling_mint, sale_id);
+    Pubkey::find_program_address(&[LotteryAccount::PDA_SEED, pool.as_ref()], &token_sale::ID).0
+}
+
+/// The ticket the wallet registered in the lottery of the pool
+pub fn ticket_address(selling_mint: &Pubkey, sale_id: u64, owner: &Pubkey) -> Pubkey {
+    let pool = pool_address(selling_mint, sale_id);
+    Pubkey::find_program_address(&[TicketAccount::PDA_SEED, pool.as_ref(), owner.as_ref()], &token_sale::ID).0
+}
+
+/// The allocation the wallet claimed for its winning ticket
+pub fn allocation_address(selling_mint: &Pubkey, sale_id: u64, owner: &Pubkey) -> Pubkey {
+    let pool = pool_address(selling_mint, sale_id);
+    Pubkey::find_program_address(&[AllocationAccount::PDA_SEED, pool.as_ref(), owner.as_ref()], &token_sale::ID).0
+}
+
+/// The sealed-bid auction of the pool
+pub fn auction_address(selling_mint: &Pubkey, sale_id: u64) -> Pubkey {
+    let pool = pool_address(selling_mint, sale_id);
+    Pubkey::find_program_address(&[AuctionAccount::PDA_SEED, pool.as_ref()], &token_sale::ID).0
+}
+
+/// The bid the wallet committed in the auction of the pool, it keeps the deposit
+pub fn bid_address(selling_mint: &Pubkey, sale_id: u64, bidder: &Pubkey) -> Pubkey {
+    let pool = pool_address(selling_mint, sale_id);
+    Pubkey::find_program_address(&[BidAccount::PDA_SEED, pool.as_ref(), bidder.as_ref()], &token_sale::ID).0
+}
+
+/// The order of the seller in the pool. A seller could have only one order at a time in a pool.
+pub fn order_address(selling_mint: &Pubkey, sale_id: u64, seller: &Pubkey) -> Pubkey {
+    let pool = pool_address(selling_mint, sale_id);
+    Pubkey::find_program_address(&[Order::PDA_SEED, pool.as_ref(), seller.as_ref()], &token_sale::ID).0
+}
+
+/// The order of the seller placed before the pool was in the seeds, see `Order::pool_seeded`
+pub fn legacy_order_address(seller: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[Order::PDA_SEED, seller.as_ref()], &token_sale::ID).0
+}
+
+/// The vault the tokens of the order are kept in, `token_program` owns the selling mint
+pub fn order_token_vault_address(order: &Pubkey, selling_mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(order, selling_mint, token_program)
+}
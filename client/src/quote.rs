@@ -0,0 +1,35 @@
+//! The prices are calculated by the same functions of `PoolAccount` the program uses,
+//! so the quotes match what the program charges as long as the pool does not change in between.
+
+use token_sale::account::PoolAccount;
+use token_sale::currency::{Lamports, Tokens};
+
+/// The lamports to pay for the tokens bought from the pool
+pub fn quote_buy(pool: &PoolAccount, tokens: Tokens) -> Option<Lamports> {
+    pool.try_tokens_to_lamports(tokens)
+}
+
+/// The lamports to pay to the owner of an order for the redeemed tokens.
+/// The program charges the current price of the pool, not the one asked by the order.
+pub fn quote_redeem(pool: &PoolAccount, tokens: Tokens) -> Option<Lamports> {
+    pool.try_tokens_to_lamports(tokens)
+}
+
+/// The most tokens the lamports could buy at the current price
+pub fn tokens_for_lamports(pool: &PoolAccount, lamports: Lamports) -> Option<Tokens> {
+    pool.try_lamports_to_tokens(lamports)
+}
+
+/// The token prices of the following buying rounds, starting with the current price
+pub fn price_schedule(pool: &PoolAccount, rounds: usize) -> Vec<u64> {
+    let mut pool = pool.clone();
+    let mut prices = Vec::with_capacity(rounds);
+    for _ in 0..rounds {
+        prices.push(pool.token_price);
+        match pool.try_next_token_price() {
+            Some(price) => pool.token_price = price,
+            None => break,
+        }
+    }
+    prices
+}
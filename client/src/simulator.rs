@@ -0,0 +1,172 @@
+//! Simulates a sale before it is launched. The simulated pool is a `PoolAccount`
+//! updated with the same functions the program uses, so the prices and the proceeds match the program.
+//! The operator is assumed to switch the rounds right as they end.
+
+use crate::error::{Error, Result};
+use crate::instruction::InitializeArgs;
+use anchor_lang::prelude::Pubkey;
+use token_sale::account::PoolAccount;
+use token_sale::currency::{Lamports, Tokens};
+use token_sale::round::Round;
+use token_sale::tier::Tier;
+
+/// How many tokens the buyers want in a buying round
+pub trait DemandModel {
+    /// The pool is in the state the buying round starts with, `buying_round` counts from 0
+    fn tokens_wanted(&mut self, pool: &PoolAccount, buying_round: usize) -> Tokens;
+}
+
+/// The same amount of tokens is wanted in every round whatever the price
+pub struct FixedDemand(pub Tokens);
+
+impl DemandModel for FixedDemand {
+    fn tokens_wanted(&mut self, _pool: &PoolAccount, _buying_round: usize) -> Tokens {
+        self.0
+    }
+}
+
+/// The buyers spend the same amount of lamports in every round, so they get fewer tokens as the price grows
+pub struct BudgetDemand(pub Lamports);
+
+impl DemandModel for BudgetDemand {
+    fn tokens_wanted(&mut self, pool: &PoolAccount, _buying_round: usize) -> Tokens {
+        pool.try_lamports_to_tokens(self.0).unwrap_or_else(|| Tokens::new(0))
+    }
+}
+
+impl<F: FnMut(&PoolAccount, usize) -> Tokens> DemandModel for F {
+    fn tokens_wanted(&mut self, pool: &PoolAccount, buying_round: usize) -> Tokens {
+        self(pool, buying_round)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedRound {
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub token_price: u64,
+    pub tokens_sold: Tokens,
+    pub proceeds: Lamports,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    /// The buying rounds, the trading rounds in between do not change the pool
+    pub rounds: Vec<SimulatedRound>,
+    pub tokens_sold: Tokens,
+    pub unsold_tokens: Tokens,
+    pub proceeds: Lamports,
+    /// The buying round the vault got empty in
+    pub sold_out_in_round: Option<usize>,
+    /// The round running when `end_at` is hit
+    pub ended_during: Round,
+}
+
+/// The pool right after `initialize`
+pub fn initial_pool(args: &InitializeArgs) -> PoolAccount {
+    PoolAccount {
+        bump: 0,
+        admin: Pubkey::default(),
+        treasury: Pubkey::default(),
+        operator: Pubkey::default(),
+        selling_mint: Pubkey::default(),
+        payment_mint: Pubkey::default(),
+        vault_selling: Pubkey::default(),
+        end_at: args.end_at,
+        buying_duration: args.buying_duration,
+        trading_duration: args.trading_duration,
+        token_price: args.token_price,
+        current_round: Round::Buying,
+        round_start_at: args.round_start_at,
+        coeff_a: args.coeff_a,
+        coeff_b: args.coeff_b,
+        paused: false,
+        total_supply: args.amount_to_sell,
+        tokens_sold: Tokens::new(0),
+        unsold_tokens_policy: args.unsold_tokens_policy.clone(),
+        unsold_tokens_destination: Pubkey::default(),
+        open_orders: 0,
+        version: PoolAccount::VERSION,
+        sale_id: args.sale_id,
+        mint_on_demand: false,
+        max_supply: None,
+        revoke_mint_authority: false,
+        price_units: args.price_units,
+        round_number: 0,
+        max_tokens_per_slot: Tokens::new(0),
+        last_buy_slot: 0,
+        tokens_sold_in_slot: Tokens::new(0),
+        buy_cooldown: 0,
+        reject_cpi: false,
+        trusted_caller: None,
+        stake_mint: None,
+        tiers: [Tier::default(); 4],
+        lottery: Pubkey::default(),
+        commit_duration: 0,
+        reveal_duration: 0,
+        auction_tokens: Tokens::new(0),
+        open_bids: 0,
+        last_registry_page: 0,
+        reserved: [0; 8],
+    }
+}
+
+pub fn simulate(args: &InitializeArgs, demand: &mut impl DemandModel) -> Result<Simulation> {
+    let mut pool = initial_pool(args);
+    let mut vault_selling = args.amount_to_sell;
+    let mut proceeds = Lamports::new(0);
+    let mut rounds = vec![];
+    let mut sold_out_in_round = None;
+
+    let ended_during = loop {
+        // The buying round
+        let ends_at = (pool.round_start_at + pool.buying_duration as i64).min(pool.end_at);
+        let tokens_sold = demand.tokens_wanted(&pool, rounds.len()).min(vault_selling);
+        let round_proceeds = pool
+            .try_tokens_to_lamports(tokens_sold)
+            .ok_or_else(|| Error::Overflow(format!("the proceeds of the buying round {}", rounds.len())))?;
+
+        vault_selling -= tokens_sold;
+        pool.tokens_sold += tokens_sold;
+        proceeds += round_proceeds;
+        if vault_selling == Tokens::new(0) && sold_out_in_round.is_none() {
+            sold_out_in_round = Some(rounds.len());
+        }
+        rounds.push(SimulatedRound {
+            starts_at: pool.round_start_at,
+            ends_at,
+            token_price: pool.token_price,
+            tokens_sold,
+            proceeds: round_proceeds,
+        });
+        if ends_at >= pool.end_at {
+            break Round::Buying;
+        }
+
+        // The trading round, see `switch_to_trading`
+        pool.round_start_at = ends_at;
+        pool.current_round = Round::Trading;
+        pool.round_number += 1;
+        let ends_at = pool.round_start_at + pool.trading_duration as i64;
+        if ends_at >= pool.end_at || (pool.buying_duration == 0 && pool.trading_duration == 0) {
+            break Round::Trading;
+        }
+
+        // The next buying round, see `switch_to_buying`
+        pool.round_start_at = ends_at;
+        pool.current_round = Round::Buying;
+        pool.round_number += 1;
+        pool.token_price = pool
+            .try_next_token_price()
+            .ok_or_else(|| Error::Overflow(format!("the price of the buying round {}", rounds.len())))?;
+    };
+
+    Ok(Simulation {
+        rounds,
+        tokens_sold: pool.tokens_sold,
+        unsold_tokens: vault_selling,
+        proceeds,
+        sold_out_in_round,
+        ended_during,
+    })
+}
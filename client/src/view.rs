@@ -0,0 +1,79 @@
+//! Calls the view instructions of the program by simulating them and decoding their return data.
+//! The return data is read from the `Program return: <program id> <base64 data>` line of the logs.
+
+use crate::error::{Error, Result};
+use crate::account::fetch_token_program;
+use crate::instruction;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AnchorDeserialize;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::transaction::Transaction;
+use token_sale::currency::Tokens;
+use token_sale::view::{BuyQuote, PoolStatus, RedeemQuote};
+
+/// The runtime may trim the trailing zeros of the return data
+const MAX_RETURN_DATA: usize = 1024;
+
+/// `payer` only has to exist, the transaction is not signed
+pub fn quote_buy(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    amount: Tokens,
+) -> Result<BuyQuote> {
+    let token_program = fetch_token_program(rpc, selling_mint)?;
+    view(rpc, payer, instruction::quote_buy(selling_mint, sale_id, &token_program, amount))
+}
+
+pub fn quote_redeem(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    selling_mint: &Pubkey,
+    sale_id: u64,
+    seller: &Pubkey,
+    amount: Tokens,
+) -> Result<RedeemQuote> {
+    let token_program = fetch_token_program(rpc, selling_mint)?;
+    view(rpc, payer, instruction::quote_redeem(selling_mint, sale_id, &token_program, seller, amount))
+}
+
+pub fn pool_status(rpc: &RpcClient, payer: &Pubkey, selling_mint: &Pubkey, sale_id: u64) -> Result<PoolStatus> {
+    let token_program = fetch_token_program(rpc, selling_mint)?;
+    view(rpc, payer, instruction::pool_status(selling_mint, sale_id, &token_program))
+}
+
+fn view<T: AnchorDeserialize>(rpc: &RpcClient, payer: &Pubkey, instruction: Instruction) -> Result<T> {
+    let tx = Transaction::new_with_payer(&[instruction], Some(payer));
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(rpc.commitment()),
+        ..RpcSimulateTransactionConfig::default()
+    };
+    let result = rpc.simulate_transaction_with_config(&tx, config)?.value;
+    if let Some(err) = result.err {
+        return Err(Error::View(err.to_string()));
+    }
+
+    let logs = result.logs.unwrap_or_default();
+    let data = return_data(&logs).ok_or_else(|| Error::View("no return data".to_owned()))?;
+    decode_return_data(&data)
+}
+
+/// The data returned by the token sale program in the logs
+pub fn return_data(logs: &[String]) -> Option<Vec<u8>> {
+    let prefix = format!("Program return: {} ", token_sale::ID);
+    logs.iter()
+        .rev()
+        .find_map(|log| log.strip_prefix(&prefix))
+        .and_then(|data| base64::decode(data).ok())
+}
+
+pub fn decode_return_data<T: AnchorDeserialize>(data: &[u8]) -> Result<T> {
+    let mut padded = data.to_vec();
+    padded.resize(padded.len().max(MAX_RETURN_DATA), 0);
+    T::deserialize(&mut &padded[..]).map_err(|err| Error::View(err.to_string()))
+}
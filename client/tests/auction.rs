@@ -0,0 +1,54 @@
+use anchor_lang::prelude::Pubkey;
+use token_sale_client::auction::{bid_commitment, clearing_price, filled, fills, rank, RankedBid};
+use token_sale_client::{AuctionAccount, Tokens};
+
+fn ranked(price: u64, tokens: u64) -> RankedBid {
+    RankedBid { bid: Pubkey::new_unique(), price, tokens: Tokens::new(tokens) }
+}
+
+fn auction(tokens: u64, bids: &[RankedBid], max_bids: usize) -> AuctionAccount {
+    let mut auction =
+        AuctionAccount { bump: 255, pool: Pubkey::new_unique(), tokens: Tokens::new(tokens), bids: vec![] };
+    for bid in bids {
+        rank(&mut auction.bids, *bid, auction.tokens, max_bids);
+    }
+    auction
+}
+
+#[test]
+fn binds_the_commitment_to_the_bidder_and_the_bid() {
+    let (bidder, salt) = (Pubkey::new_unique(), [5; 32]);
+    let commitment = bid_commitment(&bidder, Tokens::new(10), 1_000, &salt);
+
+    assert_eq!(bid_commitment(&bidder, Tokens::new(10), 1_000, &salt), commitment);
+    assert_ne!(bid_commitment(&Pubkey::new_unique(), Tokens::new(10), 1_000, &salt), commitment);
+    assert_ne!(bid_commitment(&bidder, Tokens::new(11), 1_000, &salt), commitment);
+    assert_ne!(bid_commitment(&bidder, Tokens::new(10), 1_001, &salt), commitment);
+    assert_ne!(bid_commitment(&bidder, Tokens::new(10), 1_000, &[6; 32]), commitment);
+}
+
+#[test]
+fn fills_the_highest_bids_first() {
+    let (low, high, tie, later_tie) = (ranked(100, 40), ranked(300, 50), ranked(200, 30), ranked(200, 30));
+    let auction = auction(100, &[low, high, tie, later_tie], 64);
+
+    // The earlier reveal wins the tie, the lowest bid is left without tokens and dropped
+    assert_eq!(auction.bids, vec![high, tie, later_tie]);
+    assert_eq!(filled(&auction.bids, auction.tokens, &high.bid), Tokens::new(50));
+    assert_eq!(filled(&auction.bids, auction.tokens, &tie.bid), Tokens::new(30));
+    assert_eq!(filled(&auction.bids, auction.tokens, &later_tie.bid), Tokens::new(20));
+    assert_eq!(filled(&auction.bids, auction.tokens, &low.bid), Tokens::new(0));
+    assert_eq!(fills(&auction).len(), 3);
+    assert_eq!(clearing_price(&auction), Some(200));
+}
+
+#[test]
+fn keeps_at_most_the_highest_bids() {
+    let bids: Vec<_> = (1..=5).map(|price| ranked(price, 1)).collect();
+    let auction = auction(1_000, &bids, 3);
+
+    assert_eq!(auction.bids.iter().map(|bid| bid.price).collect::<Vec<_>>(), vec![5, 4, 3]);
+    assert_eq!(fills(&auction).iter().map(|(_, tokens)| u64::from(*tokens)).sum::<u64>(), 3);
+    assert_eq!(clearing_price(&auction), Some(3));
+    assert_eq!(clearing_price(&self::auction(10, &[], 3)), None);
+}
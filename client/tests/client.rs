@@ -0,0 +1,253 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, InstructionData};
+use anchor_spl::{token, token_2022};
+use token_sale_client::instruction::{self, BuyArgs, InitializeArgs};
+use token_sale_client::{pda, quote, Lamports, PoolAccount, Round, Tier, Tokens, UnsoldTokensPolicy};
+
+fn pool(token_price: u64, coeff_a: f32, coeff_b: u32) -> PoolAccount {
+    PoolAccount {
+        bump: 255,
+        admin: Pubkey::new_unique(),
+        treasury: Pubkey::new_unique(),
+        operator: Pubkey::new_unique(),
+        selling_mint: Pubkey::new_unique(),
+        payment_mint: Pubkey::default(),
+        vault_selling: Pubkey::new_unique(),
+        end_at: 0,
+        buying_duration: 100,
+        trading_duration: 100,
+        token_price,
+        current_round: Round::Buying,
+        round_start_at: 0,
+        coeff_a,
+        coeff_b,
+        paused: false,
+        total_supply: Tokens::new(1_000),
+        tokens_sold: Tokens::new(0),
+        unsold_tokens_policy: UnsoldTokensPolicy::Burn,
+        unsold_tokens_destination: Pubkey::default(),
+        open_orders: 0,
+        version: PoolAccount::VERSION,
+        sale_id: 0,
+        mint_on_demand: false,
+        max_supply: None,
+        revoke_mint_authority: false,
+        price_units: 1,
+        round_number: 0,
+        max_tokens_per_slot: Tokens::new(0),
+        last_buy_slot: 0,
+        tokens_sold_in_slot: Tokens::new(0),
+        buy_cooldown: 0,
+        reject_cpi: false,
+        trusted_caller: None,
+        stake_mint: None,
+        tiers: [Tier::default(); 4],
+        lottery: Pubkey::default(),
+        commit_duration: 0,
+        reveal_duration: 0,
+        auction_tokens: Tokens::new(0),
+        open_bids: 0,
+        last_registry_page: 0,
+        reserved: [0; 8],
+    }
+}
+
+#[test]
+fn derives_the_program_addresses() {
+    let mint = Pubkey::new_unique();
+    let seller = Pubkey::new_unique();
+
+    // The first sale of the mint keeps the address derived from the mint alone
+    let (pool, _) = Pubkey::find_program_address(&[mint.as_ref()], &token_sale::ID);
+    assert_eq!(pda::pool_address(&mint, 0), pool);
+
+    let (second_pool, _) = Pubkey::find_program_address(&[mint.as_ref(), &1u64.to_le_bytes()], &token_sale::ID);
+    assert_eq!(pda::pool_address(&mint, 1), second_pool);
+    assert_ne!(pda::vault_selling_address(&mint, 0, &token::ID), pda::vault_selling_address(&mint, 1, &token::ID));
+    // The associated token accounts of Token-2022 mints are derived with the program
+    assert_ne!(pda::vault_selling_address(&mint, 0, &token::ID), pda::vault_selling_address(&mint, 0, &token_2022::ID));
+
+    let (registry, _) = Pubkey::find_program_address(&[b"order_registry", pool.as_ref()], &token_sale::ID);
+    assert_eq!(pda::order_registry_address(&mint, 0), registry);
+    let (page, _) =
+        Pubkey::find_program_address(&[b"order_registry", pool.as_ref(), &1u32.to_le_bytes()], &token_sale::ID);
+    assert_eq!(pda::order_registry_page_address(&mint, 0, 1), page);
+
+    let (buyer_account, _) = Pubkey::find_program_address(&[b"buyer", pool.as_ref(), seller.as_ref()], &token_sale::ID);
+    assert_eq!(pda::buyer_account_address(&mint, 0, &seller), buyer_account);
+
+    let (stake, _) = Pubkey::find_program_address(&[b"stake", pool.as_ref(), seller.as_ref()], &token_sale::ID);
+    assert_eq!(pda::stake_account_address(&mint, 0, &seller), stake);
+    let stake_mint = Pubkey::new_unique();
+    let vault = |sale_id| pda::stake_vault_address(&mint, sale_id, &seller, &stake_mint);
+    assert_ne!(vault(0), vault(1));
+
+    let (lottery, _) = Pubkey::find_program_address(&[b"lottery", pool.as_ref()], &token_sale::ID);
+    assert_eq!(pda::lottery_address(&mint, 0), lottery);
+    let (ticket, _) = Pubkey::find_program_address(&[b"ticket", pool.as_ref(), seller.as_ref()], &token_sale::ID);
+    assert_eq!(pda::ticket_address(&mint, 0, &seller), ticket);
+    let (allocation, _) =
+        Pubkey::find_program_address(&[b"allocation", pool.as_ref(), seller.as_ref()], &token_sale::ID);
+    assert_eq!(pda::allocation_address(&mint, 0, &seller), allocation);
+
+    let (order, _) = Pubkey::find_program_address(&[b"order", pool.as_ref(), seller.as_ref()], &token_sale::ID);
+    assert_eq!(pda::order_address(&mint, 0, &seller), order);
+    assert_ne!(pda::order_address(&mint, 0, &seller), pda::order_address(&mint, 1, &seller));
+    let (legacy_order, _) = Pubkey::find_program_address(&[b"order", seller.as_ref()], &token_sale::ID);
+    assert_eq!(pda::legacy_order_address(&seller), legacy_order);
+    let order_vault = pda::order_token_vault_address(&order, &mint, &token::ID);
+    assert_ne!(order_vault, pda::vault_selling_address(&mint, 0, &token::ID));
+}
+
+#[test]
+fn quotes_the_prices_charged_by_the_program() {
+    let pool = pool(1_000, 1.5, 100);
+
+    assert_eq!(quote::quote_buy(&pool, Tokens::new(7)), Some(Lamports::new(7_000)));
+    assert_eq!(quote::quote_redeem(&pool, Tokens::new(3)), Some(Lamports::new(3_000)));
+    assert_eq!(quote::tokens_for_lamports(&pool, Lamports::new(7_999)), Some(Tokens::new(7)));
+    assert_eq!(quote::quote_buy(&pool, Tokens::new(u64::MAX)), None);
+
+    // 1_000 * 1.5 + 100, 1_600 * 1.5 + 100
+    assert_eq!(quote::price_schedule(&pool, 3), vec![1_000, 1_600, 2_500]);
+}
+
+#[test]
+fn rounds_the_fractions_of_a_lamport_in_favour_of_the_pool() {
+    // 3 lamports for 1_000 minimal parts
+    let pool = PoolAccount { price_units: 1_000, ..pool(3, 1.5, 0) };
+
+    assert_eq!(quote::quote_buy(&pool, Tokens::new(1)), Some(Lamports::new(1)));
+    assert_eq!(quote::quote_buy(&pool, Tokens::new(1_001)), Some(Lamports::new(4)));
+    assert_eq!(quote::quote_buy(&pool, Tokens::new(u64::MAX)), Some(Lamports::new(55_340_232_221_128_655)));
+    assert_eq!(quote::tokens_for_lamports(&pool, Lamports::new(2)), Some(Tokens::new(666)));
+    assert_eq!(quote::tokens_for_lamports(&pool, Lamports::new(u64::MAX)), None);
+
+    // The pools initialized before the price units were added price a single minimal part
+    let legacy = PoolAccount { price_units: 0, ..pool };
+    assert_eq!(quote::quote_buy(&legacy, Tokens::new(7)), Some(Lamports::new(21)));
+}
+
+#[test]
+fn gives_the_allocation_of_the_highest_tier_reached() {
+    let tier = |min_stake, allocation| Tier { min_stake: Tokens::new(min_stake), allocation: Tokens::new(allocation) };
+    let mut pool = pool(1_000, 1.5, 100);
+    pool.tiers[..2].copy_from_slice(&[tier(100, 10), tier(500, 60)]);
+
+    // Anyone buys anything in a sale without the stake mint
+    assert_eq!(pool.allocation(Tokens::new(0)), None);
+
+    pool.stake_mint = Some(Pubkey::new_unique());
+    assert_eq!(pool.allocation(Tokens::new(99)), Some(Tokens::new(0)));
+    assert_eq!(pool.allocation(Tokens::new(100)), Some(Tokens::new(10)));
+    assert_eq!(pool.allocation(Tokens::new(10_000)), Some(Tokens::new(60)));
+}
+
+#[test]
+fn price_schedule_stops_on_overflow() {
+    let pool = pool(u64::MAX / 2, 3.0, 0);
+
+    assert_eq!(quote::price_schedule(&pool, 5), vec![u64::MAX / 2]);
+}
+
+#[test]
+fn builds_the_instructions() {
+    let mint = Pubkey::new_unique();
+    let buyer = Pubkey::new_unique();
+    let buyer_tokens = Pubkey::new_unique();
+
+    let buy_args = || BuyArgs {
+        amount_to_buy: Tokens::new(42),
+        max_total_lamports: Lamports::new(42_000),
+        expected_round: Some(2),
+    };
+    let ix = instruction::buy(&mint, 0, &token_2022::ID, &buyer, &buyer_tokens, buy_args());
+    assert_eq!(ix.program_id, token_sale::ID);
+    assert_eq!(ix.accounts[0].pubkey, pda::pool_address(&mint, 0));
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == buyer && meta.is_signer));
+    let buyer_account = pda::buyer_account_address(&mint, 0, &buyer);
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == buyer_account && meta.is_writable));
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == pda::vault_selling_address(&mint, 0, &token_2022::ID)));
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == token_2022::ID));
+    assert_eq!(ix.data, buy_args().data());
+    let args = token_sale::instruction::Buy::try_from_slice(&ix.data[8..]).unwrap();
+    assert_eq!(args.amount_to_buy, Tokens::new(42));
+    assert_eq!(args.expected_round, Some(2));
+
+    let ix = instruction::buy_with_lamports(&mint, 0, &token::ID, &buyer, &buyer_tokens, Lamports::new(2_000));
+    let args = token_sale::instruction::BuyWithLamports::try_from_slice(&ix.data[8..]).unwrap();
+    assert_eq!(args.max_lamports, Lamports::new(2_000));
+
+    let authority = Pubkey::new_unique();
+    let ix = instruction::initialize(
+        &authority,
+        &Pubkey::new_unique(),
+        &mint,
+        &token::ID,
+        &Pubkey::default(),
+        InitializeArgs {
+            sale_id: 1,
+            round_start_at: 10,
+            end_at: 1_000,
+            buying_duration: 100,
+            trading_duration: 100,
+            token_price: 1_000,
+            price_units: 1,
+            amount_to_sell: Tokens::new(10_000),
+            coeff_a: 1.5,
+            coeff_b: 100,
+            unsold_tokens_policy: UnsoldTokensPolicy::Burn,
+        },
+    );
+    assert_eq!(ix.accounts[0].pubkey, pda::pool_address(&mint, 1));
+    let vault_selling = pda::vault_selling_address(&mint, 1, &token::ID);
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == vault_selling && meta.is_writable));
+
+    let ix = instruction::terminate(&mint, 0, &token::ID, &authority, None, 2);
+    let vault_selling = pda::vault_selling_address(&mint, 0, &token::ID);
+    assert!(ix.accounts.iter().filter(|meta| meta.pubkey == vault_selling).count() == 2);
+    let pages: Vec<Pubkey> = ix.accounts.iter().rev().take(2).map(|meta| meta.pubkey).collect();
+    assert_eq!(pages, [pda::order_registry_page_address(&mint, 0, 2), pda::order_registry_page_address(&mint, 0, 1)]);
+
+    let ix = instruction::enable_minting(&mint, 0, &token::ID, &authority, &authority, Some(Tokens::new(500)), true);
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == mint && meta.is_writable));
+    let args = token_sale::instruction::EnableMinting::try_from_slice(&ix.data[8..]).unwrap();
+    assert_eq!(args.max_supply, Some(Tokens::new(500)));
+
+    let order = pda::order_address(&mint, 0, &buyer);
+    let ix = instruction::close_order(&mint, 0, &token::ID, &buyer, 0, &authority);
+    let ix = instruction::for_legacy_order(ix, &mint, 0, &buyer);
+    let legacy_order = pda::legacy_order_address(&buyer);
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == legacy_order));
+    let legacy_vault = pda::order_token_vault_address(&legacy_order, &mint, &token::ID);
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == legacy_vault));
+    assert!(!ix.accounts.iter().any(|meta| meta.pubkey == order));
+
+    let ix = instruction::migrate_pool(&mint, 0, &token::ID, &authority, 0, &[order]);
+    let last = ix.accounts.last().unwrap();
+    assert!(last.pubkey == order && last.is_writable && !last.is_signer);
+}
+
+#[test]
+fn decodes_the_return_data_of_the_views() {
+    use anchor_lang::AnchorSerialize;
+    use token_sale::view::BuyQuote;
+    use token_sale_client::view::{decode_return_data, return_data};
+
+    let quote = BuyQuote { amount: Tokens::new(7), lamports: Lamports::new(7_000), tokens_in_vault: Tokens::new(0) };
+    let mut data = quote.try_to_vec().unwrap();
+    // The runtime may trim the trailing zeros
+    while data.last() == Some(&0) {
+        data.pop();
+    }
+    let logs = vec![
+        format!("Program {} invoke [1]", token_sale::ID),
+        format!("Program return: {} {}", Pubkey::new_unique(), base64::encode([1, 2, 3])),
+        format!("Program return: {} {}", token_sale::ID, base64::encode(&data)),
+        format!("Program {} success", token_sale::ID),
+    ];
+
+    let data = return_data(&logs).unwrap();
+    assert_eq!(decode_return_data::<BuyQuote>(&data).unwrap(), quote);
+    assert!(return_data(&logs[..2]).is_none());
+}
@@ -0,0 +1,160 @@
+use anchor_lang::error::Error as ProgramError;
+use anchor_lang::prelude::Pubkey;
+use std::path::Path;
+use token_sale::error::ErrorCode;
+use token_sale_client::config::{schedule, ui_amount, ui_price, validate, Policy, SaleConfig, ScheduledRound};
+use token_sale_client::{Error, Round, UnsoldTokensPolicy};
+
+/// 2022-05-01T12:00:00Z
+const START: i64 = 1_651_406_400;
+
+const TOML: &str = r#"
+round_start_at = "2022-05-01T12:00:00Z"
+end_at = "2022-05-02T12:00:00Z"
+buying_duration = "8h"
+trading_duration = "4h"
+token_price = "0.001"
+amount_to_sell = "1000.5"
+coeff_a = 1.5
+coeff_b = "0.0001"
+unsold_tokens_policy = "return-to-admin"
+"#;
+
+fn config() -> SaleConfig {
+    SaleConfig::from_toml(TOML).unwrap()
+}
+
+fn assert_rejected(result: token_sale_client::Result<()>, code: ErrorCode) {
+    match result {
+        Err(Error::Rejected(err)) => match *err {
+            ProgramError::AnchorError(err) => assert_eq!(err.error_code_number, u32::from(code)),
+            other => panic!("expected {:?}, got {:?}", code, other),
+        },
+        other => panic!("expected {:?}, got {:?}", code, other),
+    }
+}
+
+#[test]
+fn converts_human_units_for_the_mint() {
+    let args = config().initialize_args(3).unwrap();
+
+    assert_eq!(args.round_start_at, START);
+    assert_eq!(args.end_at, START + 24 * 3600);
+    assert_eq!(args.buying_duration, 8 * 3600);
+    assert_eq!(args.trading_duration, 4 * 3600);
+    // 0.001 SOL for a whole token, 10^3 minimal parts
+    assert_eq!(args.token_price, 1_000_000);
+    assert_eq!(args.price_units, 1_000);
+    assert_eq!(args.coeff_b, 100_000);
+    assert_eq!(u64::from(args.amount_to_sell), 1_000_500);
+    assert!(args.unsold_tokens_policy == UnsoldTokensPolicy::ReturnToAdmin);
+
+    assert_eq!(ui_price(args.token_price, args.price_units, 3), "0.001");
+    assert_eq!(ui_amount(u64::from(args.amount_to_sell), 3), "1000.5");
+
+    // A minimal part of a 9 decimals token costs a fraction of a lamport
+    let args = SaleConfig { amount_to_sell: "1".to_owned(), ..config() }.initialize_args(9).unwrap();
+    assert_eq!(args.price_units, 1_000_000_000);
+    assert_eq!(ui_price(args.token_price, args.price_units, 9), "0.001");
+}
+
+#[test]
+fn reads_toml_and_json() {
+    let json = serde_json::to_string(&config()).unwrap();
+
+    assert_eq!(SaleConfig::from_json(&json).unwrap(), config());
+    assert_eq!(config().unsold_tokens_policy, Policy::ReturnToAdmin);
+    assert!(SaleConfig::from_toml(&format!("{}\nunknown = 1", TOML)).is_err());
+    assert!(matches!(SaleConfig::load(Path::new("sale.yaml")), Err(Error::InvalidConfig(_))));
+}
+
+#[test]
+fn rejects_values_the_mint_cannot_represent() {
+    // The amount has more decimals than the mint
+    assert!(matches!(config().initialize_args(0), Err(Error::InvalidConfig(_))));
+
+    // A whole token has more minimal parts than the program could count
+    let config = SaleConfig { amount_to_sell: "0".to_owned(), ..config() };
+    assert!(matches!(config.initialize_args(20), Err(Error::InvalidConfig(_))));
+
+    for (field, value) in [("round_start_at", "tomorrow"), ("buying_duration", "8 hours ago"), ("token_price", "-1")] {
+        let mut config = config.clone();
+        match field {
+            "round_start_at" => config.round_start_at = value.to_owned(),
+            "buying_duration" => config.buying_duration = value.to_owned(),
+            _ => config.token_price = value.to_owned(),
+        }
+        assert!(matches!(config.initialize_args(0), Err(Error::InvalidConfig(_))), "{} = {}", field, value);
+    }
+
+    let config = SaleConfig { unsold_tokens_policy: Policy::SendToDestination, ..config };
+    assert!(matches!(config.unsold_tokens_destination(), Err(Error::InvalidConfig(_))));
+    let config = SaleConfig { unsold_tokens_destination: Some("liquidity pool".to_owned()), ..config };
+    assert!(matches!(config.unsold_tokens_destination(), Err(Error::InvalidConfig(_))));
+}
+
+#[test]
+fn reproduces_the_checks_of_initialize() {
+    let mint = Pubkey::new_unique();
+    let args = config().initialize_args(3).unwrap();
+    let tokens = u64::from(args.amount_to_sell);
+    validate(&args, START, tokens, &mint, None).unwrap();
+
+    let zero_price = SaleConfig { token_price: "0".to_owned(), ..config() }.initialize_args(3).unwrap();
+    assert_rejected(validate(&zero_price, START, tokens, &mint, None), ErrorCode::TokenPriceZero);
+
+    assert_rejected(validate(&args, START, tokens - 1, &mint, None), ErrorCode::NotEnoughTokensForSale);
+    assert_rejected(validate(&args, START + 1, tokens, &mint, None), ErrorCode::FirstRoundAlreadyStarted);
+
+    let short = SaleConfig { end_at: "2022-05-01T23:59:59Z".to_owned(), ..config() }.initialize_args(3).unwrap();
+    assert_rejected(validate(&short, START, tokens, &mint, None), ErrorCode::EndsBeforeFullCircle);
+
+    let no_growth = SaleConfig { coeff_a: 0.0, coeff_b: "0".to_owned(), ..config() }.initialize_args(3).unwrap();
+    assert_rejected(validate(&no_growth, START, tokens, &mint, None), ErrorCode::InvalidPriceCoefficients);
+
+    // The destination must be a token account of the selling mint
+    let send = SaleConfig { unsold_tokens_policy: Policy::SendToDestination, ..config() }.initialize_args(3).unwrap();
+    validate(&send, START, tokens, &mint, Some(mint)).unwrap();
+    let other_mint = Some(Pubkey::new_unique());
+    assert_rejected(validate(&send, START, tokens, &mint, other_mint), ErrorCode::InvalidUnsoldTokensDestination);
+    assert_rejected(validate(&send, START, tokens, &mint, None), ErrorCode::InvalidUnsoldTokensDestination);
+}
+
+#[test]
+fn projects_the_schedule() {
+    let args = config().initialize_args(3).unwrap();
+
+    let rounds = schedule(&args).unwrap();
+
+    let hour = 3600;
+    assert_eq!(
+        rounds,
+        vec![
+            ScheduledRound {
+                round: Round::Buying,
+                starts_at: START,
+                ends_at: START + 8 * hour,
+                token_price: 1_000_000,
+            },
+            ScheduledRound {
+                round: Round::Trading,
+                starts_at: START + 8 * hour,
+                ends_at: START + 12 * hour,
+                token_price: 1_000_000,
+            },
+            ScheduledRound {
+                round: Round::Buying,
+                starts_at: START + 12 * hour,
+                ends_at: START + 20 * hour,
+                token_price: 1_600_000,
+            },
+            // Cut short by the end of the sale
+            ScheduledRound {
+                round: Round::Trading,
+                starts_at: START + 20 * hour,
+                ends_at: START + 24 * hour,
+                token_price: 1_600_000,
+            },
+        ]
+    );
+}
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::Pubkey;
+use solana_sdk::account::create_account_for_test;
+use solana_sdk::hash::Hash;
+use solana_sdk::slot_hashes::SlotHashes;
+use token_sale_client::lottery::{
+    draw_seed, find_slot_hash, format_hash, is_winner, parse_secret, secret_hash, shuffled_position, verify_draw,
+    winning_tickets,
+};
+use token_sale_client::{LotteryAccount, Tokens};
+
+fn drawn_lottery(secret: &[u8; 32], slot_hash: [u8; 32], tickets: u32, winners: u32) -> LotteryAccount {
+    LotteryAccount {
+        bump: 255,
+        pool: Pubkey::new_unique(),
+        registration_ends_at: 0,
+        winners,
+        allocation: Tokens::new(100),
+        tickets,
+        secret_hash: secret_hash(secret),
+        draw_slot: Some(10),
+        drawn: true,
+        slot_hash,
+        seed: draw_seed(secret, &slot_hash),
+        abandoned: false,
+        reserved: [0; 31],
+    }
+}
+
+#[test]
+fn draws_exactly_the_winners() {
+    for tickets in 1..40 {
+        for winners in 1..tickets + 3 {
+            let seed = draw_seed(&[tickets as u8; 32], &[winners as u8; 32]);
+            let drawn = (0..tickets).filter(|number| is_winner(&seed, *number, tickets, winners)).count();
+            assert_eq!(drawn as u32, winners.min(tickets));
+            assert!(!is_winner(&seed, tickets, tickets, winners));
+        }
+    }
+}
+
+#[test]
+fn shuffles_the_tickets() {
+    for tickets in 1..100 {
+        let seed = draw_seed(&[tickets as u8; 32], &[0; 32]);
+        let mut positions: Vec<u32> = (0..tickets).map(|number| shuffled_position(&seed, number, tickets)).collect();
+        positions.sort_unstable();
+        assert_eq!(positions, (0..tickets).collect::<Vec<_>>());
+    }
+
+    // The winners are spread over the tickets rather than registered one after another
+    let scattered = (0..20u8)
+        .map(|byte| draw_seed(&[byte; 32], &[0; 32]))
+        .filter(|seed| {
+            let winners: Vec<u32> = (0..100).filter(|number| is_winner(seed, *number, 100, 10)).collect();
+            winners.windows(2).filter(|pair| pair[1] - pair[0] > 1).count() > 1
+        })
+        .count();
+    assert_eq!(scattered, 20);
+}
+
+#[test]
+fn verifies_the_draw_with_the_revealed_secret() {
+    let secret = [7; 32];
+    let lottery = drawn_lottery(&secret, [3; 32], 10, 4);
+
+    assert!(verify_draw(&lottery, &secret));
+    assert!(!verify_draw(&lottery, &[8; 32]));
+    assert!(!verify_draw(&LotteryAccount { seed: [0; 32], ..drawn_lottery(&secret, [3; 32], 10, 4) }, &secret));
+
+    let winners = winning_tickets(&lottery);
+    assert_eq!(winners.len(), 4);
+    assert!(winners.iter().all(|number| is_winner(&lottery.seed, *number, 10, 4)));
+
+    // Another slot hash draws other winners from the same secret
+    assert_ne!(winning_tickets(&drawn_lottery(&secret, [4; 32], 10, 4)), winners);
+    assert!(winning_tickets(&LotteryAccount { drawn: false, ..lottery }).is_empty());
+}
+
+#[test]
+fn finds_the_slot_hash_in_the_sysvar() {
+    let hash = |byte| Hash::new_from_array([byte; 32]);
+    let slot_hashes = SlotHashes::new(&[(12, hash(2)), (11, hash(1)), (10, hash(0))]);
+    let account = create_account_for_test(&slot_hashes);
+
+    assert_eq!(find_slot_hash(&account.data, 11), Some([1; 32]));
+    assert_eq!(find_slot_hash(&account.data, 13), None);
+    assert_eq!(find_slot_hash(&account.data[..4], 11), None);
+}
+
+#[test]
+fn parses_the_secret_from_hex() {
+    let secret: [u8; 32] = std::array::from_fn(|index| index as u8 * 7);
+
+    assert_eq!(parse_secret(&format_hash(&secret)), Some(secret));
+    assert_eq!(parse_secret("00ff"), None);
+    assert_eq!(parse_secret(&"zz".repeat(32)), None);
+}
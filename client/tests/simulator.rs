@@ -0,0 +1,76 @@
+use token_sale_client::instruction::InitializeArgs;
+use token_sale_client::simulator::{simulate, BudgetDemand, FixedDemand, SimulatedRound};
+use token_sale_client::{Lamports, PoolAccount, Round, Tokens, UnsoldTokensPolicy};
+
+/// Three buying rounds, the last one cut short by the end of the sale
+fn args() -> InitializeArgs {
+    InitializeArgs {
+        sale_id: 0,
+        round_start_at: 0,
+        end_at: 500,
+        buying_duration: 100,
+        trading_duration: 100,
+        token_price: 1_000,
+        price_units: 1,
+        amount_to_sell: Tokens::new(10_000),
+        coeff_a: 1.5,
+        coeff_b: 100,
+        unsold_tokens_policy: UnsoldTokensPolicy::Burn,
+    }
+}
+
+#[test]
+fn sells_the_demanded_tokens_at_the_growing_price() {
+    let simulation = simulate(&args(), &mut FixedDemand(Tokens::new(1_000))).unwrap();
+
+    let round = |starts_at, ends_at, token_price: u64| SimulatedRound {
+        starts_at,
+        ends_at,
+        token_price,
+        tokens_sold: Tokens::new(1_000),
+        proceeds: Lamports::new(1_000 * token_price),
+    };
+    // 1_000 * 1.5 + 100, 1_600 * 1.5 + 100
+    assert_eq!(simulation.rounds, vec![round(0, 100, 1_000), round(200, 300, 1_600), round(400, 500, 2_500)]);
+    assert_eq!(simulation.tokens_sold, Tokens::new(3_000));
+    assert_eq!(simulation.unsold_tokens, Tokens::new(7_000));
+    assert_eq!(simulation.proceeds, Lamports::new(5_100_000));
+    assert_eq!(simulation.sold_out_in_round, None);
+    assert!(simulation.ended_during == Round::Buying);
+}
+
+#[test]
+fn stops_selling_when_sold_out() {
+    let simulation = simulate(&args(), &mut FixedDemand(Tokens::new(6_000))).unwrap();
+
+    let sold: Vec<u64> = simulation.rounds.iter().map(|round| round.tokens_sold.into()).collect();
+    assert_eq!(sold, vec![6_000, 4_000, 0]);
+    assert_eq!(simulation.unsold_tokens, Tokens::new(0));
+    assert_eq!(simulation.sold_out_in_round, Some(1));
+}
+
+#[test]
+fn budget_buys_fewer_tokens_as_the_price_grows() {
+    let simulation = simulate(&args(), &mut BudgetDemand(Lamports::new(1_000_000))).unwrap();
+
+    let sold: Vec<u64> = simulation.rounds.iter().map(|round| round.tokens_sold.into()).collect();
+    assert_eq!(sold, vec![1_000, 625, 400]);
+}
+
+#[test]
+fn ends_during_the_trading_round() {
+    let args = InitializeArgs { end_at: 350, ..args() };
+
+    let simulation = simulate(&args, &mut |_: &PoolAccount, round: usize| Tokens::new(round as u64)).unwrap();
+
+    assert_eq!(simulation.rounds.len(), 2);
+    assert_eq!(simulation.tokens_sold, Tokens::new(1));
+    assert!(simulation.ended_during == Round::Trading);
+}
+
+#[test]
+fn reports_the_overflow_of_the_price() {
+    let args = InitializeArgs { token_price: u64::MAX / 2, coeff_a: 3.0, ..args() };
+
+    assert!(simulate(&args, &mut FixedDemand(Tokens::new(0))).is_err());
+}
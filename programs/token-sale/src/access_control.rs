@@ -1,6 +1,11 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use anchor_spl::token_interface::TokenAccount;
 use crate::account::*;
+use crate::helper::load_interface_account;
+use crate::lottery;
 use crate::round::*;
+use crate::unsold_tokens_policy::UnsoldTokensPolicy;
 use crate::error::ErrorCode;
 
 // Is buying round running?
@@ -8,6 +13,10 @@ pub fn round_buying<'info>(
     pool: &Account<'info, PoolAccount>,
     clock: &Sysvar<'info, Clock>,
 ) -> Result<()> {
+    if pool.paused {
+        return err!(ErrorCode::SalePaused);
+    }
+
     if pool.current_round != Round::Buying {
         return err!(ErrorCode::NotBuyingRound);
     }
@@ -26,6 +35,10 @@ pub fn round_trading<'info>(
     pool: &Account<'info, PoolAccount>,
     clock: &Sysvar<'info, Clock>,
 ) -> Result<()> {
+    if pool.paused {
+        return err!(ErrorCode::SalePaused);
+    }
+
     if pool.current_round != Round::Trading {
         return err!(ErrorCode::NotTradingRound);
     }
@@ -39,6 +52,183 @@ pub fn round_trading<'info>(
     Ok(())
 }
 
+// Is the round the one the transaction expects, if it expects any?
+pub fn round_as_expected(pool: &PoolAccount, expected_round: Option<u32>) -> Result<()> {
+    match expected_round {
+        Some(round_number) if round_number != pool.round_number => err!(ErrorCode::UnexpectedRound),
+        _ => Ok(()),
+    }
+}
+
+// Is the purchase made by the transaction itself or by the trusted program, if the pool rejects the others?
+pub fn caller_allowed(pool: &PoolAccount, instructions: &AccountInfo) -> Result<()> {
+    if !pool.reject_cpi {
+        return Ok(());
+    }
+
+    // The instruction of the transaction is the one of the program that invoked `buy`, if it was not invoked directly
+    let current_index = load_current_index_checked(instructions)?;
+    let caller = load_instruction_at_checked(current_index as usize, instructions)?.program_id;
+
+    if caller != crate::ID && Some(caller) != pool.trusted_caller {
+        return err!(ErrorCode::UnknownCaller);
+    }
+
+    Ok(())
+}
+
+// Could the tickets of the lottery be registered?
+pub fn registration_open<'info>(
+    pool: &Account<'info, PoolAccount>,
+    lottery: &LotteryAccount,
+    clock: &Sysvar<'info, Clock>,
+) -> Result<()> {
+    if pool.paused {
+        return err!(ErrorCode::SalePaused);
+    }
+
+    if lottery.registration_ends_at <= clock.unix_timestamp || lottery.draw_slot.is_some() {
+        return err!(ErrorCode::RegistrationOver);
+    }
+
+    Ok(())
+}
+
+// Is the registration of the tickets over and the winners not drawn yet?
+pub fn registration_over(lottery: &LotteryAccount, clock: &Sysvar<Clock>) -> Result<()> {
+    if lottery.registration_ends_at > clock.unix_timestamp {
+        return err!(ErrorCode::RegistrationOpen);
+    }
+
+    if lottery.drawn {
+        return err!(ErrorCode::LotteryAlreadyDrawn);
+    }
+
+    if lottery.abandoned {
+        return err!(ErrorCode::LotteryAbandoned);
+    }
+
+    Ok(())
+}
+
+// Has the admin let the time to draw the winners pass?
+pub fn draw_timed_out(lottery: &LotteryAccount, clock: &Sysvar<Clock>) -> Result<()> {
+    registration_over(lottery, clock)?;
+
+    let hash_expired = lottery.draw_slot.is_some_and(|draw_slot| lottery::slot_hash_expired(draw_slot, clock.slot));
+    let never_closed = lottery.draw_slot.is_none()
+        && lottery.registration_ends_at + LotteryAccount::DRAW_TIMEOUT <= clock.unix_timestamp;
+
+    if !hash_expired && !never_closed {
+        return err!(ErrorCode::DrawNotTimedOut);
+    }
+
+    Ok(())
+}
+
+// Could an auction be started instead of the current round?
+pub fn can_start_auction<'info>(
+    pool: &Account<'info, PoolAccount>,
+    clock: &Sysvar<'info, Clock>,
+) -> Result<()> {
+    if pool.end_at <= clock.unix_timestamp {
+        return err!(ErrorCode::IDOOver);
+    }
+
+    if pool.current_round == Round::Commit || pool.current_round == Round::Reveal {
+        return err!(ErrorCode::AuctionRunning);
+    }
+
+    if pool.open_bids > 0 {
+        return err!(ErrorCode::BidsNotSettled);
+    }
+
+    Ok(())
+}
+
+// Are the bids of the auction committed?
+pub fn round_committing<'info>(
+    pool: &Account<'info, PoolAccount>,
+    clock: &Sysvar<'info, Clock>,
+) -> Result<()> {
+    if pool.paused {
+        return err!(ErrorCode::SalePaused);
+    }
+
+    if pool.current_round != Round::Commit {
+        return err!(ErrorCode::NotCommitRound);
+    }
+
+    let round_ends_at = pool.round_start_at + pool.commit_duration as i64;
+
+    if round_ends_at <= clock.unix_timestamp {
+        return err!(ErrorCode::CommitOver);
+    }
+
+    Ok(())
+}
+
+// Is it available to switch from the commit to the reveal round?
+pub fn can_switch_to_reveal_round<'info>(
+    pool: &Account<'info, PoolAccount>,
+    clock: &Sysvar<'info, Clock>,
+) -> Result<()> {
+    if pool.current_round != Round::Commit {
+        return err!(ErrorCode::NotCommitRound);
+    }
+
+    let round_ends_at = pool.round_start_at + pool.commit_duration as i64;
+
+    if round_ends_at > clock.unix_timestamp {
+        return err!(ErrorCode::CommitRunning);
+    }
+
+    Ok(())
+}
+
+// Are the bids of the auction revealed?
+pub fn round_revealing<'info>(
+    pool: &Account<'info, PoolAccount>,
+    clock: &Sysvar<'info, Clock>,
+) -> Result<()> {
+    if pool.paused {
+        return err!(ErrorCode::SalePaused);
+    }
+
+    if pool.current_round != Round::Reveal {
+        return err!(ErrorCode::NotRevealRound);
+    }
+
+    let round_ends_at = pool.round_start_at + pool.reveal_duration as i64;
+
+    if round_ends_at <= clock.unix_timestamp {
+        return err!(ErrorCode::RevealOver);
+    }
+
+    Ok(())
+}
+
+// Is the reveal of the bids over, so they could be settled?
+pub fn auction_over<'info>(
+    pool: &Account<'info, PoolAccount>,
+    clock: &Sysvar<'info, Clock>,
+) -> Result<()> {
+    // Nobody switched to the reveal round in time, so no bid could be revealed any more
+    let commit_and_reveal_end_at = pool.round_start_at + pool.commit_duration as i64 + pool.reveal_duration as i64;
+
+    if pool.current_round == Round::Commit && commit_and_reveal_end_at > clock.unix_timestamp {
+        return err!(ErrorCode::AuctionRunning);
+    }
+
+    let reveal_ends_at = pool.round_start_at + pool.reveal_duration as i64;
+
+    if pool.current_round == Round::Reveal && reveal_ends_at > clock.unix_timestamp {
+        return err!(ErrorCode::AuctionRunning);
+    }
+
+    Ok(())
+}
+
 // Is it available to switch from buying to trading round?
 pub fn can_switch_to_trading_round<'info>(
     pool: &Account<'info, PoolAccount>,
@@ -52,6 +242,10 @@ pub fn can_switch_to_trading_round<'info>(
         return err!(ErrorCode::AlreadyTrading);
     }
 
+    if pool.current_round != Round::Buying {
+        return auction_over(pool, clock);
+    }
+
     let round_ends_at = pool.round_start_at + pool.buying_duration as i64;
 
     if round_ends_at < clock.unix_timestamp {
@@ -74,6 +268,10 @@ pub fn can_switch_to_buying_round<'info>(
         return err!(ErrorCode::AlreadyBuying);
     }
 
+    if pool.current_round != Round::Trading {
+        return err!(ErrorCode::NotTradingRound);
+    }
+
     let round_ends_at = pool.round_start_at + pool.trading_duration as i64;
 
     if round_ends_at < clock.unix_timestamp {
@@ -83,6 +281,81 @@ pub fn can_switch_to_buying_round<'info>(
     Ok(())
 }
 
+// Is it between the buying rounds?
+pub fn buying_not_running<'info>(
+    pool: &Account<'info, PoolAccount>,
+    clock: &Sysvar<'info, Clock>,
+) -> Result<()> {
+    if pool.end_at <= clock.unix_timestamp {
+        return err!(ErrorCode::IDOOver);
+    }
+
+    let buying_ends_at = pool.round_start_at + pool.buying_duration as i64;
+
+    if pool.current_round == Round::Buying && buying_ends_at > clock.unix_timestamp {
+        return err!(ErrorCode::BuyingRunning);
+    }
+
+    Ok(())
+}
+
+// Could the config of the sale be changed?
+pub fn can_update_config<'info>(
+    pool: &Account<'info, PoolAccount>,
+    clock: &Sysvar<'info, Clock>,
+) -> Result<()> {
+    if pool.end_at <= clock.unix_timestamp {
+        return err!(ErrorCode::IDOOver);
+    }
+
+    Ok(())
+}
+
+// Does the IDO last for at least one full circle of buying and trading rounds?
+pub fn valid_schedule(now: i64, end_at: i64, buying_duration: u32, trading_duration: u32) -> Result<()> {
+    let full_cycle = now + buying_duration as i64 + trading_duration as i64;
+    require!(end_at >= full_cycle, ErrorCode::EndsBeforeFullCircle);
+
+    Ok(())
+}
+
+// Do the coefficients always give a positive price in the next buying round?
+pub fn valid_price_coefficients(coeff_a: f32, coeff_b: u32) -> Result<()> {
+    require!(coeff_a.is_finite() && coeff_a >= 0.0, ErrorCode::InvalidPriceCoefficients);
+    require!(coeff_a > 0.0 || coeff_b > 0, ErrorCode::InvalidPriceCoefficients);
+
+    Ok(())
+}
+
+// Are the unsold tokens sent to a token account of the selling mint?
+pub fn valid_unsold_tokens_destination(
+    policy: &UnsoldTokensPolicy,
+    destination: &AccountInfo,
+    selling_mint: &Pubkey,
+) -> Result<()> {
+    if *policy != UnsoldTokensPolicy::SendToDestination {
+        return Ok(());
+    }
+
+    let token_account = load_interface_account::<TokenAccount>(destination)
+        .map_err(|_| error!(ErrorCode::InvalidUnsoldTokensDestination))?;
+    require!(token_account.mint == *selling_mint, ErrorCode::InvalidUnsoldTokensDestination);
+
+    Ok(())
+}
+
+// Is the sale over, so anyone could return the tokens of an order to its owner?
+pub fn can_reclaim_order<'info>(
+    pool: &Account<'info, PoolAccount>,
+    clock: &Sysvar<'info, Clock>,
+) -> Result<()> {
+    if pool.end_at > clock.unix_timestamp {
+        return err!(ErrorCode::IDONotOver);
+    }
+
+    Ok(())
+}
+
 pub fn can_terminate<'info>(
     pool: &Account<'info, PoolAccount>,
     clock: &Sysvar<'info, Clock>,
@@ -91,5 +364,13 @@ pub fn can_terminate<'info>(
         return err!(ErrorCode::IDONotOver);
     }
 
+    if pool.open_orders > 0 {
+        return err!(ErrorCode::OrdersStillOpen);
+    }
+
+    if pool.open_bids > 0 {
+        return err!(ErrorCode::BidsNotSettled);
+    }
+
     Ok(())
 }
@@ -1,13 +1,23 @@
 use anchor_lang::prelude::*;
 use crate::Round;
-use crate::currency::Tokens;
+use crate::auction::RankedBid;
+use crate::currency::{Lamports, Tokens};
+use crate::error::ErrorCode;
+use crate::lottery;
+use crate::tier::Tier;
+use crate::unsold_tokens_policy::UnsoldTokensPolicy;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
 
 /// The main state of the program
 #[account]
 pub struct PoolAccount {
     pub bump: u8,
-    /// The owner of the token sale program.
-    pub owner: Pubkey,
+    /// Can change the config of the sale, pause it, update the roles and terminate the sale.
+    pub admin: Pubkey,
+    /// Receives the lamports withdrawn from the pool.
+    pub treasury: Pubkey,
+    /// Can switch the rounds.
+    pub operator: Pubkey,
     /// Describes the type of the selling tokens.
     /// The mint itself does not need to be under control of the token sale owner.
     pub selling_mint: Pubkey,
@@ -22,7 +32,7 @@ pub struct PoolAccount {
     /// Seconds to pass before the end of the trade round
     pub trading_duration: u32,
     /// Current price of the selling token. Could be changed after trade rounds.
-    /// Represents the amount of lamports for the one minimal part of the token
+    /// Represents the amount of lamports for `price_units` minimal parts of the token
     pub token_price: u64,
     /// Could be selling round or trading round
     pub current_round: Round,
@@ -32,10 +42,156 @@ pub struct PoolAccount {
     /// using the formula: next_token_price = token_price * coeff_a + coeff_b
     pub coeff_a: f32,
     pub coeff_b: u32,
+    /// While the sale is paused nobody can buy tokens or trade them
+    pub paused: bool,
+    /// All the tokens put into `vault_selling` minus the withdrawn unsold tokens
+    pub total_supply: Tokens,
+    /// The tokens bought from the pool
+    pub tokens_sold: Tokens,
+    /// What to do with the unsold tokens upon termination
+    pub unsold_tokens_policy: UnsoldTokensPolicy,
+    /// The token account that receives the unsold tokens with the `SendToDestination` policy
+    pub unsold_tokens_destination: Pubkey,
+    /// The amount of placed orders that are not closed yet.
+    /// The IDO could not be terminated while sellers have their tokens in the orders.
+    pub open_orders: u32,
+    /// The layout of the account, older pools are brought to `PoolAccount::VERSION` by `migrate_pool`
+    pub version: u8,
+    /// Tells apart the sales of the same mint, see `PoolAccount::sale_seed`
+    pub sale_id: u64,
+    /// `buy` mints the bought tokens instead of taking them from `vault_selling`, see `enable_minting`
+    pub mint_on_demand: bool,
+    /// The most tokens the pool could mint, `None` if the minting is not capped
+    pub max_supply: Option<Tokens>,
+    /// Whether `terminate` revokes the mint authority instead of handing it to the admin
+    pub revoke_mint_authority: bool,
+    /// The minimal parts of the token `token_price` and `coeff_b` are paid for,
+    /// zero in the pools initialized before it was added, which price a single minimal part
+    pub price_units: u64,
+    /// Counts the switches of the rounds, so a transaction could be bound to the round it was signed in
+    pub round_number: u32,
+    /// The most tokens `buy` sells within a slot, not limited if zero
+    pub max_tokens_per_slot: Tokens,
+    /// The slot of the last purchase and the tokens sold in it, counted towards `max_tokens_per_slot`
+    pub last_buy_slot: u64,
+    pub tokens_sold_in_slot: Tokens,
+    /// Seconds a buyer waits between the purchases, see `BuyerAccount`
+    pub buy_cooldown: u32,
+    /// Whether `buy` rejects being invoked by another program than `trusted_caller`
+    pub reject_cpi: bool,
+    /// The program allowed to invoke `buy` when `reject_cpi` is set, `None` if no program is
+    pub trusted_caller: Option<Pubkey>,
+    /// The governance tokens staked for the allocation tiers, `None` if anyone buys any amount
+    pub stake_mint: Option<Pubkey>,
+    /// Ordered by `min_stake`, the unused tiers are zeroed, see `PoolAccount::allocation`
+    pub tiers: [Tier; 4],
+    /// The `LotteryAccount` of the fair launch, only its winners buy. The default key if anyone buys.
+    pub lottery: Pubkey,
+    /// Seconds the bids of the auction are committed and revealed for, see `start_auction`
+    pub commit_duration: u32,
+    pub reveal_duration: u32,
+    /// The tokens of `vault_selling` held for the bids not settled yet, neither sold nor withdrawn meanwhile
+    pub auction_tokens: Tokens,
+    /// The bids committed and not settled yet.
+    /// The IDO could not be terminated and no auction started while there are any.
+    pub open_bids: u32,
+    /// The last page of the order registry, `place_order` opens the next one, see `OrderRegistry::page_seed`
+    pub last_registry_page: u32,
+    /// Spare bytes for the fields of the following versions
+    pub reserved: [u8; 8],
 }
 
 impl PoolAccount {
-    pub const SPACE: usize = 1 + 32 * 4 + 8 + 4 + 4 + 8 + 1 + 8 + 4 + 4;
+    pub const SPACE: usize = 1 + 32 * 6 + 8 + 4 + 4 + 8 + 1 + 8 + 4 + 4 + 1 + 8 + 8 + 1 + 32 + 4 + 1 + 8 + 1 + 9 + 1 + 8 + 4 + 8 + 8 + 8 + 4 + 1 + 33 + 33 + 16 * 4 + 32 + 4 + 4 + 8 + 4 + 4 + 8;
+    pub const VERSION: u8 = 3;
+
+    /// The seed of the pool address that follows the selling mint.
+    /// The first sale of a mint keeps the address derived from the mint alone.
+    pub fn sale_seed(sale_id: u64) -> Vec<u8> {
+        match sale_id {
+            0 => vec![],
+            _ => sale_id.to_le_bytes().to_vec(),
+        }
+    }
+
+    /// The tokens `buy` could still give out, either minted or taken from `vault_selling`
+    pub fn tokens_for_sale(&self, vault_selling: Tokens) -> Tokens {
+        match (self.mint_on_demand, self.max_supply) {
+            (false, _) => self.unreserved_tokens(vault_selling),
+            (true, Some(max_supply)) if max_supply > self.tokens_sold => max_supply - self.tokens_sold,
+            (true, Some(_)) => Tokens::new(0),
+            (true, None) => Tokens::new(u64::MAX),
+        }
+    }
+
+    /// The tokens of `vault_selling` not held for the bids of the auction
+    pub fn unreserved_tokens(&self, vault_selling: Tokens) -> Tokens {
+        match vault_selling > self.auction_tokens {
+            true => vault_selling - self.auction_tokens,
+            false => Tokens::new(0),
+        }
+    }
+
+    /// Seconds the current round lasts for
+    pub fn round_duration(&self) -> u32 {
+        match self.current_round {
+            Round::Buying => self.buying_duration,
+            Round::Trading => self.trading_duration,
+            Round::Commit => self.commit_duration,
+            Round::Reveal => self.reveal_duration,
+        }
+    }
+
+    /// The tokens `buy` could still sell in the slot without exceeding `max_tokens_per_slot`
+    pub fn tokens_left_in_slot(&self, slot: u64) -> Tokens {
+        let sold = match slot == self.last_buy_slot {
+            true => self.tokens_sold_in_slot,
+            false => Tokens::new(0),
+        };
+        match self.max_tokens_per_slot {
+            max if max == Tokens::new(0) => Tokens::new(u64::MAX),
+            max if max > sold => max - sold,
+            _ => Tokens::new(0),
+        }
+    }
+
+    /// Counts the tokens sold in the slot towards `max_tokens_per_slot`
+    pub fn record_slot_sale(&mut self, slot: u64, tokens_amount: Tokens) -> Result<()> {
+        require!(self.tokens_left_in_slot(slot) >= tokens_amount, ErrorCode::SlotLimitReached);
+
+        if slot != self.last_buy_slot {
+            self.last_buy_slot = slot;
+            self.tokens_sold_in_slot = Tokens::new(0);
+        }
+        self.tokens_sold_in_slot += tokens_amount;
+
+        Ok(())
+    }
+
+    /// The most tokens a wallet with the stake could buy in a tiered sale, `None` if the sale is not tiered
+    pub fn allocation(&self, stake: Tokens) -> Option<Tokens> {
+        self.stake_mint?;
+        let reached = self.tiers.iter().filter(|tier| tier.allocation > Tokens::new(0) && tier.min_stake <= stake);
+        Some(reached.map(|tier| tier.allocation).max().unwrap_or(Tokens::new(0)))
+    }
+
+    /// Whether the purchases are recorded in the `BuyerAccount` of each buyer:
+    /// the sale has a cooldown, allocation tiers or a lottery
+    pub fn tracks_buyers(&self) -> bool {
+        self.buy_cooldown > 0 || self.stake_mint.is_some() || self.lottery != Pubkey::default()
+    }
+
+    /// The token account that has to receive the unsold tokens, `None` if they have to be burned.
+    /// `token_program` owns the selling mint.
+    pub fn unsold_tokens_receiver(&self, token_program: &Pubkey) -> Option<Pubkey> {
+        match self.unsold_tokens_policy {
+            UnsoldTokensPolicy::Burn => None,
+            UnsoldTokensPolicy::ReturnToAdmin => Some(
+                get_associated_token_address_with_program_id(&self.admin, &self.selling_mint, token_program)
+            ),
+            UnsoldTokensPolicy::SendToDestination => Some(self.unsold_tokens_destination),
+        }
+    }
 }
 
 #[account]
@@ -53,10 +209,244 @@ pub struct Order {
     /// The temp storage with tokens for sale
     pub token_vault: Pubkey,
     pub bump: u8,
+    /// The pool the order is placed in
+    pub pool: Pubkey,
+    /// The layout of the account, older orders are brought to `Order::VERSION` by `migrate_pool`
+    pub version: u8,
+    /// Is the pool in the seeds of the order? The orders placed before are seeded by their owner only,
+    /// so a seller could have one order across all the pools
+    pub pool_seeded: bool,
+    /// The page of the order registry the order is listed in
+    pub registry_page: u32,
+    /// Spare bytes for the fields of the following versions
+    pub reserved: [u8; 27],
 }
 
 impl Order {
-    pub const SPACE: usize = 1 + 8 + 32 + 8 + 8 + 32 + 1;
+    pub const SPACE: usize = 1 + 8 + 32 + 8 + 8 + 32 + 1 + 32 + 1 + 1 + 4 + 27;
+    pub const VERSION: u8 = 1;
     pub const PDA_KEY: &'static str = "order";
     pub const PDA_SEED: & 'static [u8] = Self::PDA_KEY.as_bytes();
+
+    /// The seeds the order signs for its vault with
+    pub fn signer_seeds(&self) -> Vec<&[u8]> {
+        let bump = std::slice::from_ref(&self.bump);
+        if self.pool_seeded {
+            vec![Self::PDA_SEED, self.pool.as_ref(), self.owner.as_ref(), bump]
+        } else {
+            vec![Self::PDA_SEED, self.owner.as_ref(), bump]
+        }
+    }
+
+    /// Is `address` the PDA of the order? Checked in place of the `seeds` constraint, as they depend on `pool_seeded`
+    pub fn is_at(&self, address: &Pubkey) -> bool {
+        Pubkey::create_program_address(&self.signer_seeds(), &crate::ID) == Ok(*address)
+    }
+}
+
+/// The purchases of a wallet in a pool, created on its first `buy` if the pool `tracks_buyers`
+/// and closed by `close_buyer_account`.
+/// Keeps the wallet waiting `PoolAccount::buy_cooldown` seconds between the purchases.
+#[account]
+pub struct BuyerAccount {
+    pub bump: u8,
+    pub pool: Pubkey,
+    pub buyer: Pubkey,
+    /// UNIX timestamp of the last purchase
+    pub last_buy_at: i64,
+    /// The tokens bought from the pool, limited by the allocation of a tiered sale
+    pub tokens_bought: Tokens,
+    /// Spare bytes for the fields of the following versions
+    pub reserved: [u8; 24],
+}
+
+impl BuyerAccount {
+    pub const SPACE: usize = 1 + 32 + 32 + 8 + 8 + 24;
+    pub const PDA_KEY: &'static str = "buyer";
+    pub const PDA_SEED: & 'static [u8] = Self::PDA_KEY.as_bytes();
+}
+
+/// The governance tokens a wallet locked in `stake_vault`, its associated token account,
+/// to reach an allocation tier of the pool. The tokens unlock after the end of the sale.
+#[account]
+pub struct StakeAccount {
+    pub bump: u8,
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    /// The tokens of `PoolAccount::stake_mint` staked
+    pub amount: Tokens,
+    /// UNIX timestamp `unstake` is allowed after, the `end_at` of the pool.
+    /// Kept here as the pool is closed by `terminate`.
+    pub unlock_at: i64,
+    /// Spare bytes for the fields of the following versions
+    pub reserved: [u8; 32],
+}
+
+impl StakeAccount {
+    pub const SPACE: usize = 1 + 32 + 32 + 8 + 8 + 32;
+    pub const PDA_KEY: &'static str = "stake";
+    pub const PDA_SEED: & 'static [u8] = Self::PDA_KEY.as_bytes();
+}
+
+/// The fair launch of the pool: the wallets register tickets until `registration_ends_at`, then the winners are drawn
+/// from the secret committed by the admin and the hash of the slot the registration was closed in.
+#[account]
+pub struct LotteryAccount {
+    pub bump: u8,
+    pub pool: Pubkey,
+    /// UNIX timestamp the registration of the tickets closes at
+    pub registration_ends_at: i64,
+    /// The tickets drawn, all the tickets win if there are fewer of them
+    pub winners: u32,
+    /// The tokens every winner could buy
+    pub allocation: Tokens,
+    /// The tickets registered, numbered from zero
+    pub tickets: u32,
+    /// `lottery::secret_hash` of the secret revealed by `draw_lottery`
+    pub secret_hash: [u8; 32],
+    /// The slot `close_registration` was called in, `None` while the registration is open.
+    /// The lottery could only be abandoned once its hash expires undrawn, see `lottery::slot_hash_expired`.
+    pub draw_slot: Option<u64>,
+    /// Whether the winners are drawn
+    pub drawn: bool,
+    /// The hash of `draw_slot` and the seed drawn from it, see `lottery::draw_seed` and `lottery::is_winner`
+    pub slot_hash: [u8; 32],
+    pub seed: [u8; 32],
+    /// Whether the lottery was abandoned by `abandon_lottery`, the pool sells to everyone then
+    pub abandoned: bool,
+    /// Spare bytes for the fields of the following versions
+    pub reserved: [u8; 31],
+}
+
+impl LotteryAccount {
+    pub const SPACE: usize = 1 + 32 + 8 + 4 + 8 + 4 + 32 + 9 + 1 + 32 + 32 + 1 + 31;
+    pub const PDA_KEY: &'static str = "lottery";
+    pub const PDA_SEED: & 'static [u8] = Self::PDA_KEY.as_bytes();
+    /// Seconds after the end of the registration it has to be closed in,
+    /// anyone could abandon the lottery after them
+    pub const DRAW_TIMEOUT: i64 = 60 * 60;
+
+    /// Whether the ticket `number` won, false until the winners are drawn
+    pub fn is_winner(&self, number: u32) -> bool {
+        self.drawn && lottery::is_winner(&self.seed, number, self.tickets, self.winners)
+    }
+}
+
+/// The ticket of a wallet in the lottery of the pool, traded for an `AllocationAccount` if it wins
+#[account]
+pub struct TicketAccount {
+    pub bump: u8,
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    /// The order the ticket was registered in
+    pub number: u32,
+    /// Spare bytes for the fields of the following versions
+    pub reserved: [u8; 16],
+}
+
+impl TicketAccount {
+    pub const SPACE: usize = 1 + 32 + 32 + 4 + 16;
+    pub const PDA_KEY: &'static str = "ticket";
+    pub const PDA_SEED: & 'static [u8] = Self::PDA_KEY.as_bytes();
+}
+
+/// The tokens a winner of the lottery could buy, `buy` sells nothing to the wallets without it
+#[account]
+pub struct AllocationAccount {
+    pub bump: u8,
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    /// `LotteryAccount::allocation` at the time of the claim
+    pub amount: Tokens,
+    /// Spare bytes for the fields of the following versions
+    pub reserved: [u8; 16],
+}
+
+impl AllocationAccount {
+    pub const SPACE: usize = 1 + 32 + 32 + 8 + 16;
+    pub const PDA_KEY: &'static str = "allocation";
+    pub const PDA_SEED: & 'static [u8] = Self::PDA_KEY.as_bytes();
+}
+
+/// The sealed-bid auction of the pool, reused by the following auctions.
+/// Keeps the highest revealed bids, the tokens go to them at their own prices.
+#[account]
+pub struct AuctionAccount {
+    pub bump: u8,
+    pub pool: Pubkey,
+    /// The tokens of `vault_selling` the auction sells
+    pub tokens: Tokens,
+    /// Ordered by the price from the highest, see `auction::rank`
+    pub bids: Vec<RankedBid>,
+}
+
+impl AuctionAccount {
+    pub const MAX_BIDS: usize = 64;
+    pub const SPACE: usize = 1 + 32 + 8 + 4 + (32 + 8 + 8) * Self::MAX_BIDS;
+    pub const PDA_KEY: &'static str = "auction";
+    pub const PDA_SEED: & 'static [u8] = Self::PDA_KEY.as_bytes();
+}
+
+/// The sealed bid of a wallet in the auction of the pool. Holds the deposit in lamports
+/// until `settle_bid` pays for the tokens won and gives the rest back.
+#[account]
+pub struct BidAccount {
+    pub bump: u8,
+    pub pool: Pubkey,
+    pub bidder: Pubkey,
+    /// `auction::bid_commitment` of the bid
+    pub commitment: [u8; 32],
+    /// The lamports deposited on top of the rent, the most the bid could pay
+    pub deposit: Lamports,
+    /// Whether the bid is revealed, only the revealed bids win
+    pub revealed: bool,
+    /// The tokens asked for and the price offered, known once revealed
+    pub tokens: Tokens,
+    pub price: u64,
+    /// Spare bytes for the fields of the following versions
+    pub reserved: [u8; 16],
+}
+
+impl BidAccount {
+    pub const SPACE: usize = 1 + 32 + 32 + 32 + 8 + 1 + 8 + 8 + 16;
+    pub const PDA_KEY: &'static str = "bid";
+    pub const PDA_SEED: & 'static [u8] = Self::PDA_KEY.as_bytes();
+}
+
+/// The addresses of the active orders of the pool, so the market could be listed with a few account reads.
+/// An order leaves the registry when all its tokens are sold or when it is closed.
+/// The registry is split in pages of `MAX_ORDERS` orders and `place_order` opens a new page once they are full,
+/// so nobody could keep the others from placing orders by filling the registry.
+#[account]
+pub struct OrderRegistry {
+    pub bump: u8,
+    /// The pool the orders are placed in
+    pub pool: Pubkey,
+    pub orders: Vec<Pubkey>,
+}
+
+impl OrderRegistry {
+    pub const MAX_ORDERS: usize = 100;
+    pub const SPACE: usize = 1 + 32 + 4 + 32 * Self::MAX_ORDERS;
+    pub const PDA_KEY: &'static str = "order_registry";
+    pub const PDA_SEED: & 'static [u8] = Self::PDA_KEY.as_bytes();
+
+    /// The seed of the page address that follows the pool.
+    /// The first page keeps the address derived from the pool alone.
+    pub fn page_seed(page: u32) -> Vec<u8> {
+        match page {
+            0 => vec![],
+            _ => page.to_le_bytes().to_vec(),
+        }
+    }
+
+    pub fn add(&mut self, order: Pubkey) -> Result<()> {
+        require!(self.orders.len() < Self::MAX_ORDERS, ErrorCode::OrderRegistryFull);
+        self.orders.push(order);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, order: &Pubkey) {
+        self.orders.retain(|active| active != order);
+    }
 }
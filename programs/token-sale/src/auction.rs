@@ -0,0 +1,51 @@
+//! The ranking of the sealed bids, kept free of accounts so anyone could check the fills off-chain.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::currency::Tokens;
+
+/// A revealed bid in the book of `AuctionAccount`
+#[derive(PartialEq, Eq, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct RankedBid {
+    /// The `BidAccount` of the bid
+    pub bid: Pubkey,
+    /// Lamports for `price_units` minimal parts of the token, as `PoolAccount::token_price`
+    pub price: u64,
+    pub tokens: Tokens,
+}
+
+/// The commitment to the bid published by `commit_bid`. The bidder is hashed in,
+/// so a bid copied from another wallet never matches.
+pub fn bid_commitment(bidder: &Pubkey, tokens: Tokens, price: u64, salt: &[u8; 32]) -> [u8; 32] {
+    hashv(&[bidder.as_ref(), &u64::from(tokens).to_le_bytes(), &price.to_le_bytes(), salt]).to_bytes()
+}
+
+/// Puts the bid after the ones with the same or a higher price, so the earlier reveal wins a tie,
+/// and drops the bids left without tokens. At most `max_bids` of the highest bids are kept.
+pub fn rank(bids: &mut Vec<RankedBid>, bid: RankedBid, tokens_for_sale: Tokens, max_bids: usize) {
+    let position = bids.iter().position(|ranked| ranked.price < bid.price).unwrap_or(bids.len());
+    bids.insert(position, bid);
+
+    let mut taken = 0u64;
+    let filled = bids
+        .iter()
+        .take_while(|ranked| {
+            let left = taken < u64::from(tokens_for_sale);
+            taken = taken.saturating_add(u64::from(ranked.tokens));
+            left
+        })
+        .count();
+    bids.truncate(filled.min(max_bids));
+}
+
+/// The tokens the bid gets: the higher bids are filled first, the last bid filled may get only a part of its tokens
+pub fn filled(bids: &[RankedBid], tokens_for_sale: Tokens, bid: &Pubkey) -> Tokens {
+    let mut left = u64::from(tokens_for_sale);
+    for ranked in bids {
+        let fill = u64::from(ranked.tokens).min(left);
+        if ranked.bid == *bid {
+            return Tokens::new(fill);
+        }
+        left -= fill;
+    }
+    Tokens::new(0)
+}
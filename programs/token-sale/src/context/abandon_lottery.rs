@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use crate::account::*;
+
+/// Could be called by anyone once the time to draw the winners is over
+#[derive(Accounts)]
+pub struct AbandonLottery<'info> {
+    #[account(
+        mut,
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    #[account(
+        mut,
+        seeds = [LotteryAccount::PDA_SEED, pool_account.key().as_ref()],
+        bump = lottery_account.bump,
+    )]
+    pub lottery_account: Box<Account<'info, LotteryAccount>>,
+    pub clock: Sysvar<'info, Clock>,
+}
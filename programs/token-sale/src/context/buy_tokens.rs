@@ -1,21 +1,34 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{TokenAccount, Token, Mint, transfer, Transfer};
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+use anchor_spl::token_interface::{
+    TokenAccount,
+    TokenInterface,
+    Mint,
+    TransferChecked,
+    transfer_checked,
+    MintTo,
+    mint_to,
+};
 use crate::account::*;
-use crate::Tokens;
+use crate::error::ErrorCode;
+use crate::helper::{create_pda_account, load_account, send_lamports, transfer_fee};
+use crate::{Lamports, Tokens};
 
 #[derive(Accounts)]
 pub struct BuyTokens<'info> {
     #[account(
         mut,
-        seeds = [selling_mint.to_account_info().key.as_ref()],
+        seeds = [selling_mint.to_account_info().key.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
         bump = pool_account.bump,
         has_one = selling_mint,
         has_one = vault_selling,
     )]
     pub pool_account: Account<'info, PoolAccount>,
-    pub selling_mint: Box<Account<'info, Mint>>,
+    /// Mutable as the pool mints the bought tokens if `pool_account.mint_on_demand`
     #[account(mut)]
-    pub vault_selling: Account<'info, TokenAccount>,
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub vault_selling: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub buyer: Signer<'info>,
     #[account(
@@ -23,24 +36,174 @@ pub struct BuyTokens<'info> {
         constraint = buyer_token_account.owner == buyer.key(),
         constraint = buyer_token_account.mint == selling_mint.key(),
     )]
-    pub buyer_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the purchases of the buyer, created by `BuyTokens::record_purchase` at the buyer's expense
+    /// if the pool `tracks_buyers`, might not exist. `close_buyer_account` refunds it after the sale
+    #[account(mut, seeds = [BuyerAccount::PDA_SEED, pool_account.key().as_ref(), buyer.key().as_ref()], bump)]
+    pub buyer_account: UncheckedAccount<'info>,
+    /// CHECK: the stake of the buyer, read by `BuyTokens::stake` in a tiered sale, might not exist
+    #[account(seeds = [StakeAccount::PDA_SEED, pool_account.key().as_ref(), buyer.key().as_ref()], bump)]
+    pub stake_account: UncheckedAccount<'info>,
+    /// CHECK: the allocation the buyer won in the lottery, read by `BuyTokens::lottery_allocation`, might not exist
+    #[account(seeds = [AllocationAccount::PDA_SEED, pool_account.key().as_ref(), buyer.key().as_ref()], bump)]
+    pub allocation_account: UncheckedAccount<'info>,
+    /// CHECK: the address is the one of the instructions sysvar, read by `caller_allowed`
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub clock: Sysvar<'info, Clock>,
 }
 
 impl<'info> BuyTokens<'info> {
-    pub fn send_tokens_from_pool_to_buyer(&self, tokens_amount: Tokens) -> Result<()> {
+    /// Charges the buyer and gives out the tokens, minted or taken from `vault_selling`.
+    /// The purchase counts towards the limit of the slot, the cooldown and the allocation of the buyer.
+    /// Returns the transfer fee the mint withholds from the buyer, the vault gives out all the tokens paid for.
+    pub fn sell(&mut self, tokens_amount: Tokens, lamports_amount: Lamports, buyer_bump: u8) -> Result<Tokens> {
+        self.pool_account.record_slot_sale(self.clock.slot, tokens_amount)?;
+        self.record_purchase(buyer_bump, tokens_amount)?;
+
+        let buyer_lamports = Lamports::new(self.buyer.lamports());
+        require!(buyer_lamports >= lamports_amount, ErrorCode::InsufficientLamportsToBuyTokens);
+
+        send_lamports(self.buyer.to_account_info(), self.pool_account.to_account_info(), lamports_amount)?;
+        self.pool_account.tokens_sold += tokens_amount;
+
+        if self.pool_account.mint_on_demand {
+            self.pool_account.total_supply += tokens_amount;
+            self.mint_tokens_to_buyer(tokens_amount)?;
+            Ok(Tokens::new(0))
+        } else {
+            self.send_tokens_from_pool_to_buyer(tokens_amount)
+        }
+    }
+
+    /// The tokens the buyer could still buy within the allocation of the tier and the one won in the lottery,
+    /// `u64::MAX` if the sale is neither tiered nor has a lottery
+    pub fn allocation_left(&self) -> Result<Tokens> {
+        let allocation = match (self.pool_account.allocation(self.stake()?), self.lottery_allocation()?) {
+            (None, None) => return Ok(Tokens::new(u64::MAX)),
+            (tier, lottery) => tier.into_iter().chain(lottery).min().expect("One of the allocations exists"),
+        };
+        let tokens_bought = self.tokens_bought()?;
+        match allocation > tokens_bought {
+            true => Ok(allocation - tokens_bought),
+            false => Ok(Tokens::new(0)),
+        }
+    }
+
+    /// The tokens recorded in the buyer account, zero if the buyer has none yet
+    fn tokens_bought(&self) -> Result<Tokens> {
+        if self.buyer_account.owner != &crate::ID {
+            return Ok(Tokens::new(0));
+        }
+        Ok(load_account::<BuyerAccount>(&self.buyer_account)?.tokens_bought)
+    }
+
+    /// The tokens staked by the buyer, zero if the buyer has not staked
+    fn stake(&self) -> Result<Tokens> {
+        if self.stake_account.owner != &crate::ID {
+            return Ok(Tokens::new(0));
+        }
+        Ok(load_account::<StakeAccount>(&self.stake_account)?.amount)
+    }
+
+    /// The tokens won by the buyer in the lottery of the pool, zero if the buyer has not won,
+    /// `None` if the pool has no lottery
+    fn lottery_allocation(&self) -> Result<Option<Tokens>> {
+        if self.pool_account.lottery == Pubkey::default() {
+            return Ok(None);
+        }
+        if self.allocation_account.owner != &crate::ID {
+            return Ok(Some(Tokens::new(0)));
+        }
+        Ok(Some(load_account::<AllocationAccount>(&self.allocation_account)?.amount))
+    }
+
+    /// Holds the buyer to `buy_cooldown` since the last purchase and to the allocations of the tier and the lottery.
+    /// Nothing is recorded unless the pool `tracks_buyers`, the buyer account is created on the first purchase.
+    fn record_purchase(&mut self, buyer_bump: u8, tokens_amount: Tokens) -> Result<()> {
+        require!(self.allocation_left()? >= tokens_amount, ErrorCode::AllocationExceeded);
+        if !self.pool_account.tracks_buyers() {
+            return Ok(());
+        }
+
+        let now = self.clock.unix_timestamp;
+        let mut buyer_account = if self.buyer_account.owner == &crate::ID {
+            let buyer_account = load_account::<BuyerAccount>(&self.buyer_account)?;
+            let wait_until = buyer_account.last_buy_at + self.pool_account.buy_cooldown as i64;
+            require!(now >= wait_until, ErrorCode::BuyCooldown);
+            buyer_account
+        } else {
+            self.create_buyer_account(buyer_bump)?
+        };
+        buyer_account.last_buy_at = now;
+        buyer_account.tokens_bought += tokens_amount;
+
+        buyer_account.try_serialize(&mut &mut self.buyer_account.try_borrow_mut_data()?[..])
+    }
+
+    fn create_buyer_account(&self, buyer_bump: u8) -> Result<BuyerAccount> {
+        let pool = self.pool_account.key();
+        let buyer = self.buyer.key();
+        let seeds = [BuyerAccount::PDA_SEED, pool.as_ref(), buyer.as_ref(), &[buyer_bump]];
+        create_pda_account(
+            self.buyer_account.to_account_info(),
+            self.buyer.to_account_info(),
+            self.system_program.to_account_info(),
+            8 + BuyerAccount::SPACE,
+            &seeds,
+        )?;
+
+        Ok(BuyerAccount {
+            bump: buyer_bump,
+            pool,
+            buyer,
+            last_buy_at: 0,
+            tokens_bought: Tokens::new(0),
+            reserved: [0; 24],
+        })
+    }
+
+    pub fn send_tokens_from_pool_to_buyer(&self, tokens_amount: Tokens) -> Result<Tokens> {
+        let sale_seed = PoolAccount::sale_seed(self.pool_account.sale_id);
         let seeds = &[
             self.selling_mint.to_account_info().key.as_ref(),
+            sale_seed.as_slice(),
             &[self.pool_account.bump]
         ];
 
-        transfer(
+        transfer_checked(
             CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: self.vault_selling.to_account_info(),
+                    mint: self.selling_mint.to_account_info(),
+                    to: self.buyer_token_account.to_account_info(),
+                    authority: self.pool_account.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            tokens_amount.into(),
+            self.selling_mint.decimals,
+        )?;
+
+        Ok(Tokens::new(transfer_fee(&self.selling_mint.to_account_info(), tokens_amount.into())?))
+    }
+
+    pub fn mint_tokens_to_buyer(&self, tokens_amount: Tokens) -> Result<()> {
+        let sale_seed = PoolAccount::sale_seed(self.pool_account.sale_id);
+        let seeds = &[
+            self.selling_mint.to_account_info().key.as_ref(),
+            sale_seed.as_slice(),
+            &[self.pool_account.bump]
+        ];
+
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.selling_mint.to_account_info(),
                     to: self.buyer_token_account.to_account_info(),
                     authority: self.pool_account.to_account_info(),
                 },
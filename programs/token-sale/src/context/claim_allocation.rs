@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::account::*;
+
+#[derive(Accounts)]
+pub struct ClaimAllocation<'info> {
+    #[account(
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    #[account(
+        seeds = [LotteryAccount::PDA_SEED, pool_account.key().as_ref()],
+        bump = lottery_account.bump,
+    )]
+    pub lottery_account: Box<Account<'info, LotteryAccount>>,
+    /// Traded for the allocation, the rent goes back to the owner
+    #[account(
+        mut,
+        seeds = [TicketAccount::PDA_SEED, pool_account.key().as_ref(), owner.key().as_ref()],
+        bump = ticket_account.bump,
+        close = owner,
+    )]
+    pub ticket_account: Box<Account<'info, TicketAccount>>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + AllocationAccount::SPACE,
+        seeds = [AllocationAccount::PDA_SEED, pool_account.key().as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub allocation_account: Box<Account<'info, AllocationAccount>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::account::*;
+use crate::helper::load_account;
+
+/// Does not require the pool, which is closed by `terminate`
+#[derive(Accounts)]
+pub struct CloseBuyerAccount<'info> {
+    #[account(
+        mut,
+        seeds = [BuyerAccount::PDA_SEED, buyer_account.pool.as_ref(), buyer.key().as_ref()],
+        bump = buyer_account.bump,
+        close = buyer,
+    )]
+    pub buyer_account: Box<Account<'info, BuyerAccount>>,
+    /// CHECK: the pool of the buyer account, read by `CloseBuyerAccount::sale_over`, might be closed already
+    #[account(address = buyer_account.pool)]
+    pub pool_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> CloseBuyerAccount<'info> {
+    /// Is the sale of the buyer account over? It is once its pool is closed by `terminate`
+    pub fn sale_over(&self) -> Result<bool> {
+        if self.pool_account.owner != &crate::ID || self.pool_account.data_is_empty() {
+            return Ok(true);
+        }
+        let pool = load_account::<PoolAccount>(&self.pool_account)?;
+        Ok(pool.end_at <= self.clock.unix_timestamp)
+    }
+}
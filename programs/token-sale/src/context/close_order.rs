@@ -1,23 +1,34 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, TokenAccount, Token, Mint, transfer, Transfer, CloseAccount};
+use anchor_spl::token_interface::{
+    TokenAccount,
+    TokenInterface,
+    Mint,
+    TransferChecked,
+    transfer_checked,
+    CloseAccount,
+    close_account,
+};
 use crate::account::*;
 use crate::currency::Tokens;
 use crate::error::ErrorCode;
+use crate::helper::harvest_withheld_fees;
 
 #[derive(Accounts)]
 pub struct CloseOrder<'info> {
     #[account(
         mut,
-        seeds = [selling_mint.to_account_info().key.as_ref()],
+        seeds = [selling_mint.to_account_info().key.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
         bump = pool_account.bump,
         has_one = selling_mint,
     )]
     pub pool_account: Account<'info, PoolAccount>,
-    pub selling_mint: Box<Account<'info, Mint>>,
+    /// Mutable as the transfer fees withheld in the order vault are harvested to the mint before closing it
+    #[account(mut)]
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
-        seeds = [Order::PDA_SEED, order.owner.as_ref()],
-        bump = order.bump,
+        constraint = order.is_at(&order.key()) @anchor_lang::error::ErrorCode::ConstraintSeeds,
+        constraint = order.pool == pool_account.key() @ErrorCode::OrderNotFoundInPool,
         constraint = order.owner == order_owner.key() @ErrorCode::OnlyOwnerCanCloseOrder,
         close = order_owner,
     )]
@@ -27,7 +38,7 @@ pub struct CloseOrder<'info> {
         constraint = order_token_vault.owner == order.key(),
         constraint = order_token_vault.mint == selling_mint.key(),
     )]
-    pub order_token_vault: Account<'info, TokenAccount>,
+    pub order_token_vault: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub order_owner: Signer<'info>,
     #[account(
@@ -35,30 +46,34 @@ pub struct CloseOrder<'info> {
         constraint = owner_token_vault.owner == order.owner.key(),
         constraint = owner_token_vault.mint == pool_account.selling_mint.key(),
     )]
-    pub owner_token_vault: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub owner_token_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [OrderRegistry::PDA_SEED, pool_account.key().as_ref(), &OrderRegistry::page_seed(order.registry_page)],
+        bump = order_registry.bump,
+    )]
+    pub order_registry: Box<Account<'info, OrderRegistry>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> CloseOrder<'info> {
     pub fn sent_all_tokens_from_order_to_owner(&mut self) -> Result<()> {
-        let seeds = &[
-            Order::PDA_SEED,
-            self.order.owner.as_ref(),
-            &[self.order.bump]
-        ];
+        let seeds = self.order.signer_seeds();
 
-        transfer(
+        transfer_checked(
             CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: self.order_token_vault.to_account_info(),
+                    mint: self.selling_mint.to_account_info(),
                     to: self.owner_token_vault.to_account_info(),
                     authority: self.order.to_account_info(),
                 },
                 &[&seeds[..]]
             ),
-            self.order_token_vault.amount
+            self.order_token_vault.amount,
+            self.selling_mint.decimals,
         )?;
 
         self.order.token_amount = Tokens::new(0);
@@ -67,13 +82,14 @@ impl<'info> CloseOrder<'info> {
     }
 
     pub fn close_order_token_vault(&mut self) -> Result<()> {
-        let seeds = &[
-            Order::PDA_SEED,
-            self.order.owner.as_ref(),
-            &[self.order.bump]
-        ];
+        let seeds = self.order.signer_seeds();
+        harvest_withheld_fees(
+            self.token_program.to_account_info(),
+            self.selling_mint.to_account_info(),
+            &[self.order_token_vault.to_account_info()],
+        )?;
 
-        token::close_account(
+        close_account(
             CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
                 CloseAccount {
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+use crate::account::*;
+
+/// Could be called by anyone once the registration is over
+#[derive(Accounts)]
+pub struct CloseRegistration<'info> {
+    #[account(
+        mut,
+        seeds = [LotteryAccount::PDA_SEED, lottery_account.pool.as_ref()],
+        bump = lottery_account.bump,
+    )]
+    pub lottery_account: Box<Account<'info, LotteryAccount>>,
+    pub clock: Sysvar<'info, Clock>,
+}
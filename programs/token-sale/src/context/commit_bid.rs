@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+use crate::account::*;
+
+#[derive(Accounts)]
+pub struct CommitBid<'info> {
+    #[account(
+        mut,
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    /// One bid per wallet in an auction, closed by `settle_bid`
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + BidAccount::SPACE,
+        seeds = [BidAccount::PDA_SEED, pool_account.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    pub bid_account: Box<Account<'info, BidAccount>>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
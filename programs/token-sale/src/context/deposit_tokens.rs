@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface, Mint, transfer_checked, TransferChecked};
+use crate::account::*;
+use crate::currency::Tokens;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct DepositTokens<'info> {
+    #[account(
+        mut,
+        seeds = [selling_mint.to_account_info().key.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+        has_one = selling_mint,
+        has_one = vault_selling,
+        has_one = admin @ErrorCode::OnlyAdmin,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub vault_selling: InterfaceAccount<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        constraint = admin_token_account.owner == admin.key(),
+        constraint = admin_token_account.mint == selling_mint.key(),
+    )]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> DepositTokens<'info> {
+    /// Returns the tokens the vault received, less than `amount` if the mint charges a transfer fee
+    pub fn send_tokens_to_pool(&mut self, amount: Tokens) -> Result<Tokens> {
+        let vault_tokens = self.vault_selling.amount;
+        let cpi_accounts = TransferChecked {
+            from: self.admin_token_account.to_account_info(),
+            mint: self.selling_mint.to_account_info(),
+            to: self.vault_selling.to_account_info(),
+            authority: self.admin.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new(self.token_program.to_account_info(), cpi_accounts),
+            amount.into(),
+            self.selling_mint.decimals,
+        )?;
+
+        self.vault_selling.reload()?;
+        Ok(Tokens::new(self.vault_selling.amount - vault_tokens))
+    }
+}
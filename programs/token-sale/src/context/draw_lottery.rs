@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use crate::account::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct DrawLottery<'info> {
+    #[account(
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+        has_one = admin @ErrorCode::OnlyAdmin,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    #[account(
+        mut,
+        seeds = [LotteryAccount::PDA_SEED, pool_account.key().as_ref()],
+        bump = lottery_account.bump,
+    )]
+    pub lottery_account: Box<Account<'info, LotteryAccount>>,
+    pub admin: Signer<'info>,
+    /// CHECK: the address is the one of the slot hashes sysvar, read by `lottery::find_slot_hash`
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    pub clock: Sysvar<'info, Clock>,
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TokenInterface, Mint, SetAuthority, set_authority};
+use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
+use crate::account::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct EnableMinting<'info> {
+    #[account(
+        mut,
+        seeds = [selling_mint.to_account_info().key.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+        has_one = selling_mint,
+        has_one = admin @ErrorCode::OnlyAdmin,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    #[account(mut)]
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub admin: Signer<'info>,
+    /// The current mint authority of the selling mint, hands it over to the pool
+    pub mint_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> EnableMinting<'info> {
+    pub fn hand_mint_authority_to_pool(&self) -> Result<()> {
+        set_authority(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: self.mint_authority.to_account_info(),
+                    account_or_mint: self.selling_mint.to_account_info(),
+                },
+            ),
+            AuthorityType::MintTokens,
+            Some(self.pool_account.key()),
+        )
+    }
+}
@@ -1,54 +1,72 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{TokenAccount, Token, Mint, transfer, Transfer};
+use anchor_spl::token_interface::{TokenAccount, TokenInterface, Mint, transfer_checked, TransferChecked};
 use anchor_spl::associated_token::AssociatedToken;
 use crate::account::*;
 use crate::currency::{Tokens};
 
 #[derive(Accounts)]
+#[instruction(sale_id: u64)]
 pub struct Initialize<'info> {
     #[account(
         init,
         payer = distribution_authority,
         space = 8 + PoolAccount::SPACE,
-        seeds = [selling_mint.to_account_info().key.as_ref()],
+        seeds = [selling_mint.to_account_info().key.as_ref(), &PoolAccount::sale_seed(sale_id)],
         bump,
     )]
     pub pool_account: Account<'info, PoolAccount>,
     // The one who will sign the transaction of transferring tokens from the `tokens_for_distribution` account
     // to the `vault_selling` account.
-    // And then allowed to withdraw payment from the pool.
+    // Becomes the admin, the treasury and the operator of the pool till the admin hands the roles over.
     #[account(mut)]
     pub distribution_authority: Signer<'info>,
     #[account(
         mut,
         constraint = tokens_for_distribution.owner == distribution_authority.key(),
     )]
-    pub tokens_for_distribution: Account<'info, TokenAccount>,
-    pub selling_mint: Box<Account<'info, Mint>>,
+    pub tokens_for_distribution: InterfaceAccount<'info, TokenAccount>,
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         init,
         payer = distribution_authority,
         associated_token::mint = selling_mint,
         associated_token::authority = pool_account,
+        associated_token::token_program = token_program,
     )]
-    pub vault_selling: Account<'info, TokenAccount>,
+    pub vault_selling: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = distribution_authority,
+        space = 8 + OrderRegistry::SPACE,
+        seeds = [OrderRegistry::PDA_SEED, pool_account.key().as_ref()],
+        bump,
+    )]
+    pub order_registry: Box<Account<'info, OrderRegistry>>,
+    /// CHECK: a token account of the selling mint for `UnsoldTokensPolicy::SendToDestination`, ignored otherwise
+    pub unsold_tokens_destination: UncheckedAccount<'info>,
     pub clock: Sysvar<'info, Clock>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub rent: Sysvar<'info, Rent>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Initialize<'info> {
-    pub fn send_tokens_to_pool(&self, amount_to_sell: Tokens) -> Result<()> {
-        let cpi_accounts = Transfer {
+    /// Returns the tokens the vault received, less than `amount_to_sell` if the mint charges a transfer fee
+    pub fn send_tokens_to_pool(&mut self, amount_to_sell: Tokens) -> Result<Tokens> {
+        let cpi_accounts = TransferChecked {
             from: self.tokens_for_distribution.to_account_info(),
+            mint: self.selling_mint.to_account_info(),
             to: self.vault_selling.to_account_info(),
             authority: self.distribution_authority.to_account_info(),
         };
-        transfer(
+        transfer_checked(
             CpiContext::new(self.token_program.to_account_info(), cpi_accounts),
-            amount_to_sell.into()
-        )
+            amount_to_sell.into(),
+            self.selling_mint.decimals,
+        )?;
+
+        self.vault_selling.reload()?;
+        Ok(Tokens::new(self.vault_selling.amount))
     }
 }
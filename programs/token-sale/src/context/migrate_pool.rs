@@ -0,0 +1,222 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::account::*;
+use crate::currency::Tokens;
+use crate::error::ErrorCode;
+use crate::helper::{load_account, realloc_account};
+use crate::round::Round;
+use crate::unsold_tokens_policy::UnsoldTokensPolicy;
+
+#[derive(Accounts)]
+#[instruction(sale_id: u64, registry_page: u32)]
+pub struct MigratePool<'info> {
+    /// CHECK: an outdated pool does not deserialize, it is checked by `MigratePool::migrate_pool` after the realloc
+    #[account(
+        mut,
+        seeds = [selling_mint.to_account_info().key.as_ref(), &PoolAccount::sale_seed(sale_id)],
+        bump,
+        owner = crate::ID,
+    )]
+    pub pool_account: UncheckedAccount<'info>,
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        associated_token::mint = selling_mint,
+        associated_token::authority = pool_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_selling: Box<InterfaceAccount<'info, TokenAccount>>,
+    // The pools initialized before the registry was added get one, the adopted orders are listed in this page
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + OrderRegistry::SPACE,
+        seeds = [OrderRegistry::PDA_SEED, pool_account.key().as_ref(), &OrderRegistry::page_seed(registry_page)],
+        bump,
+    )]
+    pub order_registry: Box<Account<'info, OrderRegistry>>,
+    /// Pays for the rent of the grown accounts
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The layout of the pools initialized before the roles, the counters and the version were added
+#[derive(AnchorDeserialize)]
+struct PoolAccountV0 {
+    bump: u8,
+    owner: Pubkey,
+    selling_mint: Pubkey,
+    payment_mint: Pubkey,
+    vault_selling: Pubkey,
+    end_at: i64,
+    buying_duration: u32,
+    trading_duration: u32,
+    token_price: u64,
+    current_round: Round,
+    round_start_at: i64,
+    coeff_a: f32,
+    coeff_b: u32,
+}
+
+impl PoolAccountV0 {
+    const SPACE: usize = 1 + 32 * 4 + 8 + 4 + 4 + 8 + 1 + 8 + 4 + 4;
+}
+
+impl<'info> MigratePool<'info> {
+    /// Brings the pool to the current version, if it is not up to date already, and gives it an order registry.
+    /// The pool is written back by the caller with `MigratePool::save_pool`.
+    pub fn migrate_pool(&mut self, registry_bump: u8, registry_page: u32) -> Result<PoolAccount> {
+        let info = self.pool_account.to_account_info();
+        if info.data_len() == 8 + PoolAccountV0::SPACE {
+            self.migrate_pool_v0(&info)?;
+        }
+        self.grow(&info, 8 + PoolAccount::SPACE)?;
+
+        let mut pool = load_account::<PoolAccount>(&info)?;
+        require!(pool.admin == self.admin.key(), ErrorCode::OnlyAdmin);
+
+        if pool.version < PoolAccount::VERSION {
+            pool.version = PoolAccount::VERSION;
+        }
+
+        require!(registry_page <= pool.last_registry_page + 1, ErrorCode::InvalidRegistryPage);
+        if self.order_registry.pool == Pubkey::default() {
+            self.order_registry.bump = registry_bump;
+            self.order_registry.pool = self.pool_account.key();
+            pool.last_registry_page = registry_page;
+        }
+
+        Ok(pool)
+    }
+
+    pub fn save_pool(&self, pool: &PoolAccount) -> Result<()> {
+        pool.try_serialize(&mut &mut self.pool_account.try_borrow_mut_data()?[..])
+    }
+
+    /// Brings the order of the pool to the current version, returns `false` if it is up to date already
+    pub fn migrate_order(
+        &mut self,
+        pool: &mut PoolAccount,
+        info: &'info AccountInfo<'info>,
+        registry_page: u32,
+    ) -> Result<bool> {
+        if info.owner != &crate::ID {
+            return err!(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram);
+        }
+        self.grow(info, 8 + Order::SPACE)?;
+
+        let mut order = Account::<Order>::try_from(info)?;
+        let adopted = order.pool == Pubkey::default();
+        if adopted {
+            self.adopt_order(pool, &mut order, registry_page)?;
+        }
+        require!(order.pool == self.pool_account.key(), ErrorCode::OrderNotFoundInPool);
+
+        if !adopted && order.version >= Order::VERSION {
+            return Ok(false);
+        }
+
+        order.version = Order::VERSION;
+        order.exit(&crate::ID)?;
+
+        Ok(true)
+    }
+
+    /// The orders placed before they kept their pool belong to the first sale of the mint of their vault,
+    /// which belongs to SPL Token as Token-2022 mints were not accepted yet.
+    /// They are counted as open and the ones with tokens left are listed in the page `registry_page`.
+    fn adopt_order(
+        &mut self,
+        pool: &mut PoolAccount,
+        order: &mut Account<'info, Order>,
+        registry_page: u32,
+    ) -> Result<()> {
+        let vault = get_associated_token_address(&order.key(), &pool.selling_mint);
+        require!(pool.sale_id == 0 && order.token_vault == vault, ErrorCode::OrderNotFoundInPool);
+
+        order.pool = self.pool_account.key();
+        pool.open_orders += 1;
+        if !order.is_empty {
+            self.order_registry.add(order.key())?;
+            order.registry_page = registry_page;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites the pool of the first layout in the current one.
+    /// The owner takes all the roles, the tokens in the vault become the supply as the tokens sold were not counted.
+    fn migrate_pool_v0(&self, info: &AccountInfo<'info>) -> Result<()> {
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(
+                data[..8] == PoolAccount::discriminator(),
+                anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+            );
+            PoolAccountV0::deserialize(&mut &data[8..])?
+        };
+        self.grow(info, 8 + PoolAccount::SPACE)?;
+
+        let pool = PoolAccount {
+            bump: legacy.bump,
+            admin: legacy.owner,
+            treasury: legacy.owner,
+            operator: legacy.owner,
+            selling_mint: legacy.selling_mint,
+            payment_mint: legacy.payment_mint,
+            vault_selling: legacy.vault_selling,
+            end_at: legacy.end_at,
+            buying_duration: legacy.buying_duration,
+            trading_duration: legacy.trading_duration,
+            token_price: legacy.token_price,
+            current_round: legacy.current_round,
+            round_start_at: legacy.round_start_at,
+            coeff_a: legacy.coeff_a,
+            coeff_b: legacy.coeff_b,
+            paused: false,
+            total_supply: Tokens::new(self.vault_selling.amount),
+            tokens_sold: Tokens::new(0),
+            // The first layout burned the unsold tokens
+            unsold_tokens_policy: UnsoldTokensPolicy::Burn,
+            unsold_tokens_destination: Pubkey::default(),
+            // Counted as the orders are migrated
+            open_orders: 0,
+            version: PoolAccount::VERSION,
+            sale_id: 0,
+            mint_on_demand: false,
+            max_supply: None,
+            revoke_mint_authority: false,
+            // The first layout priced a single minimal part
+            price_units: 1,
+            round_number: 0,
+            max_tokens_per_slot: Tokens::new(0),
+            last_buy_slot: 0,
+            tokens_sold_in_slot: Tokens::new(0),
+            buy_cooldown: 0,
+            reject_cpi: false,
+            trusted_caller: None,
+            stake_mint: None,
+            tiers: Default::default(),
+            lottery: Pubkey::default(),
+            commit_duration: 0,
+            reveal_duration: 0,
+            auction_tokens: Tokens::new(0),
+            open_bids: 0,
+            last_registry_page: 0,
+            reserved: [0; 8],
+        };
+        let mut data = info.try_borrow_mut_data()?;
+        pool.try_serialize(&mut &mut data[..])
+    }
+
+    // The new fields are zeroed, which is the default of every one of them
+    fn grow(&self, info: &AccountInfo<'info>, space: usize) -> Result<()> {
+        if info.data_len() < space {
+            realloc_account(info.clone(), self.admin.to_account_info(), space)?;
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,37 @@
+mod abandon_lottery; pub use abandon_lottery::*;
 mod buy_tokens; pub use buy_tokens::*;
+mod claim_allocation; pub use claim_allocation::*;
+mod close_buyer_account; pub use close_buyer_account::*;
 mod close_order; pub use close_order::*;
+mod close_registration; pub use close_registration::*;
+mod commit_bid; pub use commit_bid::*;
+mod deposit_tokens; pub use deposit_tokens::*;
+mod draw_lottery; pub use draw_lottery::*;
+mod enable_minting; pub use enable_minting::*;
 mod initialize; pub use initialize::*;
+mod migrate_pool; pub use migrate_pool::*;
 mod place_order; pub use place_order::*;
+mod quote_buy; pub use quote_buy::*;
+mod quote_redeem; pub use quote_redeem::*;
+mod reclaim_order; pub use reclaim_order::*;
 mod redeem_order; pub use redeem_order::*;
+mod register_ticket; pub use register_ticket::*;
+mod reveal_bid; pub use reveal_bid::*;
+mod set_buy_protection; pub use set_buy_protection::*;
+mod set_paused; pub use set_paused::*;
+mod set_tiers; pub use set_tiers::*;
+mod set_unsold_tokens_policy; pub use set_unsold_tokens_policy::*;
+mod settle_bid; pub use settle_bid::*;
+mod stake; pub use stake::*;
+mod start_auction; pub use start_auction::*;
+mod start_lottery; pub use start_lottery::*;
 mod switch_to_buying; pub use switch_to_buying::*;
+mod switch_to_reveal; pub use switch_to_reveal::*;
 mod switch_to_trading; pub use switch_to_trading::*;
 mod terminate; pub use terminate::*;
+mod unstake; pub use unstake::*;
+mod update_config; pub use update_config::*;
+mod update_roles; pub use update_roles::*;
+mod view_pool_status; pub use view_pool_status::*;
 mod withdraw_lamports; pub use withdraw_lamports::*;
+mod withdraw_unsold_tokens; pub use withdraw_unsold_tokens::*;
@@ -1,19 +1,20 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{TokenAccount, Token, Mint, transfer, Transfer};
+use anchor_spl::token_interface::{TokenAccount, TokenInterface, Mint, transfer_checked, TransferChecked};
 use anchor_spl::associated_token::AssociatedToken;
 use crate::account::*;
 use crate::Tokens;
 
 #[derive(Accounts)]
+#[instruction(amount_to_sell: Tokens, price_for_token: u64, registry_page: u32)]
 pub struct PlaceOrder<'info> {
     #[account(
         mut,
-        seeds = [selling_mint.to_account_info().key.as_ref()],
+        seeds = [selling_mint.to_account_info().key.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
         bump = pool_account.bump,
         has_one = selling_mint
     )]
     pub pool_account: Account<'info, PoolAccount>,
-    pub selling_mint: Box<Account<'info, Mint>>,
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut)]
     pub seller: Signer<'info>,
     #[account(
@@ -21,12 +22,12 @@ pub struct PlaceOrder<'info> {
         constraint = seller_token_account.owner == seller.key(),
         constraint = seller_token_account.mint == selling_mint.key(),
     )]
-    pub seller_token_account: Account<'info, TokenAccount>,
+    pub seller_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(
         init,
         payer = seller,
         space = 8 + Order::SPACE,
-        seeds = [Order::PDA_SEED, seller.key().as_ref()],
+        seeds = [Order::PDA_SEED, pool_account.key().as_ref(), seller.key().as_ref()],
         bump,
     )]
     pub order: Account<'info, Order>,
@@ -35,9 +36,19 @@ pub struct PlaceOrder<'info> {
         payer = seller,
         associated_token::mint = selling_mint,
         associated_token::authority = order,
+        associated_token::token_program = token_program,
     )]
-    pub order_token_vault: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub order_token_vault: InterfaceAccount<'info, TokenAccount>,
+    // The seller opens the next page of the registry once the others are full
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = 8 + OrderRegistry::SPACE,
+        seeds = [OrderRegistry::PDA_SEED, pool_account.key().as_ref(), &OrderRegistry::page_seed(registry_page)],
+        bump,
+    )]
+    pub order_registry: Box<Account<'info, OrderRegistry>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub rent: Sysvar<'info, Rent>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -45,15 +56,21 @@ pub struct PlaceOrder<'info> {
 }
 
 impl<'info> PlaceOrder<'info> {
-    pub fn send_tokens_from_seller_to_order(&self, amount_to_sell: Tokens) -> Result<()> {
-        let cpi_accounts = Transfer {
+    /// Returns the tokens the order received, less than `amount_to_sell` if the mint charges a transfer fee
+    pub fn send_tokens_from_seller_to_order(&mut self, amount_to_sell: Tokens) -> Result<Tokens> {
+        let cpi_accounts = TransferChecked {
             from:  self.seller_token_account.to_account_info(),
+            mint: self.selling_mint.to_account_info(),
             to: self.order_token_vault.to_account_info(),
             authority: self.seller.to_account_info(),
         };
-        transfer(
+        transfer_checked(
             CpiContext::new(self.token_program.to_account_info(), cpi_accounts),
-            amount_to_sell.into()
-        )
+            amount_to_sell.into(),
+            self.selling_mint.decimals,
+        )?;
+
+        self.order_token_vault.reload()?;
+        Ok(Tokens::new(self.order_token_vault.amount))
     }
 }
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::account::*;
+
+#[derive(Accounts)]
+pub struct QuoteBuy<'info> {
+    #[account(
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+        has_one = vault_selling,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    pub vault_selling: InterfaceAccount<'info, TokenAccount>,
+}
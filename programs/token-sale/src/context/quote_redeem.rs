@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::account::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct QuoteRedeem<'info> {
+    #[account(
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    #[account(
+        constraint = order.is_at(&order.key()) @anchor_lang::error::ErrorCode::ConstraintSeeds,
+        constraint = order.pool == pool_account.key() @ErrorCode::OrderNotFoundInPool,
+        has_one = token_vault,
+    )]
+    pub order: Account<'info, Order>,
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+}
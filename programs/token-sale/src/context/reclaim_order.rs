@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    TokenAccount,
+    TokenInterface,
+    Mint,
+    TransferChecked,
+    transfer_checked,
+    CloseAccount,
+    close_account,
+};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::account::*;
+use crate::currency::Tokens;
+use crate::error::ErrorCode;
+use crate::helper::harvest_withheld_fees;
+
+#[derive(Accounts)]
+pub struct ReclaimOrder<'info> {
+    #[account(
+        mut,
+        seeds = [selling_mint.to_account_info().key.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+        has_one = selling_mint,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    /// Mutable as the transfer fees withheld in the order vault are harvested to the mint before closing it
+    #[account(mut)]
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        constraint = order.is_at(&order.key()) @anchor_lang::error::ErrorCode::ConstraintSeeds,
+        constraint = order.pool == pool_account.key() @ErrorCode::OrderNotFoundInPool,
+        close = order_owner,
+    )]
+    pub order: Account<'info, Order>,
+    #[account(
+        mut,
+        constraint = order_token_vault.owner == order.key(),
+        constraint = order_token_vault.mint == selling_mint.key(),
+    )]
+    pub order_token_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the owner of the order, only receives the tokens and the rent
+    #[account(mut, address = order.owner)]
+    pub order_owner: UncheckedAccount<'info>,
+    // Created for the owner if the owner closed it, so nobody can keep the order open
+    #[account(
+        init_if_needed,
+        payer = reclaimer,
+        associated_token::mint = selling_mint,
+        associated_token::authority = order_owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub reclaimer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [OrderRegistry::PDA_SEED, pool_account.key().as_ref(), &OrderRegistry::page_seed(order.registry_page)],
+        bump = order_registry.bump,
+    )]
+    pub order_registry: Box<Account<'info, OrderRegistry>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> ReclaimOrder<'info> {
+    pub fn send_all_tokens_from_order_to_owner(&mut self) -> Result<()> {
+        let seeds = self.order.signer_seeds();
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.order_token_vault.to_account_info(),
+                    mint: self.selling_mint.to_account_info(),
+                    to: self.owner_token_vault.to_account_info(),
+                    authority: self.order.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            self.order_token_vault.amount,
+            self.selling_mint.decimals,
+        )?;
+
+        self.order.token_amount = Tokens::new(0);
+
+        Ok(())
+    }
+
+    pub fn close_order_token_vault(&mut self) -> Result<()> {
+        let seeds = self.order.signer_seeds();
+        harvest_withheld_fees(
+            self.token_program.to_account_info(),
+            self.selling_mint.to_account_info(),
+            &[self.order_token_vault.to_account_info()],
+        )?;
+
+        close_account(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: self.order_token_vault.to_account_info(),
+                    destination: self.order_owner.to_account_info(),
+                    authority: self.order.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+        )
+    }
+}
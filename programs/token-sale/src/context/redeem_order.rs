@@ -1,18 +1,20 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{TokenAccount, Token, Mint, transfer, Transfer};
+use anchor_spl::token_interface::{TokenAccount, TokenInterface, Mint, transfer_checked, TransferChecked};
 use crate::account::*;
+use crate::error::ErrorCode;
+use crate::helper::transfer_fee;
 use crate::Tokens;
 
 #[derive(Accounts)]
 pub struct RedeemOrder<'info> {
     #[account(
         mut,
-        seeds = [selling_mint.to_account_info().key.as_ref()],
+        seeds = [selling_mint.to_account_info().key.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
         bump = pool_account.bump,
         has_one = selling_mint
     )]
     pub pool_account: Account<'info, PoolAccount>,
-    pub selling_mint: Box<Account<'info, Mint>>,
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut)]
     pub buyer: Signer<'info>,
     #[account(
@@ -20,11 +22,11 @@ pub struct RedeemOrder<'info> {
         constraint = buyer_token_account.owner == buyer.key(),
         constraint = buyer_token_account.mint == selling_mint.key(),
     )]
-    pub buyer_token_account: Account<'info, TokenAccount>,
+    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
-        seeds = [Order::PDA_SEED, order.owner.as_ref()],
-        bump = order.bump,
+        constraint = order.is_at(&order.key()) @anchor_lang::error::ErrorCode::ConstraintSeeds,
+        constraint = order.pool == pool_account.key() @ErrorCode::OrderNotFoundInPool,
         constraint = order.owner == order_owner.key(),
     )]
     pub order: Account<'info, Order>,
@@ -36,32 +38,39 @@ pub struct RedeemOrder<'info> {
         constraint = order_token_vault.owner == order.key(),
         constraint = order_token_vault.mint == selling_mint.key(),
     )]
-    pub order_token_vault: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub order_token_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [OrderRegistry::PDA_SEED, pool_account.key().as_ref(), &OrderRegistry::page_seed(order.registry_page)],
+        bump = order_registry.bump,
+    )]
+    pub order_registry: Box<Account<'info, OrderRegistry>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub clock: Sysvar<'info, Clock>,
     pub system_program: Program<'info, System>,
 }
 
 
 impl<'info> RedeemOrder<'info> {
-    pub fn send_tokens_from_order_to_buyer(&self, tokens_amount: Tokens) -> Result<()> {
-        let seeds = &[
-            Order::PDA_SEED,
-            self.order.owner.as_ref(),
-            &[self.order.bump]
-        ];
+    /// Returns the transfer fee the mint withholds from the buyer, the order gives out all the tokens paid for
+    pub fn send_tokens_from_order_to_buyer(&self, tokens_amount: Tokens) -> Result<Tokens> {
+        let seeds = self.order.signer_seeds();
 
-        transfer(
+        transfer_checked(
             CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: self.order_token_vault.to_account_info(),
+                    mint: self.selling_mint.to_account_info(),
                     to: self.buyer_token_account.to_account_info(),
                     authority: self.order.to_account_info(),
                 },
                 &[&seeds[..]]
             ),
-            tokens_amount.into()
-        )
+            tokens_amount.into(),
+            self.selling_mint.decimals,
+        )?;
+
+        Ok(Tokens::new(transfer_fee(&self.selling_mint.to_account_info(), tokens_amount.into())?))
     }
 }
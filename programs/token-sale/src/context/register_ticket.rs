@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::account::*;
+
+#[derive(Accounts)]
+pub struct RegisterTicket<'info> {
+    #[account(
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    #[account(
+        mut,
+        seeds = [LotteryAccount::PDA_SEED, pool_account.key().as_ref()],
+        bump = lottery_account.bump,
+    )]
+    pub lottery_account: Box<Account<'info, LotteryAccount>>,
+    /// A wallet registers only one ticket
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + TicketAccount::SPACE,
+        seeds = [TicketAccount::PDA_SEED, pool_account.key().as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub ticket_account: Box<Account<'info, TicketAccount>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+use crate::account::*;
+
+#[derive(Accounts)]
+pub struct RevealBid<'info> {
+    #[account(
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    #[account(
+        mut,
+        seeds = [AuctionAccount::PDA_SEED, pool_account.key().as_ref()],
+        bump = auction_account.bump,
+    )]
+    pub auction_account: Box<Account<'info, AuctionAccount>>,
+    #[account(
+        mut,
+        seeds = [BidAccount::PDA_SEED, pool_account.key().as_ref(), bidder.key().as_ref()],
+        bump = bid_account.bump,
+    )]
+    pub bid_account: Box<Account<'info, BidAccount>>,
+    pub bidder: Signer<'info>,
+    pub clock: Sysvar<'info, Clock>,
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::account::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct SetTiers<'info> {
+    #[account(
+        mut,
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+        has_one = admin @ErrorCode::OnlyAdmin,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    /// The governance tokens the buyers stake, could not change once set
+    #[account(
+        constraint = pool_account.stake_mint.is_none_or(|mint| mint == stake_mint.key()) @ErrorCode::StakeMintChanged,
+    )]
+    pub stake_mint: Box<Account<'info, Mint>>,
+    pub admin: Signer<'info>,
+    pub clock: Sysvar<'info, Clock>,
+}
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+use crate::account::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct SetUnsoldTokensPolicy<'info> {
+    #[account(
+        mut,
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+        has_one = admin @ErrorCode::OnlyAdmin,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    pub admin: Signer<'info>,
+    /// CHECK: a token account of the selling mint for `UnsoldTokensPolicy::SendToDestination`, ignored otherwise
+    pub unsold_tokens_destination: UncheckedAccount<'info>,
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface, Mint, transfer_checked, TransferChecked};
+use crate::account::*;
+use crate::{Lamports, Tokens};
+
+#[derive(Accounts)]
+pub struct SettleBid<'info> {
+    #[account(
+        mut,
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+        has_one = vault_selling,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    #[account(address = pool_account.selling_mint)]
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub vault_selling: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [AuctionAccount::PDA_SEED, pool_account.key().as_ref()],
+        bump = auction_account.bump,
+    )]
+    pub auction_account: Box<Account<'info, AuctionAccount>>,
+    /// The deposit left after paying for the tokens goes back to the bidder with the rent
+    #[account(
+        mut,
+        seeds = [BidAccount::PDA_SEED, pool_account.key().as_ref(), bidder.key().as_ref()],
+        bump = bid_account.bump,
+        close = bidder,
+    )]
+    pub bid_account: Box<Account<'info, BidAccount>>,
+    /// CHECK: the owner of the bid, only receives lamports
+    #[account(mut)]
+    pub bidder: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = bidder_token_account.owner == bidder.key(),
+        constraint = bidder_token_account.mint == pool_account.selling_mint,
+    )]
+    pub bidder_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> SettleBid<'info> {
+    /// Moves the payment for the tokens won from the deposit to the pool, the program owns both accounts
+    pub fn pay_from_deposit(&self, lamports: Lamports) -> Result<()> {
+        **self.bid_account.to_account_info().try_borrow_mut_lamports()? -= u64::from(lamports);
+        **self.pool_account.to_account_info().try_borrow_mut_lamports()? += u64::from(lamports);
+
+        Ok(())
+    }
+
+    pub fn send_tokens_from_pool_to_bidder(&self, tokens_amount: Tokens) -> Result<()> {
+        let sale_seed = PoolAccount::sale_seed(self.pool_account.sale_id);
+        let seeds = &[
+            self.pool_account.selling_mint.as_ref(),
+            sale_seed.as_slice(),
+            &[self.pool_account.bump]
+        ];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault_selling.to_account_info(),
+                    mint: self.selling_mint.to_account_info(),
+                    to: self.bidder_token_account.to_account_info(),
+                    authority: self.pool_account.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            tokens_amount.into(),
+            self.selling_mint.decimals,
+        )
+    }
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{TokenAccount, Token, Mint, transfer, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::account::*;
+use crate::error::ErrorCode;
+use crate::Tokens;
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+        constraint = pool_account.stake_mint == Some(stake_mint.key()) @ErrorCode::SaleNotTiered,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    pub stake_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + StakeAccount::SPACE,
+        seeds = [StakeAccount::PDA_SEED, pool_account.key().as_ref(), staker.key().as_ref()],
+        bump,
+    )]
+    pub stake_account: Box<Account<'info, StakeAccount>>,
+    #[account(
+        init_if_needed,
+        payer = staker,
+        associated_token::mint = stake_mint,
+        associated_token::authority = stake_account,
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub staker: Signer<'info>,
+    #[account(
+        mut,
+        constraint = staker_token_account.owner == staker.key(),
+        constraint = staker_token_account.mint == stake_mint.key(),
+    )]
+    pub staker_token_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> Stake<'info> {
+    pub fn send_tokens_from_staker_to_stake(&self, amount: Tokens) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.staker_token_account.to_account_info(),
+                    to: self.stake_vault.to_account_info(),
+                    authority: self.staker.to_account_info(),
+                },
+            ),
+            amount.into()
+        )
+    }
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::account::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct StartAuction<'info> {
+    #[account(
+        mut,
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+        has_one = operator @ErrorCode::OnlyOperator,
+        has_one = vault_selling,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    pub vault_selling: InterfaceAccount<'info, TokenAccount>,
+    /// Created by the first auction at the operator's expense
+    #[account(
+        init_if_needed,
+        payer = operator,
+        space = 8 + AuctionAccount::SPACE,
+        seeds = [AuctionAccount::PDA_SEED, pool_account.key().as_ref()],
+        bump,
+    )]
+    pub auction_account: Box<Account<'info, AuctionAccount>>,
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
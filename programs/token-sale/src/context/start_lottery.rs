@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::account::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct StartLottery<'info> {
+    #[account(
+        mut,
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+        has_one = admin @ErrorCode::OnlyAdmin,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    /// A pool runs only one lottery
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + LotteryAccount::SPACE,
+        seeds = [LotteryAccount::PDA_SEED, pool_account.key().as_ref()],
+        bump,
+    )]
+    pub lottery_account: Box<Account<'info, LotteryAccount>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
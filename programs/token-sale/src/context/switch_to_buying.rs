@@ -1,13 +1,16 @@
 use anchor_lang::prelude::*;
 use crate::account::*;
+use crate::error::ErrorCode;
 
 #[derive(Accounts)]
 pub struct SwitchToBuying<'info> {
     #[account(
         mut,
-        seeds = [pool_account.selling_mint.as_ref()],
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
         bump = pool_account.bump,
+        has_one = operator @ErrorCode::OnlyOperator,
     )]
     pub pool_account: Account<'info, PoolAccount>,
+    pub operator: Signer<'info>,
     pub clock: Sysvar<'info, Clock>,
-}
\ No newline at end of file
+}
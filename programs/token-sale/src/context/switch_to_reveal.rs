@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+use crate::account::*;
+
+/// Could be called by anyone once the commit round is over
+#[derive(Accounts)]
+pub struct SwitchToReveal<'info> {
+    #[account(
+        mut,
+        seeds = [pool_account.selling_mint.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    pub clock: Sysvar<'info, Clock>,
+}
@@ -1,45 +1,68 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{
+use anchor_lang::AccountsClose;
+use anchor_spl::token_interface::{
     TokenAccount,
-    Token,
+    TokenInterface,
     Mint,
     Burn,
     burn,
+    TransferChecked,
+    transfer_checked,
     CloseAccount,
     close_account,
+    SetAuthority,
+    set_authority,
 };
+use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
 use crate::account::*;
+use crate::error::ErrorCode;
+use crate::helper::{harvest_withheld_fees, transfer_fee};
+use crate::Tokens;
 
 #[derive(Accounts)]
 pub struct Terminate<'info> {
     #[account(
         mut,
-        seeds = [selling_mint.to_account_info().key.as_ref()],
+        seeds = [selling_mint.to_account_info().key.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
         bump = pool_account.bump,
         has_one = selling_mint,
-        has_one = owner,
-        close = owner,
+        has_one = admin @ErrorCode::OnlyAdmin,
+        close = admin,
     )]
     pub pool_account: Account<'info, PoolAccount>,
+    /// Mutable as the unsold tokens are burned from it and the transfer fees withheld in the vault harvested to it
     #[account(mut)]
-    pub selling_mint: Box<Account<'info, Mint>>,
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
         associated_token::mint = selling_mint,
         associated_token::authority = pool_account,
+        associated_token::token_program = token_program,
     )]
-    pub vault_selling: Account<'info, TokenAccount>,
+    pub vault_selling: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub admin: Signer<'info>,
+    /// CHECK: must match `pool_account.unsold_tokens_receiver(token_program)`, ignored if the unsold tokens are burned
+    #[account(mut)]
+    pub unsold_tokens_receiver: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [OrderRegistry::PDA_SEED, pool_account.key().as_ref()],
+        bump = order_registry.bump,
+        close = admin,
+    )]
+    pub order_registry: Box<Account<'info, OrderRegistry>>,
     pub clock: Sysvar<'info, Clock>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Terminate<'info> {
     pub fn burn_left_tokens(&mut self) -> Result<()> {
+        let sale_seed = PoolAccount::sale_seed(self.pool_account.sale_id);
         let seeds = &[
             self.selling_mint.to_account_info().key.as_ref(),
+            sale_seed.as_slice(),
             &[self.pool_account.bump]
         ];
 
@@ -57,22 +80,90 @@ impl<'info> Terminate<'info> {
         )
     }
 
+    /// Returns the transfer fee the mint withholds from the receiver, the vault gives out all its tokens
+    pub fn send_left_tokens_to_receiver(&mut self) -> Result<Tokens> {
+        let sale_seed = PoolAccount::sale_seed(self.pool_account.sale_id);
+        let seeds = &[
+            self.selling_mint.to_account_info().key.as_ref(),
+            sale_seed.as_slice(),
+            &[self.pool_account.bump]
+        ];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault_selling.to_account_info(),
+                    mint: self.selling_mint.to_account_info(),
+                    to: self.unsold_tokens_receiver.to_account_info(),
+                    authority: self.pool_account.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            self.vault_selling.amount,
+            self.selling_mint.decimals,
+        )?;
+
+        Ok(Tokens::new(transfer_fee(&self.selling_mint.to_account_info(), self.vault_selling.amount)?))
+    }
+
     pub fn close_vault_selling(&mut self) -> Result<()> {
+        let sale_seed = PoolAccount::sale_seed(self.pool_account.sale_id);
         let seeds = &[
             self.selling_mint.to_account_info().key.as_ref(),
+            sale_seed.as_slice(),
             &[self.pool_account.bump]
         ];
+        harvest_withheld_fees(
+            self.token_program.to_account_info(),
+            self.selling_mint.to_account_info(),
+            &[self.vault_selling.to_account_info()],
+        )?;
 
         close_account(
             CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
                 CloseAccount {
                     account: self.vault_selling.to_account_info(),
-                    destination: self.owner.to_account_info(),
+                    destination: self.admin.to_account_info(),
                     authority: self.pool_account.to_account_info(),
                 },
                 &[&seeds[..]]
             ),
         )
     }
+
+    /// Closes a page of the order registry opened by `place_order`, empty once all the orders are closed
+    pub fn close_registry_page(&self, info: &'info AccountInfo<'info>) -> Result<()> {
+        let page = Account::<OrderRegistry>::try_from(info)?;
+        require!(page.pool == self.pool_account.key(), anchor_lang::error::ErrorCode::ConstraintHasOne);
+        page.close(self.admin.to_account_info())
+    }
+
+    /// Hands the mint authority of a minting pool to the admin or revokes it
+    pub fn release_mint_authority(&mut self) -> Result<()> {
+        let sale_seed = PoolAccount::sale_seed(self.pool_account.sale_id);
+        let seeds = &[
+            self.selling_mint.to_account_info().key.as_ref(),
+            sale_seed.as_slice(),
+            &[self.pool_account.bump]
+        ];
+        let new_authority = match self.pool_account.revoke_mint_authority {
+            true => None,
+            false => Some(self.admin.key()),
+        };
+
+        set_authority(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: self.pool_account.to_account_info(),
+                    account_or_mint: self.selling_mint.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            AuthorityType::MintTokens,
+            new_authority,
+        )
+    }
 }
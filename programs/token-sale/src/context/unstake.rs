@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount, Token, Mint, transfer, Transfer, CloseAccount};
+use crate::account::*;
+
+/// Does not take the pool, which is closed by `terminate` before or after the tokens unlock
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(
+        mut,
+        seeds = [StakeAccount::PDA_SEED, stake_account.pool.as_ref(), staker.key().as_ref()],
+        bump = stake_account.bump,
+        close = staker,
+    )]
+    pub stake_account: Box<Account<'info, StakeAccount>>,
+    pub stake_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = stake_account,
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub staker: Signer<'info>,
+    #[account(
+        mut,
+        constraint = staker_token_account.owner == staker.key(),
+        constraint = staker_token_account.mint == stake_mint.key(),
+    )]
+    pub staker_token_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> Unstake<'info> {
+    pub fn send_all_tokens_from_stake_to_staker(&self) -> Result<()> {
+        let seeds = &[
+            StakeAccount::PDA_SEED,
+            self.stake_account.pool.as_ref(),
+            self.stake_account.owner.as_ref(),
+            &[self.stake_account.bump]
+        ];
+
+        transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.stake_vault.to_account_info(),
+                    to: self.staker_token_account.to_account_info(),
+                    authority: self.stake_account.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            self.stake_vault.amount
+        )
+    }
+
+    pub fn close_stake_vault(&self) -> Result<()> {
+        let seeds = &[
+            StakeAccount::PDA_SEED,
+            self.stake_account.pool.as_ref(),
+            self.stake_account.owner.as_ref(),
+            &[self.stake_account.bump]
+        ];
+
+        token::close_account(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: self.stake_vault.to_account_info(),
+                    destination: self.staker.to_account_info(),
+                    authority: self.stake_account.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+        )
+    }
+}
@@ -1,28 +1,32 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, Mint};
+use anchor_spl::token_interface::{TokenInterface, Mint};
 use crate::account::*;
+use crate::currency::Lamports;
+use crate::error::ErrorCode;
 
 #[derive(Accounts)]
 pub struct WithdrawLamports<'info> {
     #[account(
         mut,
-        seeds = [selling_mint.to_account_info().key.as_ref()],
+        seeds = [selling_mint.to_account_info().key.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
         bump = pool_account.bump,
         has_one = selling_mint,
-        has_one = owner,
+        has_one = admin @ErrorCode::OnlyAdmin,
+        has_one = treasury,
     )]
     pub pool_account: Account<'info, PoolAccount>,
-    pub selling_mint: Box<Account<'info, Mint>>,
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub admin: Signer<'info>,
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub treasury: SystemAccount<'info>,
     pub clock: Sysvar<'info, Clock>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 impl<'info> WithdrawLamports<'info> {
-    pub fn send_lamports_from_pool_to_owner(&mut self) -> Result<()> {
+    pub fn send_lamports_from_pool_to_treasury(&mut self) -> Result<Lamports> {
         let pool_info = self.pool_account.to_account_info();
         let pool_data_len = pool_info.try_data_len()?;
         let pool_minimum_rent_exempt_balance = self.rent.minimum_balance(pool_data_len);
@@ -30,8 +34,8 @@ impl<'info> WithdrawLamports<'info> {
         let available_lamports = all_pool_lamports - pool_minimum_rent_exempt_balance;
 
         **pool_info.try_borrow_mut_lamports()? -= available_lamports;
-        **self.owner.try_borrow_mut_lamports()? += available_lamports;
+        **self.treasury.try_borrow_mut_lamports()? += available_lamports;
 
-        Ok(())
+        Ok(Lamports::new(available_lamports))
     }
 }
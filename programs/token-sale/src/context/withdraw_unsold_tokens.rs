@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface, Mint, transfer_checked, TransferChecked};
+use crate::account::*;
+use crate::currency::Tokens;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct WithdrawUnsoldTokens<'info> {
+    #[account(
+        mut,
+        seeds = [selling_mint.to_account_info().key.as_ref(), &PoolAccount::sale_seed(pool_account.sale_id)],
+        bump = pool_account.bump,
+        has_one = selling_mint,
+        has_one = vault_selling,
+        has_one = admin @ErrorCode::OnlyAdmin,
+    )]
+    pub pool_account: Account<'info, PoolAccount>,
+    pub selling_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub vault_selling: InterfaceAccount<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        constraint = destination_token_account.mint == selling_mint.key(),
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> WithdrawUnsoldTokens<'info> {
+    pub fn send_tokens_from_pool_to_destination(&self, amount: Tokens) -> Result<()> {
+        let sale_seed = PoolAccount::sale_seed(self.pool_account.sale_id);
+        let seeds = &[
+            self.selling_mint.to_account_info().key.as_ref(),
+            sale_seed.as_slice(),
+            &[self.pool_account.bump]
+        ];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault_selling.to_account_info(),
+                    mint: self.selling_mint.to_account_info(),
+                    to: self.destination_token_account.to_account_info(),
+                    authority: self.pool_account.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            amount.into(),
+            self.selling_mint.decimals,
+        )
+    }
+}
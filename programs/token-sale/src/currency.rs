@@ -4,7 +4,7 @@ use std::ops::{Add, AddAssign, Sub, SubAssign};
 
 // Currently the syntax Tokens(u64) is not supported
 // https://github.com/project-serum/anchor/issues/1719
-#[derive(PartialEq, Eq, PartialOrd, Ord, AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
 pub struct Tokens { tokens: u64 }
 
 impl Tokens {
@@ -47,7 +47,7 @@ impl SubAssign for Tokens {
     }
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
 pub struct Lamports { lamports: u64 }
 
 impl Lamports {
@@ -91,13 +91,42 @@ impl SubAssign for Lamports {
 }
 
 impl PoolAccount {
+    /// The lamports to pay for the tokens, rounded up so the pool is never underpaid
     pub fn try_tokens_to_lamports(&self, tokens: Tokens) -> Option<Lamports> {
-        let lamports_amount = u64::from(tokens.clone()).checked_mul(self.token_price as u64);
-        lamports_amount.map(|lamports_amount| Lamports::new(lamports_amount))
+        self.try_tokens_to_lamports_at(self.token_price, tokens)
     }
 
+    /// The lamports to pay for the tokens at another price than the pool's one, a price of a bid
+    pub fn try_tokens_to_lamports_at(&self, token_price: u64, tokens: Tokens) -> Option<Lamports> {
+        let (token_price, price_units) = (token_price as u128, self.price_units.max(1) as u128);
+        let lamports_amount = (u64::from(tokens) as u128 * token_price).div_ceil(price_units);
+        u64::try_from(lamports_amount).ok().map(Lamports::new)
+    }
+
+    /// The tokens the lamports pay for, rounded down so the pool never gives out more than it is paid for
     pub fn try_lamports_to_tokens(&self, lamports: Lamports) -> Option<Tokens> {
-        let tokens_amount = u64::from(lamports).checked_div(self.token_price as u64);
-        tokens_amount.map(|tokens_amount| Tokens::new(tokens_amount))
+        let (token_price, price_units) = self.price();
+        let tokens_amount = (u64::from(lamports) as u128 * price_units).checked_div(token_price)?;
+        u64::try_from(tokens_amount).ok().map(Tokens::new)
+    }
+
+    /// The price of the token in the next buying round
+    pub fn try_next_token_price(&self) -> Option<u64> {
+        try_next_token_price(self.token_price, self.coeff_a, self.coeff_b)
     }
+
+    /// `token_price` lamports for `price_units` minimal parts of the token
+    fn price(&self) -> (u128, u128) {
+        (self.token_price as u128, self.price_units.max(1) as u128)
+    }
+}
+
+/// `token_price * coeff_a + coeff_b`, the price of the token in the buying round following the one with `token_price`.
+/// Both prices are for the same `price_units` of the pool.
+pub fn try_next_token_price(token_price: u64, coeff_a: f32, coeff_b: u32) -> Option<u64> {
+    const PRECISENESS: u64 = 10000;
+    token_price
+        .checked_mul((coeff_a * PRECISENESS as f32) as u64)?
+        .checked_div(PRECISENESS)?
+        .checked_add(coeff_b as u64)
 }
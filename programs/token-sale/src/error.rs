@@ -44,4 +44,108 @@ pub enum ErrorCode {
     EndsBeforeFullCircle,
     #[msg("Only the owner of an order can close it")]
     OnlyOwnerCanCloseOrder,
+    #[msg("Only the admin of the pool can perform this action")]
+    OnlyAdmin,
+    #[msg("Only the operator of the pool can switch the rounds")]
+    OnlyOperator,
+    #[msg("The sale is paused")]
+    SalePaused,
+    #[msg("The coefficients must be finite, non-negative and give a positive token price")]
+    InvalidPriceCoefficients,
+    #[msg("The end of the IDO could only be postponed")]
+    EndAtCannotBeReduced,
+    #[msg("The duration of the running round cannot be changed")]
+    RunningRoundDurationLocked,
+    #[msg("Unsold tokens cannot be withdrawn during the buying round")]
+    BuyingRunning,
+    #[msg("The unsold tokens receiver does not match the policy of the pool")]
+    WrongUnsoldTokensReceiver,
+    #[msg("The IDO cannot be terminated while there are open orders, reclaim them first")]
+    OrdersStillOpen,
+    #[msg("The amount of tokens is too large to be priced")]
+    AmountTooLarge,
+    #[msg("The order registry of the pool is full")]
+    OrderRegistryFull,
+    #[msg("The pool already mints the tokens it sells")]
+    MintingAlreadyEnabled,
+    #[msg("Only a pool without tokens could mint the tokens it sells")]
+    PoolHoldsTokens,
+    #[msg("The pool mints the tokens it sells and does not take deposits")]
+    MintingOnDemand,
+    #[msg("The token price must be given for at least one minimal part of the token")]
+    PriceUnitsZero,
+    #[msg("The tokens cost more lamports than the buyer allowed")]
+    MaxLamportsExceeded,
+    #[msg("The token price is above the one the buyer allowed")]
+    MaxPriceExceeded,
+    #[msg("The round has switched since the transaction was signed")]
+    UnexpectedRound,
+    #[msg("The pool sold as many tokens within the slot as it allows")]
+    SlotLimitReached,
+    #[msg("The buyer has to wait longer since the last purchase")]
+    BuyCooldown,
+    #[msg("The pool accepts purchases only from the transaction itself or its trusted program")]
+    UnknownCaller,
+    #[msg("The sale does not take stakes")]
+    SaleNotTiered,
+    #[msg("The stake mint could not be changed")]
+    StakeMintChanged,
+    #[msg("There are at most four tiers, each with a larger stake and allocation than the previous one")]
+    InvalidTiers,
+    #[msg("Stake at least one minimal part of the token")]
+    StakingToFewTokens,
+    #[msg("The staked tokens unlock after the end of the sale")]
+    StakeLocked,
+    #[msg("The tokens exceed the allocation of the buyer")]
+    AllocationExceeded,
+    #[msg("The registration must close before the end of the sale, with at least one winner and allocation")]
+    InvalidLottery,
+    #[msg("The registration of the tickets is over")]
+    RegistrationOver,
+    #[msg("The registration of the tickets is still open")]
+    RegistrationOpen,
+    #[msg("The winners of the lottery are already drawn")]
+    LotteryAlreadyDrawn,
+    #[msg("The secret does not match the committed hash")]
+    WrongSecret,
+    #[msg("The hash of the slot the registration was closed in is not available yet or any longer")]
+    SlotHashUnavailable,
+    #[msg("The winners of the lottery are not drawn yet")]
+    LotteryNotDrawn,
+    #[msg("The ticket did not win")]
+    TicketLost,
+    #[msg("The auction must sell the tokens of the vault and reveal the bids before the end of the sale")]
+    InvalidAuction,
+    #[msg("The auction is running")]
+    AuctionRunning,
+    #[msg("The bids of the last auction are not settled yet")]
+    BidsNotSettled,
+    #[msg("Not the commit round")]
+    NotCommitRound,
+    #[msg("The commit round is over")]
+    CommitOver,
+    #[msg("The commit round is still running")]
+    CommitRunning,
+    #[msg("Not the reveal round")]
+    NotRevealRound,
+    #[msg("The reveal round is over")]
+    RevealOver,
+    #[msg("The bid is already revealed")]
+    BidAlreadyRevealed,
+    #[msg("The bid does not match the commitment")]
+    WrongBid,
+    #[msg("Bid for at least one minimal part of the token at no less than the token price")]
+    InvalidBid,
+    #[msg("The deposit does not cover the bid")]
+    BidNotCovered,
+    #[msg("The unsold tokens must go to a token account of the selling mint")]
+    InvalidUnsoldTokensDestination,
+    #[msg("The admin could still draw the winners of the lottery")]
+    DrawNotTimedOut,
+    #[msg("The lottery was abandoned")]
+    LotteryAbandoned,
+    #[msg("The sale needs at least one tier")]
+    NoTiers,
+    #[msg("The page of the order registry is neither open nor the next one")]
+    InvalidRegistryPage,
 }
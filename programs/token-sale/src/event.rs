@@ -0,0 +1,310 @@
+use anchor_lang::prelude::*;
+use crate::currency::{Lamports, Tokens};
+use crate::round::Round;
+use crate::tier::Tier;
+use crate::unsold_tokens_policy::UnsoldTokensPolicy;
+
+#[event]
+pub struct TokensDeposited {
+    pub pool: Pubkey,
+    /// The tokens received by the vault, net of the transfer fee of the mint
+    pub amount: Tokens,
+    /// The supply of the pool after the deposit
+    pub total_supply: Tokens,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnsoldTokensWithdrawn {
+    pub pool: Pubkey,
+    pub amount: Tokens,
+    /// The token account that received the unsold tokens
+    pub destination: Pubkey,
+    /// The supply of the pool after the withdrawal
+    pub total_supply: Tokens,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensBought {
+    pub pool: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: Tokens,
+    /// The price for one token
+    pub token_price: u64,
+    pub lamports_paid: Lamports,
+    /// The part of `amount` the transfer fee of the mint withheld from the buyer
+    pub transfer_fee: Tokens,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoundSwitched {
+    pub pool: Pubkey,
+    pub round: Round,
+    /// The price for one token in the new round
+    pub token_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderPlaced {
+    pub pool: Pubkey,
+    pub order: Pubkey,
+    pub seller: Pubkey,
+    /// The tokens received by the order, net of the transfer fee of the mint
+    pub amount: Tokens,
+    /// The price for one token asked by the seller
+    pub token_price: u64,
+    /// The page of the order registry listing the order
+    pub registry_page: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderFilled {
+    pub pool: Pubkey,
+    pub order: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: Tokens,
+    /// The price for one token paid by the buyer
+    pub token_price: u64,
+    pub lamports_paid: Lamports,
+    /// The tokens left in the order
+    pub tokens_left: Tokens,
+    /// The part of `amount` the transfer fee of the mint withheld from the buyer
+    pub transfer_fee: Tokens,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderClosed {
+    pub pool: Pubkey,
+    pub order: Pubkey,
+    pub seller: Pubkey,
+    /// The tokens returned to the seller
+    pub tokens_returned: Tokens,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderReclaimed {
+    pub pool: Pubkey,
+    pub order: Pubkey,
+    pub seller: Pubkey,
+    pub reclaimer: Pubkey,
+    /// The tokens returned to the seller
+    pub tokens_returned: Tokens,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LamportsWithdrawn {
+    pub pool: Pubkey,
+    pub treasury: Pubkey,
+    pub amount: Lamports,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub pool: Pubkey,
+    pub end_at: i64,
+    pub buying_duration: u32,
+    pub trading_duration: u32,
+    pub coeff_a: f32,
+    pub coeff_b: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RolesUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub operator: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnsoldTokensPolicyChanged {
+    pub pool: Pubkey,
+    pub unsold_tokens_policy: UnsoldTokensPolicy,
+    pub unsold_tokens_destination: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PausedChanged {
+    pub pool: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SaleTerminated {
+    pub pool: Pubkey,
+    pub tokens_sold: Tokens,
+    pub unsold_tokens: Tokens,
+    /// The token account that received the unsold tokens, `None` if they were burned
+    pub unsold_tokens_receiver: Option<Pubkey>,
+    /// The part of `unsold_tokens` the transfer fee of the mint withheld from the receiver
+    pub transfer_fee: Tokens,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolMigrated {
+    pub pool: Pubkey,
+    pub version: u8,
+    /// The orders of the pool brought to the current version along with it
+    pub orders_migrated: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintingEnabled {
+    pub pool: Pubkey,
+    /// The most tokens the pool could mint, `None` if not capped
+    pub max_supply: Option<Tokens>,
+    pub revoke_mint_authority: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BuyProtectionChanged {
+    pub pool: Pubkey,
+    pub max_tokens_per_slot: Tokens,
+    pub buy_cooldown: u32,
+    pub reject_cpi: bool,
+    pub trusted_caller: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TiersSet {
+    pub pool: Pubkey,
+    pub stake_mint: Pubkey,
+    pub tiers: Vec<Tier>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensStaked {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub amount: Tokens,
+    /// All the tokens staked by the staker
+    pub stake: Tokens,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensUnstaked {
+    pub pool: Pubkey,
+    pub staker: Pubkey,
+    pub amount: Tokens,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BuyerAccountClosed {
+    pub pool: Pubkey,
+    pub buyer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LotteryStarted {
+    pub pool: Pubkey,
+    pub registration_ends_at: i64,
+    pub winners: u32,
+    pub allocation: Tokens,
+    pub secret_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TicketRegistered {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub number: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RegistrationClosed {
+    pub pool: Pubkey,
+    pub tickets: u32,
+    pub draw_slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LotteryAbandoned {
+    pub pool: Pubkey,
+    pub tickets: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LotteryDrawn {
+    pub pool: Pubkey,
+    pub secret: [u8; 32],
+    pub slot_hash: [u8; 32],
+    pub seed: [u8; 32],
+    pub tickets: u32,
+    pub winners: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AllocationClaimed {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub ticket: u32,
+    pub amount: Tokens,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionStarted {
+    pub pool: Pubkey,
+    pub tokens: Tokens,
+    pub commit_duration: u32,
+    pub reveal_duration: u32,
+    pub round_number: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BidCommitted {
+    pub pool: Pubkey,
+    pub bidder: Pubkey,
+    pub commitment: [u8; 32],
+    pub deposit: Lamports,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BidRevealed {
+    pub pool: Pubkey,
+    pub bidder: Pubkey,
+    pub tokens: Tokens,
+    pub price: u64,
+    /// Whether the bid is among the highest ones kept by the auction
+    pub ranked: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BidSettled {
+    pub pool: Pubkey,
+    pub bidder: Pubkey,
+    pub tokens: Tokens,
+    pub price: u64,
+    pub lamports_paid: Lamports,
+    pub lamports_refunded: Lamports,
+    pub timestamp: i64,
+}
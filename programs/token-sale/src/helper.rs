@@ -1,4 +1,14 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Owners;
+use anchor_lang::system_program::{allocate, assign, create_account, Allocate, Assign, CreateAccount};
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::{
+    instruction::harvest_withheld_tokens_to_mint,
+    TransferFeeAmount,
+    TransferFeeConfig,
+};
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use crate::error::ErrorCode;
 use crate::Lamports;
 
 pub fn send_lamports<'a>(from: AccountInfo<'a>, to: AccountInfo<'a>, amount: Lamports) -> Result<()> {
@@ -16,3 +26,108 @@ pub fn send_lamports<'a>(from: AccountInfo<'a>, to: AccountInfo<'a>, amount: Lam
         ],
     ).map_err(|err| err.into())
 }
+
+/// Grows the account to `new_len` bytes filled with zeros, the payer keeps it rent-exempt
+pub fn realloc_account<'a>(account: AccountInfo<'a>, payer: AccountInfo<'a>, new_len: usize) -> Result<()> {
+    let rent_exempt = Rent::get()?.minimum_balance(new_len);
+    let lamports = account.lamports();
+    if rent_exempt > lamports {
+        send_lamports(payer, account.clone(), Lamports::new(rent_exempt - lamports))?;
+    }
+
+    account.realloc(new_len, true).map_err(|err| err.into())
+}
+
+/// Creates the program account at the address of `seeds`, the payer keeps it rent-exempt.
+/// Lamports sent to the address beforehand are kept, so they could not block the creation.
+pub fn create_pda_account<'a>(
+    account: AccountInfo<'a>,
+    payer: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let rent_exempt = Rent::get()?.minimum_balance(space);
+    let lamports = account.lamports();
+    let signer_seeds = &[seeds];
+    if lamports == 0 {
+        let accounts = CreateAccount { from: payer, to: account };
+        let ctx = CpiContext::new_with_signer(system_program, accounts, signer_seeds);
+        return create_account(ctx, rent_exempt, space as u64, &crate::ID);
+    }
+
+    if rent_exempt > lamports {
+        send_lamports(payer, account.clone(), Lamports::new(rent_exempt - lamports))?;
+    }
+    let accounts = Allocate { account_to_allocate: account.clone() };
+    allocate(CpiContext::new_with_signer(system_program.clone(), accounts, signer_seeds), space as u64)?;
+    let accounts = Assign { account_to_assign: account };
+    assign(CpiContext::new_with_signer(system_program, accounts, signer_seeds), &crate::ID)
+}
+
+/// Reads an account the context leaves unchecked, as `Account::try_from` does
+/// but without borrowing the account info for the lifetime of the accounts
+pub fn load_account<T: AccountDeserialize + Owner>(info: &AccountInfo) -> Result<T> {
+    if info.owner != &T::owner() {
+        return err!(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram);
+    }
+    T::try_deserialize(&mut &info.try_borrow_data()?[..])
+}
+
+/// Reads a token account of either token program the context leaves unchecked, as `load_account` does
+pub fn load_interface_account<T: AccountDeserialize + Owners>(info: &AccountInfo) -> Result<T> {
+    if !T::owners().contains(info.owner) {
+        return err!(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram);
+    }
+    T::try_deserialize(&mut &info.try_borrow_data()?[..])
+}
+
+/// The fee a transfer of `amount` tokens of the mint withholds from the receiver,
+/// zero unless the mint belongs to Token-2022 and has the transfer fee extension
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(0);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    match mint.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config.calculate_epoch_fee(Clock::get()?.epoch, amount).ok_or(error!(ErrorCode::AmountTooLarge)),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Moves the transfer fees withheld in the token accounts to their mint, as Token-2022 refuses to close
+/// an account that withholds fees. Nothing is done if none of the accounts withholds fees
+pub fn harvest_withheld_fees<'a>(
+    token_program: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    sources: &[AccountInfo<'a>],
+) -> Result<()> {
+    if token_program.key() != spl_token_2022::ID {
+        return Ok(());
+    }
+    let mut withholding = vec![];
+    for source in sources {
+        let data = source.try_borrow_data()?;
+        let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+        let withheld = account.get_extension::<TransferFeeAmount>().map_or(0, |fee| u64::from(fee.withheld_amount));
+        if withheld > 0 {
+            withholding.push(source.clone());
+        }
+    }
+    if withholding.is_empty() {
+        return Ok(());
+    }
+
+    let keys = withholding.iter().map(|source| source.key).collect::<Vec<_>>();
+    let ix = harvest_withheld_tokens_to_mint(token_program.key, mint.key, &keys)?;
+    let mut infos = vec![mint, token_program];
+    infos.extend(withholding);
+    anchor_lang::solana_program::program::invoke(&ix, &infos).map_err(|err| err.into())
+}
+
+/// Returns the value of a view instruction to the caller
+pub fn set_return<T: AnchorSerialize>(value: &T) -> Result<()> {
+    anchor_lang::solana_program::program::set_return_data(&value.try_to_vec()?);
+    Ok(())
+}
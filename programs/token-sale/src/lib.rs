@@ -1,11 +1,17 @@
 use anchor_lang::prelude::*;
-mod account;
+pub mod account; use account::{AuctionAccount, Order, PoolAccount};
 mod context; use context::*;
-mod access_control; use access_control::*;
-mod error; use error::ErrorCode;
-mod round; use round::Round;
-mod helper; use helper::send_lamports;
-mod currency; use currency::{Lamports, Tokens};
+pub mod access_control; use access_control::*;
+pub mod error; use error::ErrorCode;
+pub mod round; use round::Round;
+pub mod unsold_tokens_policy; use unsold_tokens_policy::UnsoldTokensPolicy;
+pub mod tier; use tier::Tier;
+pub mod lottery;
+pub mod auction;
+mod helper; use helper::{send_lamports, set_return};
+pub mod currency; use currency::{Lamports, Tokens};
+pub mod event; use event::*;
+pub mod view; use view::*;
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
@@ -13,29 +19,46 @@ declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 pub mod token_sale {
     use super::*;
 
+    /// Starts the sale `sale_id` of the selling mint, a mint could have any number of sales with different ids.
+    /// `token_price` and `coeff_b` are lamports for `price_units` minimal parts of the token.
     pub fn initialize(
         ctx: Context<Initialize>,
+        sale_id: u64,
         round_start_at: i64,
         end_at: i64,
         buying_duration: u32,
         trading_duration: u32,
         token_price: u64,
+        price_units: u64,
         amount_to_sell: Tokens,
         coeff_a: f32,
         coeff_b: u32,
+        unsold_tokens_policy: UnsoldTokensPolicy,
     ) -> Result<()> {
         let tokens_for_sale = Tokens::new(ctx.accounts.tokens_for_distribution.amount);
         let now = ctx.accounts.clock.unix_timestamp;
-        let full_cycle = now + buying_duration as i64 + trading_duration as i64;
 
         require!(token_price != 0, ErrorCode::TokenPriceZero);
+        require!(price_units != 0, ErrorCode::PriceUnitsZero);
         require!(amount_to_sell <= tokens_for_sale, ErrorCode::NotEnoughTokensForSale);
         require!(round_start_at >= now, ErrorCode::FirstRoundAlreadyStarted);
-        require!(end_at >= full_cycle, ErrorCode::EndsBeforeFullCircle);
+        valid_schedule(now, end_at, buying_duration, trading_duration)?;
+        valid_price_coefficients(coeff_a, coeff_b)?;
+        valid_unsold_tokens_destination(
+            &unsold_tokens_policy,
+            &ctx.accounts.unsold_tokens_destination,
+            &ctx.accounts.selling_mint.key(),
+        )?;
+        let unsold_tokens_destination = match unsold_tokens_policy {
+            UnsoldTokensPolicy::SendToDestination => ctx.accounts.unsold_tokens_destination.key(),
+            UnsoldTokensPolicy::Burn | UnsoldTokensPolicy::ReturnToAdmin => Pubkey::default(),
+        };
 
         let pool_account = &mut ctx.accounts.pool_account;
-        pool_account.bump = *ctx.bumps.get("pool_account").expect("pool_account bump exists");
-        pool_account.owner = ctx.accounts.distribution_authority.key();
+        pool_account.bump = ctx.bumps.pool_account;
+        pool_account.admin = ctx.accounts.distribution_authority.key();
+        pool_account.treasury = ctx.accounts.distribution_authority.key();
+        pool_account.operator = ctx.accounts.distribution_authority.key();
         pool_account.selling_mint = ctx.accounts.selling_mint.key();
         pool_account.vault_selling = ctx.accounts.vault_selling.key();
         pool_account.round_start_at = round_start_at;
@@ -43,28 +66,96 @@ pub mod token_sale {
         pool_account.buying_duration = buying_duration;
         pool_account.trading_duration = trading_duration;
         pool_account.token_price = token_price;
+        pool_account.price_units = price_units;
         pool_account.current_round = Round::Buying;
+        pool_account.round_number = 0;
         pool_account.coeff_a = coeff_a;
         pool_account.coeff_b = coeff_b;
+        pool_account.paused = false;
+        pool_account.tokens_sold = Tokens::new(0);
+        pool_account.unsold_tokens_policy = unsold_tokens_policy;
+        pool_account.unsold_tokens_destination = unsold_tokens_destination;
+        pool_account.open_orders = 0;
+        pool_account.version = PoolAccount::VERSION;
+        pool_account.sale_id = sale_id;
 
-        ctx.accounts.send_tokens_to_pool(amount_to_sell)
+        let order_registry = &mut ctx.accounts.order_registry;
+        order_registry.bump = ctx.bumps.order_registry;
+        order_registry.pool = ctx.accounts.pool_account.key();
+
+        ctx.accounts.pool_account.total_supply = ctx.accounts.send_tokens_to_pool(amount_to_sell)?;
+        Ok(())
     }
 
-    #[access_control(round_buying(&ctx.accounts.pool_account, &ctx.accounts.clock))]
-    pub fn buy(ctx: Context<BuyTokens>, amount_to_buy: Tokens) -> Result<()> {
-        let amount_for_sale = Tokens::new(ctx.accounts.vault_selling.amount);
+    /// Fails if the tokens cost more than `max_total_lamports`
+    /// or the round is not `expected_round`, see `PoolAccount::round_number`.
+    #[access_control(
+        round_buying(&ctx.accounts.pool_account, &ctx.accounts.clock)
+        round_as_expected(&ctx.accounts.pool_account, expected_round)
+        caller_allowed(&ctx.accounts.pool_account, &ctx.accounts.instructions)
+    )]
+    pub fn buy(
+        ctx: Context<BuyTokens>,
+        amount_to_buy: Tokens,
+        max_total_lamports: Lamports,
+        expected_round: Option<u32>,
+    ) -> Result<()> {
+        let amount_for_sale = ctx.accounts.pool_account.tokens_for_sale(Tokens::new(ctx.accounts.vault_selling.amount));
         let lamports_amount = ctx.accounts.pool_account
             .try_tokens_to_lamports(amount_to_buy).expect("Converts tokens to lamports");
-        let buyer_lamports = **ctx.accounts.buyer.to_account_info().try_borrow_lamports()?;
 
         require!(amount_for_sale >= amount_to_buy, ErrorCode::InsufficientTokensInVault);
-        require!(buyer_lamports >= lamports_amount.into(), ErrorCode::InsufficientLamportsToBuyTokens);
+        require!(lamports_amount <= max_total_lamports, ErrorCode::MaxLamportsExceeded);
 
-        let buyer = &mut ctx.accounts.buyer;
-        let pool = &mut ctx.accounts.pool_account;
+        let buyer_bump = ctx.bumps.buyer_account;
+        let transfer_fee = ctx.accounts.sell(amount_to_buy, lamports_amount, buyer_bump)?;
 
-        send_lamports(buyer.to_account_info(), pool.to_account_info(), lamports_amount)?;
-        ctx.accounts.send_tokens_from_pool_to_buyer(amount_to_buy)?;
+        emit!(TokensBought {
+            pool: ctx.accounts.pool_account.key(),
+            buyer: ctx.accounts.buyer.key(),
+            amount: amount_to_buy,
+            token_price: ctx.accounts.pool_account.token_price,
+            lamports_paid: lamports_amount,
+            transfer_fee,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Buys as many tokens as `max_lamports` pay for,
+    /// but not more than the pool has for sale, could sell in the slot or the buyer's allocation allows.
+    /// Only the price of the tokens bought is charged, which never exceeds `max_lamports`.
+    #[access_control(
+        round_buying(&ctx.accounts.pool_account, &ctx.accounts.clock)
+        caller_allowed(&ctx.accounts.pool_account, &ctx.accounts.instructions)
+    )]
+    pub fn buy_with_lamports(ctx: Context<BuyTokens>, max_lamports: Lamports) -> Result<()> {
+        let pool = &ctx.accounts.pool_account;
+        let amount_for_sale = pool.tokens_for_sale(Tokens::new(ctx.accounts.vault_selling.amount))
+            .min(pool.tokens_left_in_slot(ctx.accounts.clock.slot))
+            .min(ctx.accounts.allocation_left()?);
+        let amount_to_buy = ctx.accounts.pool_account
+            .try_lamports_to_tokens(max_lamports)
+            .map_or(amount_for_sale, |tokens_amount| tokens_amount.min(amount_for_sale));
+        require!(amount_to_buy >= Tokens::new(1), ErrorCode::BuyingToFewTokens);
+
+        let lamports_amount = ctx.accounts.pool_account
+            .try_tokens_to_lamports(amount_to_buy)
+            .ok_or(ErrorCode::AmountTooLarge)?;
+
+        let buyer_bump = ctx.bumps.buyer_account;
+        let transfer_fee = ctx.accounts.sell(amount_to_buy, lamports_amount, buyer_bump)?;
+
+        emit!(TokensBought {
+            pool: ctx.accounts.pool_account.key(),
+            buyer: ctx.accounts.buyer.key(),
+            amount: amount_to_buy,
+            token_price: ctx.accounts.pool_account.token_price,
+            lamports_paid: lamports_amount,
+            transfer_fee,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
 
         Ok(())
     }
@@ -74,34 +165,91 @@ pub mod token_sale {
         let pool = &mut ctx.accounts.pool_account;
         pool.round_start_at = ctx.accounts.clock.unix_timestamp;
         pool.current_round = Round::Trading;
+        pool.round_number += 1;
+
+        // The tokens of an auction without bids to settle are for sale again
+        if pool.open_bids == 0 {
+            pool.auction_tokens = Tokens::new(0);
+        }
+
+        emit!(RoundSwitched {
+            pool: pool.key(),
+            round: Round::Trading,
+            token_price: pool.token_price,
+            timestamp: pool.round_start_at,
+        });
 
         Ok(())
     }
 
+    /// Lists the order in the page `registry_page` of the order registry, the next page is opened at the seller's
+    /// expense once the others are full. The order sells the tokens it receives, net of the transfer fee of the mint.
     #[access_control(round_trading(&ctx.accounts.pool_account, &ctx.accounts.clock))]
-    pub fn place_order(ctx: Context<PlaceOrder>, amount_to_sell: Tokens, price_for_token: u64) -> Result<()> {
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        amount_to_sell: Tokens,
+        price_for_token: u64,
+        registry_page: u32,
+    ) -> Result<()> {
         require!(amount_to_sell >= Tokens::new(1), ErrorCode::SellingToFewTokens);
+        let last_registry_page = ctx.accounts.pool_account.last_registry_page;
+        require!(registry_page <= last_registry_page + 1, ErrorCode::InvalidRegistryPage);
 
         let seller_tokens = Tokens::new(ctx.accounts.seller_token_account.amount);
         require!(seller_tokens > amount_to_sell, ErrorCode::InsufficientTokensInVault);
 
-        ctx.accounts.send_tokens_from_seller_to_order(amount_to_sell)?;
+        let tokens_received = ctx.accounts.send_tokens_from_seller_to_order(amount_to_sell)?;
+        require!(tokens_received >= Tokens::new(1), ErrorCode::SellingToFewTokens);
 
         let order = &mut ctx.accounts.order;
         order.is_empty = false;
         order.created_at = ctx.accounts.clock.unix_timestamp;
-        order.bump = *ctx.bumps.get("order").expect("order bump exists");
+        order.bump = ctx.bumps.order;
         order.token_price = price_for_token;
         order.token_vault = ctx.accounts.order_token_vault.key();
         order.owner = ctx.accounts.seller.key();
-        order.token_amount = amount_to_sell;
+        order.token_amount = tokens_received;
+        order.pool = ctx.accounts.pool_account.key();
+        order.version = Order::VERSION;
+        order.pool_seeded = true;
+        order.registry_page = registry_page;
+
+        let order_registry = &mut ctx.accounts.order_registry;
+        if order_registry.pool == Pubkey::default() {
+            order_registry.bump = ctx.bumps.order_registry;
+            order_registry.pool = ctx.accounts.pool_account.key();
+            ctx.accounts.pool_account.last_registry_page = registry_page;
+        }
+        order_registry.add(ctx.accounts.order.key())?;
+        ctx.accounts.pool_account.open_orders += 1;
+
+        emit!(OrderPlaced {
+            pool: ctx.accounts.pool_account.key(),
+            order: ctx.accounts.order.key(),
+            seller: ctx.accounts.seller.key(),
+            amount: tokens_received,
+            token_price: price_for_token,
+            registry_page,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
 
         Ok(())
     }
 
-    #[access_control(round_trading(&ctx.accounts.pool_account, &ctx.accounts.clock))]
-    pub fn redeem_order(ctx: Context<RedeemOrder>, tokens_amount: Tokens) -> Result<()> {
+    /// Fails if the pool price is above `max_price_per_token`, in the units of `PoolAccount::token_price`,
+    /// or the round is not `expected_round`, see `PoolAccount::round_number`.
+    #[access_control(
+        round_trading(&ctx.accounts.pool_account, &ctx.accounts.clock)
+        round_as_expected(&ctx.accounts.pool_account, expected_round)
+    )]
+    pub fn redeem_order(
+        ctx: Context<RedeemOrder>,
+        tokens_amount: Tokens,
+        max_price_per_token: u64,
+        expected_round: Option<u32>,
+    ) -> Result<()> {
         require!(tokens_amount >= Tokens::new(1), ErrorCode::BuyingToFewTokens);
+        require!(ctx.accounts.pool_account.token_price <= max_price_per_token, ErrorCode::MaxPriceExceeded);
 
         let order_tokens = Tokens::new(ctx.accounts.order_token_vault.amount);
         require!(order_tokens >= tokens_amount, ErrorCode::InsufficientTokensInVault);
@@ -114,7 +262,7 @@ pub mod token_sale {
 
         // Send lamports to the order's owner, send tokens to the buyer
         send_lamports(ctx.accounts.buyer.to_account_info(), ctx.accounts.order_owner.to_account_info(), lamports_amount)?;
-        ctx.accounts.send_tokens_from_order_to_buyer(tokens_amount)?;
+        let transfer_fee = ctx.accounts.send_tokens_from_order_to_buyer(tokens_amount)?;
 
         // Reduce the token amount in the order
         let order = &mut ctx.accounts.order;
@@ -122,14 +270,67 @@ pub mod token_sale {
 
         if order.token_amount == Tokens::new(0) {
             ctx.accounts.order.is_empty = true;
+            ctx.accounts.order_registry.remove(&ctx.accounts.order.key());
         }
 
+        emit!(OrderFilled {
+            pool: ctx.accounts.pool_account.key(),
+            order: ctx.accounts.order.key(),
+            seller: ctx.accounts.order_owner.key(),
+            buyer: ctx.accounts.buyer.key(),
+            amount: tokens_amount,
+            token_price: ctx.accounts.pool_account.token_price,
+            lamports_paid: lamports_amount,
+            tokens_left: ctx.accounts.order.token_amount,
+            transfer_fee,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
         Ok(())
     }
 
     pub fn close_order(ctx: Context<CloseOrder>) -> Result<()> {
+        let tokens_returned = Tokens::new(ctx.accounts.order_token_vault.amount);
+
         ctx.accounts.sent_all_tokens_from_order_to_owner()?;
-        ctx.accounts.close_order_token_vault()
+        ctx.accounts.close_order_token_vault()?;
+
+        ctx.accounts.pool_account.open_orders -= 1;
+        ctx.accounts.order_registry.remove(&ctx.accounts.order.key());
+
+        emit!(OrderClosed {
+            pool: ctx.accounts.pool_account.key(),
+            order: ctx.accounts.order.key(),
+            seller: ctx.accounts.order_owner.key(),
+            tokens_returned,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Once the sale is over anyone could close an order left open, returning its tokens and rent to the seller,
+    /// so the orders never block `terminate`.
+    #[access_control(can_reclaim_order(&ctx.accounts.pool_account, &ctx.accounts.clock))]
+    pub fn reclaim_order(ctx: Context<ReclaimOrder>) -> Result<()> {
+        let tokens_returned = Tokens::new(ctx.accounts.order_token_vault.amount);
+
+        ctx.accounts.send_all_tokens_from_order_to_owner()?;
+        ctx.accounts.close_order_token_vault()?;
+
+        ctx.accounts.pool_account.open_orders -= 1;
+        ctx.accounts.order_registry.remove(&ctx.accounts.order.key());
+
+        emit!(OrderReclaimed {
+            pool: ctx.accounts.pool_account.key(),
+            order: ctx.accounts.order.key(),
+            seller: ctx.accounts.order_owner.key(),
+            reclaimer: ctx.accounts.reclaimer.key(),
+            tokens_returned,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
     }
 
     #[access_control(can_switch_to_buying_round(&ctx.accounts.pool_account, &ctx.accounts.clock))]
@@ -137,24 +338,758 @@ pub mod token_sale {
         let pool = &mut ctx.accounts.pool_account;
         pool.round_start_at = ctx.accounts.clock.unix_timestamp;
         pool.current_round = Round::Buying;
+        pool.round_number += 1;
+
+        pool.token_price = pool.try_next_token_price().expect("Calculates the next token price");
+
+        emit!(RoundSwitched {
+            pool: pool.key(),
+            round: Round::Buying,
+            token_price: pool.token_price,
+            timestamp: pool.round_start_at,
+        });
+
+        Ok(())
+    }
+
+    /// Adds a tranche of tokens for sale in the following buying rounds.
+    pub fn deposit_tokens(ctx: Context<DepositTokens>, amount: Tokens) -> Result<()> {
+        require!(!ctx.accounts.pool_account.mint_on_demand, ErrorCode::MintingOnDemand);
+
+        let admin_tokens = Tokens::new(ctx.accounts.admin_token_account.amount);
+        require!(amount <= admin_tokens, ErrorCode::NotEnoughTokensForSale);
+
+        let tokens_received = ctx.accounts.send_tokens_to_pool(amount)?;
+
+        let pool = &mut ctx.accounts.pool_account;
+        pool.total_supply += tokens_received;
+
+        emit!(TokensDeposited {
+            pool: pool.key(),
+            amount: tokens_received,
+            total_supply: pool.total_supply,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Hands the mint authority of the selling mint to the pool, so `buy` mints the bought tokens
+    /// instead of taking them from the vault. Only a pool initialized without tokens could mint.
+    /// `terminate` hands the mint authority to the admin or revokes it if `revoke_mint_authority` is set.
+    pub fn enable_minting(
+        ctx: Context<EnableMinting>,
+        max_supply: Option<Tokens>,
+        revoke_mint_authority: bool,
+    ) -> Result<()> {
+        let pool = &ctx.accounts.pool_account;
+        require!(!pool.mint_on_demand, ErrorCode::MintingAlreadyEnabled);
+        require!(pool.total_supply == Tokens::new(0), ErrorCode::PoolHoldsTokens);
+
+        ctx.accounts.hand_mint_authority_to_pool()?;
+
+        let pool = &mut ctx.accounts.pool_account;
+        pool.mint_on_demand = true;
+        pool.max_supply = max_supply;
+        pool.revoke_mint_authority = revoke_mint_authority;
+
+        emit!(MintingEnabled {
+            pool: pool.key(),
+            max_supply,
+            revoke_mint_authority,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Takes unsold tokens back from the pool between the buying rounds.
+    #[access_control(buying_not_running(&ctx.accounts.pool_account, &ctx.accounts.clock))]
+    pub fn withdraw_unsold_tokens(ctx: Context<WithdrawUnsoldTokens>, amount: Tokens) -> Result<()> {
+        let unsold_tokens = ctx.accounts.pool_account.unreserved_tokens(Tokens::new(ctx.accounts.vault_selling.amount));
+        require!(amount <= unsold_tokens, ErrorCode::InsufficientTokensInVault);
 
-        const PRECISENESS: u64 = 10000;
-        pool.token_price = pool.token_price
-            .checked_mul((pool.coeff_a * PRECISENESS as f32) as u64).unwrap()
-            .checked_div(PRECISENESS).unwrap()
-            .checked_add(pool.coeff_b as u64).unwrap();
+        ctx.accounts.send_tokens_from_pool_to_destination(amount)?;
+
+        let pool = &mut ctx.accounts.pool_account;
+        pool.total_supply -= amount;
+
+        emit!(UnsoldTokensWithdrawn {
+            pool: pool.key(),
+            amount,
+            destination: ctx.accounts.destination_token_account.key(),
+            total_supply: pool.total_supply,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
 
         Ok(())
     }
 
+    /// Sends all the lamports above the rent-exempt minimum of the pool to the treasury.
     pub fn withdraw_lamports(ctx: Context<WithdrawLamports>) -> Result<()> {
-        ctx.accounts.send_lamports_from_pool_to_owner()
+        let amount = ctx.accounts.send_lamports_from_pool_to_treasury()?;
+
+        emit!(LamportsWithdrawn {
+            pool: ctx.accounts.pool_account.key(),
+            treasury: ctx.accounts.treasury.key(),
+            amount,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// The admin could extend the IDO, change the durations of the future rounds
+    /// and the coefficients of the next token prices.
+    /// The duration of the currently running round could not be changed.
+    #[access_control(can_update_config(&ctx.accounts.pool_account, &ctx.accounts.clock))]
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        end_at: i64,
+        buying_duration: u32,
+        trading_duration: u32,
+        coeff_a: f32,
+        coeff_b: u32,
+    ) -> Result<()> {
+        let now = ctx.accounts.clock.unix_timestamp;
+        let pool = &mut ctx.accounts.pool_account;
+
+        require!(end_at >= pool.end_at, ErrorCode::EndAtCannotBeReduced);
+        valid_schedule(now, end_at, buying_duration, trading_duration)?;
+        valid_price_coefficients(coeff_a, coeff_b)?;
+
+        if pool.round_start_at <= now {
+            let running_round_duration_changed = match pool.current_round {
+                Round::Buying => pool.buying_duration != buying_duration,
+                Round::Trading => pool.trading_duration != trading_duration,
+                Round::Commit | Round::Reveal => false,
+            };
+            require!(!running_round_duration_changed, ErrorCode::RunningRoundDurationLocked);
+        }
+
+        pool.end_at = end_at;
+        pool.buying_duration = buying_duration;
+        pool.trading_duration = trading_duration;
+        pool.coeff_a = coeff_a;
+        pool.coeff_b = coeff_b;
+
+        emit!(ConfigUpdated {
+            pool: pool.key(),
+            end_at,
+            buying_duration,
+            trading_duration,
+            coeff_a,
+            coeff_b,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// The admin could hand over any of the roles, including the admin role itself.
+    pub fn update_roles(ctx: Context<UpdateRoles>, admin: Pubkey, treasury: Pubkey, operator: Pubkey) -> Result<()> {
+        let pool = &mut ctx.accounts.pool_account;
+        pool.admin = admin;
+        pool.treasury = treasury;
+        pool.operator = operator;
+
+        emit!(RolesUpdated {
+            pool: pool.key(),
+            admin,
+            treasury,
+            operator,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Nobody can buy or trade tokens while the sale is paused.
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.pool_account.paused = paused;
+
+        emit!(PausedChanged {
+            pool: ctx.accounts.pool_account.key(),
+            paused,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Changes what happens with the unsold tokens on termination, e.g. when the destination account is closed.
+    pub fn set_unsold_tokens_policy(
+        ctx: Context<SetUnsoldTokensPolicy>,
+        unsold_tokens_policy: UnsoldTokensPolicy,
+    ) -> Result<()> {
+        let destination = &ctx.accounts.unsold_tokens_destination;
+        valid_unsold_tokens_destination(&unsold_tokens_policy, destination, &ctx.accounts.pool_account.selling_mint)?;
+
+        let unsold_tokens_destination = match unsold_tokens_policy {
+            UnsoldTokensPolicy::SendToDestination => destination.key(),
+            UnsoldTokensPolicy::Burn | UnsoldTokensPolicy::ReturnToAdmin => Pubkey::default(),
+        };
+        let pool = &mut ctx.accounts.pool_account;
+        pool.unsold_tokens_policy = unsold_tokens_policy.clone();
+        pool.unsold_tokens_destination = unsold_tokens_destination;
+
+        emit!(UnsoldTokensPolicyChanged {
+            pool: pool.key(),
+            unsold_tokens_policy,
+            unsold_tokens_destination,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Limits the tokens sold within a slot and the pace of every buyer's purchases,
+    /// and rejects the purchases invoked by other programs than `trusted_caller` if `reject_cpi` is set.
+    /// Zero `max_tokens_per_slot` and `buy_cooldown` lift the limits.
+    pub fn set_buy_protection(
+        ctx: Context<SetBuyProtection>,
+        max_tokens_per_slot: Tokens,
+        buy_cooldown: u32,
+        reject_cpi: bool,
+        trusted_caller: Option<Pubkey>,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool_account;
+        pool.max_tokens_per_slot = max_tokens_per_slot;
+        pool.buy_cooldown = buy_cooldown;
+        pool.reject_cpi = reject_cpi;
+        pool.trusted_caller = trusted_caller;
+
+        emit!(BuyProtectionChanged {
+            pool: pool.key(),
+            max_tokens_per_slot,
+            buy_cooldown,
+            reject_cpi,
+            trusted_caller,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
     }
 
-    /// The program could be terminated after the `pool_account.end_at` time has passed.
+    /// Makes the sale tiered: a buyer gets the allocation of the highest tier the stake
+    /// of `stake_mint` tokens reaches and buys nothing without a stake. At most four tiers,
+    /// each with a larger `min_stake` and `allocation` than the previous one.
+    #[access_control(can_update_config(&ctx.accounts.pool_account, &ctx.accounts.clock))]
+    pub fn set_tiers(ctx: Context<SetTiers>, tiers: Vec<Tier>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool_account;
+        require!(!tiers.is_empty(), ErrorCode::NoTiers);
+        require!(tiers.len() <= pool.tiers.len(), ErrorCode::InvalidTiers);
+        let ascending = tiers.windows(2)
+            .all(|pair| pair[0].min_stake < pair[1].min_stake && pair[0].allocation < pair[1].allocation);
+        let used = tiers.iter().all(|tier| tier.allocation > Tokens::new(0));
+        require!(ascending && used, ErrorCode::InvalidTiers);
+
+        pool.stake_mint = Some(ctx.accounts.stake_mint.key());
+        pool.tiers = [Tier::default(); 4];
+        pool.tiers[..tiers.len()].copy_from_slice(&tiers);
+
+        emit!(TiersSet {
+            pool: pool.key(),
+            stake_mint: ctx.accounts.stake_mint.key(),
+            tiers,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Locks the tokens of the pool's `stake_mint` until the end of the sale to reach an allocation tier.
+    pub fn stake(ctx: Context<Stake>, amount: Tokens) -> Result<()> {
+        let now = ctx.accounts.clock.unix_timestamp;
+        require!(ctx.accounts.pool_account.end_at > now, ErrorCode::IDOOver);
+        require!(amount >= Tokens::new(1), ErrorCode::StakingToFewTokens);
+
+        ctx.accounts.send_tokens_from_staker_to_stake(amount)?;
+
+        let stake_account = &mut ctx.accounts.stake_account;
+        if stake_account.pool == Pubkey::default() {
+            stake_account.bump = ctx.bumps.stake_account;
+            stake_account.pool = ctx.accounts.pool_account.key();
+            stake_account.owner = ctx.accounts.staker.key();
+            stake_account.unlock_at = ctx.accounts.pool_account.end_at;
+        }
+        stake_account.amount += amount;
+
+        emit!(TokensStaked {
+            pool: stake_account.pool,
+            staker: stake_account.owner,
+            amount,
+            stake: stake_account.amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Returns all the staked tokens to the staker after the end of the sale and closes the stake.
+    pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
+        let now = ctx.accounts.clock.unix_timestamp;
+        require!(now >= ctx.accounts.stake_account.unlock_at, ErrorCode::StakeLocked);
+
+        let amount = Tokens::new(ctx.accounts.stake_vault.amount);
+        ctx.accounts.send_all_tokens_from_stake_to_staker()?;
+        ctx.accounts.close_stake_vault()?;
+
+        emit!(TokensUnstaked {
+            pool: ctx.accounts.stake_account.pool,
+            staker: ctx.accounts.staker.key(),
+            amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Refunds the rent of the buyer account to the buyer once the sale is over,
+    /// the cooldown and the allocations it keeps track of no longer apply then.
+    pub fn close_buyer_account(ctx: Context<CloseBuyerAccount>) -> Result<()> {
+        require!(ctx.accounts.sale_over()?, ErrorCode::IDONotOver);
+
+        emit!(BuyerAccountClosed {
+            pool: ctx.accounts.buyer_account.pool,
+            buyer: ctx.accounts.buyer.key(),
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Starts the fair launch: the wallets register tickets until `registration_ends_at`,
+    /// then `winners` tickets are drawn and only they buy, up to `allocation` tokens each.
+    /// `secret_hash` commits to the secret the admin reveals to draw the winners, see `lottery::secret_hash`.
+    #[access_control(can_update_config(&ctx.accounts.pool_account, &ctx.accounts.clock))]
+    pub fn start_lottery(
+        ctx: Context<StartLottery>,
+        registration_ends_at: i64,
+        winners: u32,
+        allocation: Tokens,
+        secret_hash: [u8; 32],
+    ) -> Result<()> {
+        let now = ctx.accounts.clock.unix_timestamp;
+        let pool = &mut ctx.accounts.pool_account;
+        require!(registration_ends_at > now && registration_ends_at < pool.end_at, ErrorCode::InvalidLottery);
+        require!(winners > 0 && allocation > Tokens::new(0), ErrorCode::InvalidLottery);
+
+        let lottery = &mut ctx.accounts.lottery_account;
+        lottery.bump = ctx.bumps.lottery_account;
+        lottery.pool = pool.key();
+        lottery.registration_ends_at = registration_ends_at;
+        lottery.winners = winners;
+        lottery.allocation = allocation;
+        lottery.secret_hash = secret_hash;
+        pool.lottery = lottery.key();
+
+        emit!(LotteryStarted {
+            pool: pool.key(),
+            registration_ends_at,
+            winners,
+            allocation,
+            secret_hash,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Registers the only ticket of the wallet in the lottery, numbered in the order of the registration.
+    #[access_control(
+        registration_open(&ctx.accounts.pool_account, &ctx.accounts.lottery_account, &ctx.accounts.clock)
+    )]
+    pub fn register_ticket(ctx: Context<RegisterTicket>) -> Result<()> {
+        let lottery = &mut ctx.accounts.lottery_account;
+        let ticket = &mut ctx.accounts.ticket_account;
+        ticket.bump = ctx.bumps.ticket_account;
+        ticket.pool = lottery.pool;
+        ticket.owner = ctx.accounts.owner.key();
+        ticket.number = lottery.tickets;
+        lottery.tickets += 1;
+
+        emit!(TicketRegistered {
+            pool: ticket.pool,
+            owner: ticket.owner,
+            number: ticket.number,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Fixes the slot whose hash is mixed into the draw. Its hash is not known yet,
+    /// so neither the admin nor the registered wallets could predict the winners.
+    /// The slot is fixed once, the admin could otherwise withhold the draws they dislike until another slot is picked.
+    #[access_control(registration_over(&ctx.accounts.lottery_account, &ctx.accounts.clock))]
+    pub fn close_registration(ctx: Context<CloseRegistration>) -> Result<()> {
+        let lottery_account = &mut ctx.accounts.lottery_account;
+        let slot = ctx.accounts.clock.slot;
+        require!(lottery_account.draw_slot.is_none(), ErrorCode::RegistrationOver);
+        lottery_account.draw_slot = Some(slot);
+
+        emit!(RegistrationClosed {
+            pool: lottery_account.pool,
+            tickets: lottery_account.tickets,
+            draw_slot: slot,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Reveals the committed secret and draws the winners from it and the hash of `draw_slot`.
+    /// The slot hashes sysvar keeps the last 512 slots, so the draw has to follow the closing within them,
+    /// the lottery could only be abandoned after them.
+    #[access_control(registration_over(&ctx.accounts.lottery_account, &ctx.accounts.clock))]
+    pub fn draw_lottery(ctx: Context<DrawLottery>, secret: [u8; 32]) -> Result<()> {
+        let lottery_account = &mut ctx.accounts.lottery_account;
+        let draw_slot = lottery_account.draw_slot.ok_or(ErrorCode::RegistrationOpen)?;
+        require!(lottery::secret_hash(&secret) == lottery_account.secret_hash, ErrorCode::WrongSecret);
+
+        let slot_hashes = ctx.accounts.slot_hashes.try_borrow_data()?;
+        lottery_account.slot_hash = lottery::find_slot_hash(&slot_hashes, draw_slot)
+            .ok_or(ErrorCode::SlotHashUnavailable)?;
+        lottery_account.seed = lottery::draw_seed(&secret, &lottery_account.slot_hash);
+        lottery_account.drawn = true;
+
+        emit!(LotteryDrawn {
+            pool: lottery_account.pool,
+            secret,
+            slot_hash: lottery_account.slot_hash,
+            seed: lottery_account.seed,
+            tickets: lottery_account.tickets,
+            winners: lottery_account.winners,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Lets everyone buy once the hash of the draw slot has expired undrawn, or the registration was not closed
+    /// within `LotteryAccount::DRAW_TIMEOUT` of its end, so withholding the secret does not stall the sale.
+    #[access_control(draw_timed_out(&ctx.accounts.lottery_account, &ctx.accounts.clock))]
+    pub fn abandon_lottery(ctx: Context<AbandonLottery>) -> Result<()> {
+        let lottery = &mut ctx.accounts.lottery_account;
+        lottery.abandoned = true;
+        ctx.accounts.pool_account.lottery = Pubkey::default();
+
+        emit!(LotteryAbandoned {
+            pool: lottery.pool,
+            tickets: lottery.tickets,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Trades a winning ticket for the allocation `buy` requires in a pool with a lottery.
+    pub fn claim_allocation(ctx: Context<ClaimAllocation>) -> Result<()> {
+        let lottery = &ctx.accounts.lottery_account;
+        let number = ctx.accounts.ticket_account.number;
+        require!(lottery.drawn, ErrorCode::LotteryNotDrawn);
+        require!(lottery.is_winner(number), ErrorCode::TicketLost);
+
+        let allocation = &mut ctx.accounts.allocation_account;
+        allocation.bump = ctx.bumps.allocation_account;
+        allocation.pool = lottery.pool;
+        allocation.owner = ctx.accounts.owner.key();
+        allocation.amount = lottery.allocation;
+
+        emit!(AllocationClaimed {
+            pool: allocation.pool,
+            owner: allocation.owner,
+            ticket: number,
+            amount: allocation.amount,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Sells `tokens` of `vault_selling` by a sealed-bid auction instead of the buying round at the pool price.
+    /// The wallets commit their bids for `commit_duration` seconds and reveal them for `reveal_duration` seconds,
+    /// then the highest revealed bids get the tokens at their own prices, see `auction::rank`.
+    #[access_control(can_start_auction(&ctx.accounts.pool_account, &ctx.accounts.clock))]
+    pub fn start_auction(
+        ctx: Context<StartAuction>,
+        commit_duration: u32,
+        reveal_duration: u32,
+        tokens: Tokens,
+    ) -> Result<()> {
+        let now = ctx.accounts.clock.unix_timestamp;
+        let pool = &mut ctx.accounts.pool_account;
+        let reveal_ends_at = now + commit_duration as i64 + reveal_duration as i64;
+        require!(commit_duration > 0 && reveal_duration > 0 && reveal_ends_at < pool.end_at, ErrorCode::InvalidAuction);
+        let vault_tokens = Tokens::new(ctx.accounts.vault_selling.amount);
+        require!(tokens >= Tokens::new(1) && tokens <= vault_tokens, ErrorCode::InvalidAuction);
+
+        pool.round_start_at = now;
+        pool.current_round = Round::Commit;
+        pool.round_number += 1;
+        pool.commit_duration = commit_duration;
+        pool.reveal_duration = reveal_duration;
+        pool.auction_tokens = tokens;
+
+        let auction_account = &mut ctx.accounts.auction_account;
+        auction_account.bump = ctx.bumps.auction_account;
+        auction_account.pool = pool.key();
+        auction_account.tokens = tokens;
+        auction_account.bids.clear();
+
+        emit!(AuctionStarted {
+            pool: pool.key(),
+            tokens,
+            commit_duration,
+            reveal_duration,
+            round_number: pool.round_number,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Commits the sealed bid of the wallet, `auction::bid_commitment` of the tokens, the price and a secret salt.
+    /// The deposit hides the bid as well, so it should be above the cost of the bid rather than equal to it.
+    #[access_control(round_committing(&ctx.accounts.pool_account, &ctx.accounts.clock))]
+    pub fn commit_bid(ctx: Context<CommitBid>, commitment: [u8; 32], deposit: Lamports) -> Result<()> {
+        send_lamports(ctx.accounts.bidder.to_account_info(), ctx.accounts.bid_account.to_account_info(), deposit)?;
+
+        let bid = &mut ctx.accounts.bid_account;
+        bid.bump = ctx.bumps.bid_account;
+        bid.pool = ctx.accounts.pool_account.key();
+        bid.bidder = ctx.accounts.bidder.key();
+        bid.commitment = commitment;
+        bid.deposit = deposit;
+        ctx.accounts.pool_account.open_bids += 1;
+
+        emit!(BidCommitted {
+            pool: bid.pool,
+            bidder: bid.bidder,
+            commitment,
+            deposit,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    #[access_control(can_switch_to_reveal_round(&ctx.accounts.pool_account, &ctx.accounts.clock))]
+    pub fn switch_to_reveal(ctx: Context<SwitchToReveal>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool_account;
+        pool.round_start_at = ctx.accounts.clock.unix_timestamp;
+        pool.current_round = Round::Reveal;
+        pool.round_number += 1;
+
+        emit!(RoundSwitched {
+            pool: pool.key(),
+            round: Round::Reveal,
+            token_price: pool.token_price,
+            timestamp: pool.round_start_at,
+        });
+
+        Ok(())
+    }
+
+    /// Reveals the committed bid and ranks it among the others. The price is in the units of
+    /// `PoolAccount::token_price` and could not be below it, the deposit has to cover the tokens at the price.
+    #[access_control(round_revealing(&ctx.accounts.pool_account, &ctx.accounts.clock))]
+    pub fn reveal_bid(ctx: Context<RevealBid>, tokens: Tokens, price: u64, salt: [u8; 32]) -> Result<()> {
+        let pool = &ctx.accounts.pool_account;
+        let bid = &mut ctx.accounts.bid_account;
+        require!(!bid.revealed, ErrorCode::BidAlreadyRevealed);
+        require!(auction::bid_commitment(&bid.bidder, tokens, price, &salt) == bid.commitment, ErrorCode::WrongBid);
+        require!(tokens >= Tokens::new(1) && price >= pool.token_price, ErrorCode::InvalidBid);
+        let cost = pool.try_tokens_to_lamports_at(price, tokens).ok_or(ErrorCode::BidNotCovered)?;
+        require!(cost <= bid.deposit, ErrorCode::BidNotCovered);
+
+        bid.revealed = true;
+        bid.tokens = tokens;
+        bid.price = price;
+
+        let auction_account = &mut ctx.accounts.auction_account;
+        let ranked = auction::RankedBid { bid: bid.key(), price, tokens };
+        let tokens_for_sale = auction_account.tokens;
+        auction::rank(&mut auction_account.bids, ranked, tokens_for_sale, AuctionAccount::MAX_BIDS);
+
+        emit!(BidRevealed {
+            pool: bid.pool,
+            bidder: bid.bidder,
+            tokens,
+            price,
+            ranked: auction_account.bids.contains(&ranked),
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Gives the bidder the tokens the bid won, paid from the deposit at the bid price, and refunds the rest.
+    /// The unrevealed and the losing bids get the whole deposit back. Anyone could settle a bid after the reveal.
+    #[access_control(auction_over(&ctx.accounts.pool_account, &ctx.accounts.clock))]
+    pub fn settle_bid(ctx: Context<SettleBid>) -> Result<()> {
+        let auction_account = &ctx.accounts.auction_account;
+        let bid = &ctx.accounts.bid_account;
+        let tokens = match bid.revealed {
+            true => auction::filled(&auction_account.bids, auction_account.tokens, &bid.key()),
+            false => Tokens::new(0),
+        };
+        let lamports_paid = ctx.accounts.pool_account
+            .try_tokens_to_lamports_at(bid.price, tokens)
+            .expect("The revealed deposit covers the bid");
+        let lamports_refunded = bid.deposit - lamports_paid;
+
+        if tokens > Tokens::new(0) {
+            ctx.accounts.send_tokens_from_pool_to_bidder(tokens)?;
+            ctx.accounts.pay_from_deposit(lamports_paid)?;
+        }
+
+        let pool = &mut ctx.accounts.pool_account;
+        pool.tokens_sold += tokens;
+        pool.auction_tokens -= tokens;
+        pool.open_bids -= 1;
+        if pool.open_bids == 0 && !matches!(pool.current_round, Round::Commit | Round::Reveal) {
+            pool.auction_tokens = Tokens::new(0);
+        }
+
+        emit!(BidSettled {
+            pool: pool.key(),
+            bidder: ctx.accounts.bidder.key(),
+            tokens,
+            price: ctx.accounts.bid_account.price,
+            lamports_paid,
+            lamports_refunded,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Returns `BuyQuote` with the lamports `buy` would charge for the amount right now.
+    pub fn quote_buy(ctx: Context<QuoteBuy>, amount: Tokens) -> Result<()> {
+        let pool = &ctx.accounts.pool_account;
+        let lamports = pool
+            .try_tokens_to_lamports(amount)
+            .ok_or(ErrorCode::AmountTooLarge)?;
+
+        set_return(&BuyQuote {
+            amount,
+            lamports,
+            tokens_in_vault: pool.tokens_for_sale(Tokens::new(ctx.accounts.vault_selling.amount)),
+        })
+    }
+
+    /// Returns `RedeemQuote` with the lamports `redeem_order` would charge for the amount right now.
+    pub fn quote_redeem(ctx: Context<QuoteRedeem>, amount: Tokens) -> Result<()> {
+        let lamports = ctx.accounts.pool_account
+            .try_tokens_to_lamports(amount)
+            .ok_or(ErrorCode::AmountTooLarge)?;
+
+        set_return(&RedeemQuote {
+            amount,
+            lamports,
+            tokens_in_order: Tokens::new(ctx.accounts.token_vault.amount),
+        })
+    }
+
+    /// Returns `PoolStatus`, the state of the pool as seen at the current time.
+    pub fn pool_status(ctx: Context<ViewPoolStatus>) -> Result<()> {
+        let pool = &ctx.accounts.pool_account;
+        let now = ctx.accounts.clock.unix_timestamp;
+        let round_ends_at = pool.round_start_at + pool.round_duration() as i64;
+
+        set_return(&PoolStatus {
+            current_round: pool.current_round.clone(),
+            paused: pool.paused,
+            round_ends_at,
+            seconds_left: (round_ends_at - now).max(0),
+            token_price: pool.token_price,
+            price_units: pool.price_units.max(1),
+            round_number: pool.round_number,
+            tokens_left_in_slot: pool.tokens_left_in_slot(ctx.accounts.clock.slot),
+            next_token_price: pool.try_next_token_price(),
+            tokens_in_vault: pool.tokens_for_sale(Tokens::new(ctx.accounts.vault_selling.amount)),
+            tokens_sold: pool.tokens_sold,
+            open_orders: pool.open_orders,
+            ido_over: pool.end_at <= now,
+        })
+    }
+
+    /// Brings the pool and its orders passed as the remaining accounts to the current layout.
+    /// The admin pays for the rent of the grown accounts and of the order registry if the pool has none yet,
+    /// the accounts that are up to date are left as they are.
+    /// The sales started before the sale ids were introduced are the sales 0 of their mints.
+    /// Their orders are listed in the page `registry_page` of the order registry,
+    /// so more than `OrderRegistry::MAX_ORDERS` of them are migrated in several calls.
+    pub fn migrate_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigratePool<'info>>,
+        _sale_id: u64,
+        registry_page: u32,
+    ) -> Result<()> {
+        let registry_bump = ctx.bumps.order_registry;
+        let mut pool = ctx.accounts.migrate_pool(registry_bump, registry_page)?;
+
+        let mut orders_migrated = 0;
+        for order in ctx.remaining_accounts {
+            if ctx.accounts.migrate_order(&mut pool, order, registry_page)? {
+                orders_migrated += 1;
+            }
+        }
+        ctx.accounts.save_pool(&pool)?;
+
+        emit!(PoolMigrated {
+            pool: ctx.accounts.pool_account.key(),
+            version: PoolAccount::VERSION,
+            orders_migrated,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// The program could be terminated after the `pool_account.end_at` time has passed
+    /// and all the orders are closed by their owners.
+    /// The unsold tokens are disposed according to `pool_account.unsold_tokens_policy`,
+    /// the mint authority of a minting pool is handed to the admin or revoked.
+    /// The pages of the order registry after the first one are passed as the remaining accounts and closed too.
     #[access_control(can_terminate(&ctx.accounts.pool_account, &ctx.accounts.clock))]
-    pub fn terminate(ctx: Context<Terminate>) -> Result<()> {
-        ctx.accounts.burn_left_tokens()?;
-        ctx.accounts.close_vault_selling()
+    pub fn terminate<'info>(ctx: Context<'_, '_, 'info, 'info, Terminate<'info>>) -> Result<()> {
+        let unsold_tokens = Tokens::new(ctx.accounts.vault_selling.amount);
+        let unsold_tokens_receiver = ctx.accounts.pool_account.unsold_tokens_receiver(ctx.accounts.token_program.key);
+
+        let transfer_fee = match unsold_tokens_receiver {
+            None => {
+                ctx.accounts.burn_left_tokens()?;
+                Tokens::new(0)
+            }
+            Some(receiver) => {
+                require!(
+                    ctx.accounts.unsold_tokens_receiver.key() == receiver,
+                    ErrorCode::WrongUnsoldTokensReceiver
+                );
+                ctx.accounts.send_left_tokens_to_receiver()?
+            }
+        };
+
+        ctx.accounts.close_vault_selling()?;
+        for registry_page in ctx.remaining_accounts {
+            ctx.accounts.close_registry_page(registry_page)?;
+        }
+
+        if ctx.accounts.pool_account.mint_on_demand {
+            ctx.accounts.release_mint_authority()?;
+        }
+
+        emit!(SaleTerminated {
+            pool: ctx.accounts.pool_account.key(),
+            tokens_sold: ctx.accounts.pool_account.tokens_sold,
+            unsold_tokens,
+            unsold_tokens_receiver,
+            transfer_fee,
+            timestamp: ctx.accounts.clock.unix_timestamp,
+        });
+
+        Ok(())
     }
 }
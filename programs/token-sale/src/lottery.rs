@@ -0,0 +1,68 @@
+//! The commit-reveal draw of the lottery, kept free of accounts so anyone could verify the winners off-chain.
+use anchor_lang::solana_program::hash::hashv;
+
+/// The slots the slot hashes sysvar keeps the hashes of
+pub const SLOT_HASHES_KEPT: u64 = 512;
+/// The rounds of the Feistel network shuffling the tickets
+const SHUFFLE_ROUNDS: u8 = 4;
+
+/// The commitment to the secret published by `start_lottery`
+pub fn secret_hash(secret: &[u8; 32]) -> [u8; 32] {
+    hashv(&[secret]).to_bytes()
+}
+
+/// The seed the winners follow from: the secret mixed with the hash of a slot
+/// that was produced only after the registration closed
+pub fn draw_seed(secret: &[u8; 32], slot_hash: &[u8; 32]) -> [u8; 32] {
+    hashv(&[secret, slot_hash]).to_bytes()
+}
+
+/// Whether the ticket `number` is drawn. The tickets are shuffled by the seed and the first `winners` of them win,
+/// so exactly `min(winners, tickets)` tickets win, each with the same chance wherever it was registered.
+pub fn is_winner(seed: &[u8; 32], number: u32, tickets: u32, winners: u32) -> bool {
+    number < tickets && shuffled_position(seed, number, tickets) < winners
+}
+
+/// The position of the ticket `number` among the tickets shuffled by the seed, a permutation of `0..tickets`.
+/// A Feistel network keyed by the seed permutes the numbers of `2 * half_bits` bits and the permutation is walked
+/// until it lands within the tickets, so a ticket is placed without shuffling all of them.
+pub fn shuffled_position(seed: &[u8; 32], number: u32, tickets: u32) -> u32 {
+    let bits = u32::BITS - tickets.saturating_sub(1).leading_zeros();
+    let half_bits = bits.div_ceil(2);
+    let mask = (1u64 << half_bits) - 1;
+
+    let mut position = number as u64;
+    loop {
+        let (mut left, mut right) = (position >> half_bits, position & mask);
+        for round in 0..SHUFFLE_ROUNDS {
+            let hash = hashv(&[seed, &[round], &right.to_le_bytes()]).to_bytes();
+            let key = u64::from_le_bytes(hash[..8].try_into().expect("The hash has 8 bytes")) & mask;
+            (left, right) = (right, left ^ key);
+        }
+        position = (left << half_bits) | right;
+        if position < tickets as u64 {
+            return position as u32;
+        }
+    }
+}
+
+/// Whether the slot hashes sysvar no longer keeps the hash of `draw_slot` at `slot`, so the winners could not be drawn
+pub fn slot_hash_expired(draw_slot: u64, slot: u64) -> bool {
+    slot > draw_slot.saturating_add(SLOT_HASHES_KEPT)
+}
+
+/// The hash of the slot in the data of the slot hashes sysvar, `None` if the sysvar does not keep it.
+/// The data is read as is, the sysvar is too large to be deserialized by a program.
+pub fn find_slot_hash(slot_hashes: &[u8], slot: u64) -> Option<[u8; 32]> {
+    const ENTRY: usize = 8 + 32;
+    let (len, entries) = (slot_hashes.get(..8)?, slot_hashes.get(8..)?);
+    let len = u64::from_le_bytes(len.try_into().ok()?) as usize;
+
+    entries.chunks_exact(ENTRY).take(len).find_map(|entry| {
+        let (entry_slot, hash) = entry.split_at(8);
+        match u64::from_le_bytes(entry_slot.try_into().ok()?) == slot {
+            true => hash.try_into().ok(),
+            false => None,
+        }
+    })
+}
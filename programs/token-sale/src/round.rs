@@ -1,7 +1,11 @@
 use anchor_lang::prelude::{AnchorSerialize, AnchorDeserialize};
 
-#[derive(PartialEq, Eq, AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(PartialEq, Eq, AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub enum Round {
     Buying,
     Trading,
+    /// The sealed bids of the auction are committed, see `start_auction`
+    Commit,
+    /// The committed bids are revealed and ranked, see `switch_to_reveal`
+    Reveal,
 }
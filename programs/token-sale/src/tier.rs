@@ -0,0 +1,17 @@
+use anchor_lang::prelude::{AnchorSerialize, AnchorDeserialize};
+use crate::currency::Tokens;
+
+/// The allocation of a wallet that staked at least `min_stake` tokens of `PoolAccount::stake_mint`
+#[derive(PartialEq, Eq, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct Tier {
+    pub min_stake: Tokens,
+    /// The most tokens the wallet buys from the pool, the tier is unused if zero
+    pub allocation: Tokens,
+}
+
+impl Default for Tier {
+    /// The unused tier
+    fn default() -> Self {
+        Tier { min_stake: Tokens::new(0), allocation: Tokens::new(0) }
+    }
+}
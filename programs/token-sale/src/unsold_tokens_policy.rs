@@ -0,0 +1,11 @@
+use anchor_lang::prelude::{AnchorSerialize, AnchorDeserialize};
+
+/// What happens with the tokens left in `vault_selling` when the IDO is terminated
+#[derive(PartialEq, Eq, AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum UnsoldTokensPolicy {
+    Burn,
+    /// Send to the associated token account of the admin
+    ReturnToAdmin,
+    /// Send to `PoolAccount::unsold_tokens_destination`
+    SendToDestination,
+}
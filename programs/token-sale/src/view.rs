@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::currency::{Lamports, Tokens};
+use crate::round::Round;
+
+// The values returned by the view instructions with `set_return_data`.
+// The instructions change nothing, so they are meant to be simulated rather than sent.
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct BuyQuote {
+    pub amount: Tokens,
+    /// The lamports `buy` would charge for the amount
+    pub lamports: Lamports,
+    /// The tokens left for sale, `buy` fails if the amount is larger. See `PoolAccount::tokens_for_sale`:
+    /// the tokens held for the auction are left out and a pool minting on demand counts the tokens it could mint.
+    pub tokens_in_vault: Tokens,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct RedeemQuote {
+    pub amount: Tokens,
+    /// The lamports `redeem_order` would send to the owner of the order
+    pub lamports: Lamports,
+    /// `redeem_order` fails if the amount is larger
+    pub tokens_in_order: Tokens,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct PoolStatus {
+    pub current_round: Round,
+    pub paused: bool,
+    pub round_ends_at: i64,
+    /// Zero when the round is over but not switched yet
+    pub seconds_left: i64,
+    /// Lamports for `price_units` minimal parts of the token
+    pub token_price: u64,
+    pub price_units: u64,
+    /// See `PoolAccount::round_number`
+    pub round_number: u32,
+    /// The tokens `buy` could still sell in the current slot, `u64::MAX` if not limited
+    pub tokens_left_in_slot: Tokens,
+    /// The price after the next switch to the buying round, `None` on overflow
+    pub next_token_price: Option<u64>,
+    /// The tokens left for sale, as `BuyQuote::tokens_in_vault`
+    pub tokens_in_vault: Tokens,
+    pub tokens_sold: Tokens,
+    pub open_orders: u32,
+    /// Is the IDO over, so it could be terminated?
+    pub ido_over: bool,
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+use token_sale::error::ErrorCode;
+
+#[tokio::test]
+async fn updates_the_config() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let config = SaleConfig {
+        end_at: sale.config.end_at + 100,
+        buying_duration: 200,
+        trading_duration: 50,
+        coeff_a: 2.0,
+        coeff_b: 0,
+        ..sale.config.clone()
+    };
+
+    // The first round has not started yet, so its duration could be changed too
+    sale.update_config(&admin, &config).await.unwrap();
+
+    let pool = sale.pool().await;
+    assert_eq!(pool.end_at, config.end_at);
+    assert_eq!(pool.buying_duration, 200);
+    assert_eq!(pool.trading_duration, 50);
+    assert_eq!(pool.coeff_a, 2.0);
+    assert_eq!(pool.coeff_b, 0);
+}
+
+#[tokio::test]
+async fn does_not_update_the_config_unsafely() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let config = sale.config.clone();
+    sale.warp_to(config.round_start_at).await;
+
+    let stranger = Keypair::new();
+    assert_error(sale.update_config(&stranger, &config).await, ErrorCode::OnlyAdmin);
+
+    let earlier_end = SaleConfig { end_at: config.end_at - 1, ..config.clone() };
+    assert_error(sale.update_config(&admin, &earlier_end).await, ErrorCode::EndAtCannotBeReduced);
+
+    let longer_buying = SaleConfig { buying_duration: 101, ..config.clone() };
+    assert_error(sale.update_config(&admin, &longer_buying).await, ErrorCode::RunningRoundDurationLocked);
+
+    let too_long_rounds = SaleConfig { trading_duration: 10_000, ..config.clone() };
+    assert_error(sale.update_config(&admin, &too_long_rounds).await, ErrorCode::EndsBeforeFullCircle);
+
+    let zero_price = SaleConfig { coeff_a: 0.0, coeff_b: 0, ..config.clone() };
+    assert_error(sale.update_config(&admin, &zero_price).await, ErrorCode::InvalidPriceCoefficients);
+
+    sale.warp_to(config.end_at).await;
+    assert_error(sale.update_config(&admin, &config).await, ErrorCode::IDOOver);
+}
+
+#[tokio::test]
+async fn hands_over_the_roles() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let new_admin = Keypair::new();
+    let treasury = Keypair::new().pubkey();
+    let operator = Keypair::new().pubkey();
+
+    let stranger = Keypair::new();
+    assert_error(
+        sale.update_roles(&stranger, stranger.pubkey(), treasury, operator).await,
+        ErrorCode::OnlyAdmin,
+    );
+
+    sale.update_roles(&admin, new_admin.pubkey(), treasury, operator).await.unwrap();
+
+    let pool = sale.pool().await;
+    assert_eq!(pool.admin, new_admin.pubkey());
+    assert_eq!(pool.treasury, treasury);
+    assert_eq!(pool.operator, operator);
+    assert_error(sale.set_paused(&admin, true).await, ErrorCode::OnlyAdmin);
+    sale.set_paused(&new_admin, true).await.unwrap();
+}
+
+#[tokio::test]
+async fn withdraws_lamports_to_the_treasury() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let treasury = Keypair::new().pubkey();
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let pool = sale.pool;
+    sale.update_roles(&admin, admin.pubkey(), treasury, admin.pubkey()).await.unwrap();
+    sale.airdrop(&treasury, LAMPORTS_PER_SOL).await;
+    sale.buy(&buyer, 100).await.unwrap();
+    let pool_lamports_before = sale.lamports(&pool).await;
+
+    assert_error(sale.withdraw_lamports(&buyer.signer, &treasury).await, ErrorCode::OnlyAdmin);
+    sale.withdraw_lamports(&admin, &treasury).await.unwrap();
+
+    let pool_data_len = sale.account(&pool).await.unwrap().data.len();
+    let rent = sale.ctx.banks_client.get_rent().await.unwrap().minimum_balance(pool_data_len);
+    assert_eq!(sale.lamports(&pool).await, rent);
+    assert_eq!(sale.lamports(&treasury).await, LAMPORTS_PER_SOL + pool_lamports_before - rent);
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use token_sale::error::ErrorCode;
+
+#[tokio::test]
+async fn limits_the_tokens_sold_within_a_slot() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let first = sale.trader(LAMPORTS_PER_SOL).await;
+    let second = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.set_buy_protection(&admin, 10, 0, false, None).await.unwrap();
+
+    sale.buy(&first, 6).await.unwrap();
+    assert_error(sale.buy(&second, 5).await, ErrorCode::SlotLimitReached);
+    sale.buy(&second, 4).await.unwrap();
+    assert_eq!(u64::from(sale.pool().await.tokens_sold_in_slot), 10);
+
+    sale.next_slot().await;
+    sale.buy(&second, 7).await.unwrap();
+    // Only the tokens left in the slot are bought for the lamports
+    sale.buy_with_lamports(&first, LAMPORTS_PER_SOL / 10).await.unwrap();
+    assert_eq!(sale.tokens(&first.ata).await, 6 + 3);
+    assert_error(sale.buy_with_lamports(&first, LAMPORTS_PER_SOL).await, ErrorCode::BuyingToFewTokens);
+}
+
+#[tokio::test]
+async fn keeps_the_buyer_waiting_between_purchases() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let other = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.set_buy_protection(&admin, 0, 60, false, None).await.unwrap();
+
+    sale.buy(&buyer, 1).await.unwrap();
+    let record = sale.buyer_account(&buyer.signer.pubkey()).await.unwrap();
+    assert_eq!(record.pool, sale.pool);
+    assert_eq!(record.buyer, buyer.signer.pubkey());
+    assert_eq!(record.last_buy_at, sale.now().await);
+
+    sale.warp_to(record.last_buy_at + 59).await;
+    assert_error(sale.buy(&buyer, 2).await, ErrorCode::BuyCooldown);
+    sale.buy(&other, 2).await.unwrap();
+
+    sale.warp_to(record.last_buy_at + 60).await;
+    sale.buy(&buyer, 2).await.unwrap();
+    assert_eq!(sale.tokens(&buyer.ata).await, 3);
+}
+
+#[tokio::test]
+async fn creates_the_buyer_account_only_when_the_purchases_are_limited() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let other = sale.trader(LAMPORTS_PER_SOL).await;
+
+    sale.buy(&buyer, 1).await.unwrap();
+    assert!(sale.buyer_account(&buyer.signer.pubkey()).await.is_none());
+
+    // Lamports sent to the address beforehand do not block the creation
+    let other_account = sale.buyer_account_address(&other.signer.pubkey());
+    sale.airdrop(&other_account, LAMPORTS_PER_SOL / 1_000).await;
+    sale.set_buy_protection(&admin, 0, 60, false, None).await.unwrap();
+    sale.buy(&other, 1).await.unwrap();
+    assert_eq!(u64::from(sale.buyer_account(&other.signer.pubkey()).await.unwrap().tokens_bought), 1);
+}
+
+#[tokio::test]
+async fn refunds_the_rent_of_the_buyer_account_after_the_sale() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let other = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.set_buy_protection(&admin, 0, 1, false, None).await.unwrap();
+    sale.buy(&buyer, 1).await.unwrap();
+    sale.buy(&other, 1).await.unwrap();
+    let buyer_account = sale.buyer_account_address(&buyer.signer.pubkey());
+    let rent = sale.lamports(&buyer_account).await;
+
+    assert_error(sale.close_buyer_account(&buyer).await, ErrorCode::IDONotOver);
+
+    sale.warp_to(sale.config.end_at).await;
+    let lamports = sale.lamports(&buyer.signer.pubkey()).await;
+    sale.close_buyer_account(&buyer).await.unwrap();
+    assert!(sale.buyer_account(&buyer.signer.pubkey()).await.is_none());
+    assert_eq!(sale.lamports(&buyer.signer.pubkey()).await, lamports + rent);
+
+    // The pool is gone after the termination
+    sale.terminate(None).await.unwrap();
+    sale.close_buyer_account(&other).await.unwrap();
+    assert!(sale.buyer_account(&other.signer.pubkey()).await.is_none());
+}
+
+#[tokio::test]
+async fn rejects_the_purchases_of_unknown_programs() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+
+    sale.buy_by_cpi(&buyer, 1).await.unwrap();
+
+    sale.set_buy_protection(&admin, 0, 0, true, None).await.unwrap();
+    assert_error(sale.buy_by_cpi(&buyer, 2).await, ErrorCode::UnknownCaller);
+    sale.buy(&buyer, 2).await.unwrap();
+
+    sale.set_buy_protection(&admin, 0, 0, true, Some(relay_id())).await.unwrap();
+    sale.buy_by_cpi(&buyer, 3).await.unwrap();
+    assert_eq!(sale.tokens(&buyer.ata).await, 6);
+}
+
+#[tokio::test]
+async fn only_admin_sets_the_protection() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let stranger = sale.trader(LAMPORTS_PER_SOL).await;
+
+    assert_error(sale.set_buy_protection(&stranger.signer, 10, 0, false, None).await, ErrorCode::OnlyAdmin);
+}
@@ -0,0 +1,158 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use token_sale::account::BidAccount;
+use token_sale::error::ErrorCode;
+use token_sale::round::Round;
+
+const SALT: [u8; 32] = [9; 32];
+
+#[tokio::test]
+async fn sells_to_the_highest_revealed_bids() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+    let (high, low, hidden) =
+        (sale.trader(LAMPORTS_PER_SOL).await, sale.trader(LAMPORTS_PER_SOL).await, sale.trader(LAMPORTS_PER_SOL).await);
+
+    sale.start_auction(&operator, 50, 50, 100).await.unwrap();
+    assert_eq!(sale.pool().await.current_round, Round::Commit);
+    assert_error(sale.buy(&high, 1).await, ErrorCode::NotBuyingRound);
+
+    // The deposits are larger than the bids, so they do not give the prices away
+    sale.commit_bid(&high, 60, 3_000, &SALT, 200_000).await.unwrap();
+    sale.commit_bid(&low, 60, 2_000, &SALT, 200_000).await.unwrap();
+    sale.commit_bid(&hidden, 10, 5_000, &SALT, 100_000).await.unwrap();
+    assert_eq!(sale.pool().await.open_bids, 3);
+    assert_error(sale.reveal_bid(&high, 60, 3_000, SALT).await, ErrorCode::NotRevealRound);
+    assert_error(sale.switch_to_reveal().await, ErrorCode::CommitRunning);
+
+    sale.warp_to(START + 50).await;
+    let late = sale.trader(LAMPORTS_PER_SOL).await;
+    assert_error(sale.commit_bid(&late, 1, 3_000, &SALT, 3_000).await, ErrorCode::CommitOver);
+    sale.switch_to_reveal().await.unwrap();
+    sale.reveal_bid(&low, 60, 2_000, SALT).await.unwrap();
+    sale.reveal_bid(&high, 60, 3_000, SALT).await.unwrap();
+    assert_error(sale.reveal_bid(&hidden, 10, 5_000, [0; 32]).await, ErrorCode::WrongBid);
+    assert_error(sale.settle_bid(&high).await, ErrorCode::AuctionRunning);
+
+    let auction = sale.auction().await.unwrap();
+    let ranked: Vec<u64> = auction.bids.iter().map(|bid| bid.price).collect();
+    assert_eq!(ranked, vec![3_000, 2_000]);
+
+    sale.warp_to(START + 100).await;
+    let pool = sale.pool;
+    let pool_lamports = sale.lamports(&pool).await;
+    let bid_rent = sale.rent(8 + BidAccount::SPACE).await;
+    for bidder in [&high, &low, &hidden] {
+        let lamports = sale.lamports(&bidder.signer.pubkey()).await;
+        sale.settle_bid(bidder).await.unwrap();
+        assert!(sale.bid_account(&bidder.signer.pubkey()).await.is_none());
+        assert!(sale.lamports(&bidder.signer.pubkey()).await > lamports + bid_rent);
+    }
+
+    // Every bid pays its own price, the lower bid gets what is left
+    assert_eq!(sale.tokens(&high.ata).await, 60);
+    assert_eq!(sale.tokens(&low.ata).await, 40);
+    assert_eq!(sale.tokens(&hidden.ata).await, 0);
+    assert_eq!(sale.lamports(&pool).await, pool_lamports + 60 * 3_000 + 40 * 2_000);
+    let pool = sale.pool().await;
+    assert_eq!((pool.open_bids, u64::from(pool.auction_tokens), u64::from(pool.tokens_sold)), (0, 0, 100));
+
+    sale.switch_to_trading(&operator).await.unwrap();
+    assert_eq!(sale.pool().await.current_round, Round::Trading);
+}
+
+#[tokio::test]
+async fn validates_the_auction_and_the_bids() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+    let bidder = sale.trader(LAMPORTS_PER_SOL).await;
+
+    let end = (sale.config.end_at - START) as u32;
+    for (commit, reveal, tokens) in [(0, 50, 100), (50, 0, 100), (end, 50, 100), (50, 50, 0), (50, 50, 10_001)] {
+        assert_error(sale.start_auction(&operator, commit, reveal, tokens).await, ErrorCode::InvalidAuction);
+    }
+    assert_error(sale.start_auction(&bidder.signer, 50, 50, 100).await, ErrorCode::OnlyOperator);
+
+    sale.start_auction(&operator, 50, 50, 100).await.unwrap();
+    assert_error(sale.start_auction(&operator, 50, 50, 100).await, ErrorCode::AuctionRunning);
+    assert_error(sale.switch_to_trading(&operator).await, ErrorCode::AuctionRunning);
+    assert_error(sale.switch_to_buying(&operator).await, ErrorCode::NotTradingRound);
+
+    // The tokens of the auction could not be withdrawn meanwhile
+    let destination = sale.admin_ata;
+    assert_error(sale.withdraw_unsold_tokens(9_901, &destination).await, ErrorCode::InsufficientTokensInVault);
+
+    let (cheap, uncovered) = (sale.trader(LAMPORTS_PER_SOL).await, sale.trader(LAMPORTS_PER_SOL).await);
+    sale.commit_bid(&bidder, 10, 1_000, &SALT, 10_000).await.unwrap();
+    sale.commit_bid(&cheap, 10, 999, &SALT, 10_000).await.unwrap();
+    sale.commit_bid(&uncovered, 10, 1_001, &SALT, 10_000).await.unwrap();
+
+    sale.warp_to(START + 50).await;
+    sale.switch_to_reveal().await.unwrap();
+    assert_error(sale.reveal_bid(&cheap, 10, 999, SALT).await, ErrorCode::InvalidBid);
+    assert_error(sale.reveal_bid(&uncovered, 10, 1_001, SALT).await, ErrorCode::BidNotCovered);
+    sale.reveal_bid(&bidder, 10, 1_000, SALT).await.unwrap();
+    sale.next_slot().await;
+    assert_error(sale.reveal_bid(&bidder, 10, 1_000, SALT).await, ErrorCode::BidAlreadyRevealed);
+
+    sale.warp_to(START + 100).await;
+    assert_error(sale.reveal_bid(&cheap, 10, 999, SALT).await, ErrorCode::RevealOver);
+    sale.switch_to_trading(&operator).await.unwrap();
+    assert_error(sale.start_auction(&operator, 50, 50, 100).await, ErrorCode::BidsNotSettled);
+
+    // The unsold tokens of the auction are released once its last bid is settled
+    for trader in [&bidder, &cheap, &uncovered] {
+        sale.settle_bid(trader).await.unwrap();
+    }
+    let pool = sale.pool().await;
+    assert_eq!((pool.open_bids, u64::from(pool.auction_tokens), u64::from(pool.tokens_sold)), (0, 0, 10));
+    sale.start_auction(&operator, 50, 50, 100).await.unwrap();
+}
+
+#[tokio::test]
+async fn anyone_switches_to_reveal_after_the_commit_round() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+    let bidder = sale.trader(LAMPORTS_PER_SOL).await;
+
+    sale.start_auction(&operator, 50, 50, 100).await.unwrap();
+    sale.commit_bid(&bidder, 10, 1_000, &SALT, 10_000).await.unwrap();
+
+    // Only the payer of the transaction signs, not the operator
+    sale.warp_to(START + 50).await;
+    sale.switch_to_reveal().await.unwrap();
+    assert_eq!(sale.pool().await.current_round, Round::Reveal);
+    sale.reveal_bid(&bidder, 10, 1_000, SALT).await.unwrap();
+}
+
+#[tokio::test]
+async fn refunds_the_bids_once_the_auction_expired_in_the_commit_round() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+    let bidder = sale.trader(LAMPORTS_PER_SOL).await;
+
+    sale.start_auction(&operator, 50, 50, 100).await.unwrap();
+    sale.commit_bid(&bidder, 10, 1_000, &SALT, 10_000).await.unwrap();
+
+    // Nobody switched to the reveal round
+    sale.warp_to(START + 99).await;
+    assert_error(sale.settle_bid(&bidder).await, ErrorCode::AuctionRunning);
+
+    sale.warp_to(START + 100).await;
+    let lamports = sale.lamports(&bidder.signer.pubkey()).await;
+    let bid_rent = sale.rent(8 + BidAccount::SPACE).await;
+    sale.settle_bid(&bidder).await.unwrap();
+    assert!(sale.bid_account(&bidder.signer.pubkey()).await.is_none());
+    assert!(sale.lamports(&bidder.signer.pubkey()).await > lamports + bid_rent);
+    assert_eq!(sale.tokens(&bidder.ata).await, 0);
+
+    // The tokens of the auction are for sale again after the switch to trading
+    sale.switch_to_trading(&operator).await.unwrap();
+    let pool = sale.pool().await;
+    assert_eq!((pool.open_bids, u64::from(pool.auction_tokens), u64::from(pool.tokens_sold)), (0, 0, 0));
+    sale.start_auction(&operator, 50, 50, 100).await.unwrap();
+}
@@ -0,0 +1,150 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use token_sale::error::ErrorCode;
+
+#[tokio::test]
+async fn buys_tokens_from_the_pool() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let pool = sale.pool;
+    let vault_selling = sale.vault_selling;
+    let pool_lamports_before = sale.lamports(&pool).await;
+    let buyer_lamports_before = sale.lamports(&buyer.signer.pubkey()).await;
+
+    sale.buy(&buyer, 7).await.unwrap();
+
+    let cost = 7 * sale.config.token_price;
+    // The sale does not limit the purchases, so no record of them is created at the buyer's expense
+    assert_eq!(sale.lamports(&pool).await, pool_lamports_before + cost);
+    assert_eq!(sale.lamports(&buyer.signer.pubkey()).await, buyer_lamports_before - cost);
+    assert!(sale.buyer_account(&buyer.signer.pubkey()).await.is_none());
+    assert_eq!(sale.tokens(&buyer.ata).await, 7);
+    assert_eq!(sale.tokens(&vault_selling).await, sale.config.amount_to_sell - 7);
+    assert_eq!(u64::from(sale.pool().await.tokens_sold), 7);
+}
+
+#[tokio::test]
+async fn rounds_the_price_up_for_the_pool() {
+    // 3 lamports for 1_000 minimal parts
+    let config = SaleConfig { token_price: 3, price_units: 1_000, ..SaleConfig::default() };
+    let mut sale = TestSale::start(config).await;
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let pool = sale.pool;
+    let pool_lamports_before = sale.lamports(&pool).await;
+
+    sale.buy(&buyer, 1_001).await.unwrap();
+    sale.buy(&buyer, 1).await.unwrap();
+
+    assert_eq!(sale.lamports(&pool).await, pool_lamports_before + 4 + 1);
+    assert_eq!(sale.tokens(&buyer.ata).await, 1_002);
+}
+
+#[tokio::test]
+async fn buys_as_many_tokens_as_the_lamports_pay_for() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let pool = sale.pool;
+    let pool_lamports_before = sale.lamports(&pool).await;
+
+    // 7 tokens for 1_000 lamports each, the rest is not charged
+    sale.buy_with_lamports(&buyer, 7_999).await.unwrap();
+
+    assert_eq!(sale.tokens(&buyer.ata).await, 7);
+    assert_eq!(sale.lamports(&pool).await, pool_lamports_before + 7_000);
+    assert_error(sale.buy_with_lamports(&buyer, 999).await, ErrorCode::BuyingToFewTokens);
+}
+
+#[tokio::test]
+async fn spending_is_capped_by_the_vault() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let buyer = sale.trader(100 * LAMPORTS_PER_SOL).await;
+    let pool = sale.pool;
+    let pool_lamports_before = sale.lamports(&pool).await;
+
+    sale.buy_with_lamports(&buyer, 50 * LAMPORTS_PER_SOL).await.unwrap();
+
+    let amount_to_sell = sale.config.amount_to_sell;
+    assert_eq!(sale.tokens(&buyer.ata).await, amount_to_sell);
+    assert_eq!(sale.lamports(&pool).await, pool_lamports_before + amount_to_sell * sale.config.token_price);
+    assert_error(sale.buy_with_lamports(&buyer, LAMPORTS_PER_SOL).await, ErrorCode::BuyingToFewTokens);
+}
+
+#[tokio::test]
+async fn does_not_sell_more_than_the_vault_has() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let buyer = sale.trader(100 * LAMPORTS_PER_SOL).await;
+
+    assert_error(sale.buy(&buyer, sale.config.amount_to_sell + 1).await, ErrorCode::InsufficientTokensInVault);
+}
+
+#[tokio::test]
+async fn does_not_sell_above_the_limits() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let cost = 5 * sale.config.token_price;
+
+    assert_error(sale.buy_with_limits(&buyer, 5, cost - 1, None).await, ErrorCode::MaxLamportsExceeded);
+    sale.buy_with_limits(&buyer, 5, cost, Some(0)).await.unwrap();
+
+    sale.switch_to_trading(&operator).await.unwrap();
+    let trading_ends_at = sale.round_ends_at().await;
+    sale.warp_to(trading_ends_at).await;
+    sale.switch_to_buying(&operator).await.unwrap();
+    assert_eq!(sale.pool().await.round_number, 2);
+
+    // The price was raised by the switch
+    assert_error(sale.buy_with_limits(&buyer, 5, cost, None).await, ErrorCode::MaxLamportsExceeded);
+    assert_error(sale.buy_with_limits(&buyer, 5, u64::MAX, Some(0)).await, ErrorCode::UnexpectedRound);
+    sale.buy_with_limits(&buyer, 5, u64::MAX, Some(2)).await.unwrap();
+}
+
+#[tokio::test]
+async fn does_not_sell_without_enough_lamports() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let buyer = sale.trader(1_000_000).await;
+
+    assert_error(sale.buy(&buyer, 1_001).await, ErrorCode::InsufficientLamportsToBuyTokens);
+}
+
+#[tokio::test]
+async fn does_not_sell_while_paused() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+
+    sale.set_paused(&admin, true).await.unwrap();
+    assert_error(sale.buy(&buyer, 1).await, ErrorCode::SalePaused);
+
+    sale.set_paused(&admin, false).await.unwrap();
+    // The bank would return the result of the same transaction in the same slot
+    sale.next_slot().await;
+    sale.buy(&buyer, 1).await.unwrap();
+}
+
+#[tokio::test]
+async fn deposits_and_withdraws_unsold_tokens() {
+    let mut sale = TestSale::start(SaleConfig { admin_tokens: 15_000, ..SaleConfig::default() }).await;
+    let admin_ata = sale.admin_ata;
+    let vault_selling = sale.vault_selling;
+
+    sale.deposit_tokens(5_000).await.unwrap();
+    assert_eq!(sale.tokens(&vault_selling).await, 15_000);
+    assert_eq!(u64::from(sale.pool().await.total_supply), 15_000);
+    assert_error(sale.deposit_tokens(1).await, ErrorCode::NotEnoughTokensForSale);
+
+    assert_error(sale.withdraw_unsold_tokens(1, &admin_ata).await, ErrorCode::BuyingRunning);
+
+    let buying_ends_at = sale.round_ends_at().await;
+    sale.warp_to(buying_ends_at).await;
+    assert_error(sale.withdraw_unsold_tokens(15_001, &admin_ata).await, ErrorCode::InsufficientTokensInVault);
+    sale.withdraw_unsold_tokens(3_000, &admin_ata).await.unwrap();
+
+    assert_eq!(sale.tokens(&admin_ata).await, 3_000);
+    assert_eq!(sale.tokens(&vault_selling).await, 12_000);
+    assert_eq!(u64::from(sale.pool().await.total_supply), 12_000);
+}
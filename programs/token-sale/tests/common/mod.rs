@@ -0,0 +1,1296 @@
+// Every test binary uses its own part of the harness
+#![allow(dead_code)]
+
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::solana_program::{entrypoint::ProgramResult, program::invoke};
+use anchor_lang::solana_program::{instruction::{AccountMeta, Instruction}, system_program, sysvar};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::InstructionError,
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::instruction::create_associated_token_account;
+use spl_associated_token_account::{get_associated_token_address, get_associated_token_address_with_program_id};
+use spl_token_2022::extension::{transfer_fee, BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use token_sale::account::{
+    AllocationAccount, AuctionAccount, BidAccount, BuyerAccount, LotteryAccount, Order, OrderRegistry, PoolAccount,
+    StakeAccount, TicketAccount,
+};
+use token_sale::currency::{Lamports, Tokens};
+use token_sale::error::ErrorCode;
+use token_sale::tier::Tier;
+use token_sale::unsold_tokens_policy::UnsoldTokensPolicy;
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// The UNIX timestamp the clock is set to when the test starts
+pub const START: i64 = 1_650_000_000;
+
+/// The parameters of `initialize`
+#[derive(Clone)]
+pub struct SaleConfig {
+    pub sale_id: u64,
+    pub round_start_at: i64,
+    pub end_at: i64,
+    pub buying_duration: u32,
+    pub trading_duration: u32,
+    pub token_price: u64,
+    pub price_units: u64,
+    pub amount_to_sell: u64,
+    pub coeff_a: f32,
+    pub coeff_b: u32,
+    pub unsold_tokens_policy: UnsoldTokensPolicy,
+    pub unsold_tokens_destination: Pubkey,
+    /// The tokens minted to the admin before the initialization
+    pub admin_tokens: u64,
+    /// The selling mint belongs to Token-2022 and charges this transfer fee, in basis points and up to a maximum,
+    /// it belongs to SPL Token if `None`
+    pub transfer_fee: Option<(u16, u64)>,
+}
+
+impl Default for SaleConfig {
+    fn default() -> Self {
+        SaleConfig {
+            sale_id: 0,
+            round_start_at: START + 10,
+            end_at: START + 1_000,
+            buying_duration: 100,
+            trading_duration: 100,
+            token_price: 1_000,
+            price_units: 1,
+            amount_to_sell: 10_000,
+            coeff_a: 1.5,
+            coeff_b: 100,
+            unsold_tokens_policy: UnsoldTokensPolicy::Burn,
+            unsold_tokens_destination: Pubkey::default(),
+            admin_tokens: 10_000,
+            transfer_fee: None,
+        }
+    }
+}
+
+pub struct Trader {
+    pub signer: Keypair,
+    /// The associated token account of the selling mint
+    pub ata: Pubkey,
+}
+
+/// An initialized (or ready to be initialized) token sale on the in-process bank
+pub struct TestSale {
+    pub ctx: ProgramTestContext,
+    pub config: SaleConfig,
+    pub admin: Keypair,
+    pub selling_mint: Pubkey,
+    /// The program of the selling mint, SPL Token or Token-2022
+    pub token_program: Pubkey,
+    pub admin_ata: Pubkey,
+    pub pool: Pubkey,
+    pub vault_selling: Pubkey,
+    pub order_registry: Pubkey,
+}
+
+/// The BPF build of the program is preferred by `cargo test-bpf`.
+/// The native processor cannot be used alone as it does not support creating accounts via CPI.
+pub fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::new("token_sale", token_sale::id(), processor!(process_instruction));
+    program_test.add_program("relay", relay_id(), processor!(relay));
+    program_test
+}
+
+/// Anchor ties the lifetime of the accounts to the one of their slice, which the processors of program-test do not
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    token_sale::entry(program_id, Box::leak(Box::new(accounts.to_vec())), data)
+}
+
+/// The program that invokes the token sale by CPI in the tests
+pub fn relay_id() -> Pubkey {
+    Pubkey::new_from_array([7; 32])
+}
+
+/// Passes its instruction on to the program given as the first account, along with the rest of the accounts
+fn relay(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let metas = accounts[1..]
+        .iter()
+        .map(|acc| AccountMeta { pubkey: *acc.key, is_signer: acc.is_signer, is_writable: acc.is_writable })
+        .collect();
+    let ix = Instruction { program_id: *accounts[0].key, accounts: metas, data: data.to_vec() };
+    invoke(&ix, accounts)
+}
+
+/// The pool, its `vault_selling` and its order registry
+pub fn sale_addresses(selling_mint: &Pubkey, token_program: &Pubkey, sale_id: u64) -> (Pubkey, Pubkey, Pubkey) {
+    let seeds = [selling_mint.as_ref(), &PoolAccount::sale_seed(sale_id)];
+    let pool = Pubkey::find_program_address(&seeds, &token_sale::id()).0;
+    let vault_selling = get_associated_token_address_with_program_id(&pool, selling_mint, token_program);
+    let order_registry = Pubkey::find_program_address(&[OrderRegistry::PDA_SEED, pool.as_ref()], &token_sale::id()).0;
+    (pool, vault_selling, order_registry)
+}
+
+/// Converts the anchor error code to the one seen by the client
+pub fn program_error(code: ErrorCode) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(code.into()))
+}
+
+pub fn assert_error(result: Result<(), BanksClientError>, code: ErrorCode) {
+    match result {
+        Err(BanksClientError::TransactionError(err)) => assert_eq!(err, program_error(code)),
+        other => panic!("expected {:?}, got {:?}", program_error(code), other),
+    }
+}
+
+impl TestSale {
+    /// Creates the selling mint and mints `config.admin_tokens` to the admin, but does not initialize the pool
+    pub async fn new(config: SaleConfig) -> Self {
+        let ctx = program_test().start_with_context().await;
+        let admin = Keypair::new();
+        let mint = Keypair::new();
+        let token_program = match config.transfer_fee {
+            Some(_) => spl_token_2022::id(),
+            None => spl_token::id(),
+        };
+        let (pool, vault_selling, order_registry) = sale_addresses(&mint.pubkey(), &token_program, config.sale_id);
+        let admin_ata = get_associated_token_address_with_program_id(&admin.pubkey(), &mint.pubkey(), &token_program);
+
+        let mut sale = TestSale {
+            ctx,
+            config,
+            admin,
+            selling_mint: mint.pubkey(),
+            token_program,
+            admin_ata,
+            pool,
+            vault_selling,
+            order_registry,
+        };
+
+        sale.warp_to(START).await;
+        sale.airdrop(&sale.admin.pubkey(), 10 * LAMPORTS_PER_SOL).await;
+        match sale.config.transfer_fee {
+            Some((basis_points, maximum_fee)) => {
+                sale.create_mint_with_transfer_fee(&mint, basis_points, maximum_fee).await
+            }
+            None => sale.create_mint(&mint).await,
+        }
+        sale.create_ata(&sale.admin.pubkey()).await;
+        sale.mint_to(&admin_ata, sale.config.admin_tokens).await;
+        sale
+    }
+
+    /// Points the harness to another sale of the same mint, the pool of which is not initialized yet
+    pub fn next_sale(&mut self, config: SaleConfig) {
+        let (pool, vault_selling, order_registry) =
+            sale_addresses(&self.selling_mint, &self.token_program, config.sale_id);
+        self.config = config;
+        self.pool = pool;
+        self.vault_selling = vault_selling;
+        self.order_registry = order_registry;
+    }
+
+    /// Creates the sale and initializes the pool
+    pub async fn start(config: SaleConfig) -> Self {
+        let mut sale = Self::new(config).await;
+        sale.initialize().await.expect("initializes the pool");
+        sale
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Clock
+    // ---------------------------------------------------------------------------------------------
+
+    /// Moves the bank forward and sets the clock to the given UNIX timestamp
+    pub async fn warp_to(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        // The bank is warped from the slot before the target one, so it has to differ from the current slot
+        clock.slot += 2;
+        self.ctx.warp_to_slot(clock.slot).unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    /// Moves the bank the given slots forward, keeping the clock time
+    pub async fn skip_slots(&mut self, slots: u64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        let unix_timestamp = clock.unix_timestamp;
+        let target = clock.slot + slots;
+        // The slot hashes sysvar only gains the banks a warp goes through, one warp over all the slots
+        // would keep the hashes of the slots before it
+        while clock.slot < target {
+            clock.slot += 2;
+            self.ctx.warp_to_slot(clock.slot).unwrap();
+        }
+        clock.unix_timestamp = unix_timestamp;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    /// Moves the bank to a following slot, keeping the clock time
+    pub async fn next_slot(&mut self) {
+        let now = self.now().await;
+        self.warp_to(now).await;
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    /// The time the current round of the pool ends at
+    pub async fn round_ends_at(&mut self) -> i64 {
+        let pool = self.pool().await;
+        pool.round_start_at + pool.round_duration() as i64
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Accounts
+    // ---------------------------------------------------------------------------------------------
+
+    pub async fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), BanksClientError> {
+        let blockhash = self.ctx.banks_client.get_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.ctx.payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.ctx.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn airdrop(&mut self, to: &Pubkey, lamports: u64) {
+        let ix = system_instruction::transfer(&self.ctx.payer.pubkey(), to, lamports);
+        self.process(&[ix], &[]).await.unwrap();
+    }
+
+    async fn create_mint(&mut self, mint: &Keypair) {
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let ixs = [
+            system_instruction::create_account(
+                &self.ctx.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &self.admin.pubkey(), None, 6)
+                .unwrap(),
+        ];
+        self.process(&ixs, &[mint]).await.unwrap();
+    }
+
+    /// Creates a Token-2022 mint withholding `basis_points` of every transfer, up to `maximum_fee`
+    async fn create_mint_with_transfer_fee(&mut self, mint: &Keypair, basis_points: u16, maximum_fee: u64) {
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::TransferFeeConfig,
+        ])
+        .unwrap();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let admin = self.admin.pubkey();
+        let ixs = [
+            system_instruction::create_account(
+                &self.ctx.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(space),
+                space as u64,
+                &spl_token_2022::id(),
+            ),
+            transfer_fee::instruction::initialize_transfer_fee_config(
+                &spl_token_2022::id(),
+                &mint.pubkey(),
+                Some(&admin),
+                Some(&admin),
+                basis_points,
+                maximum_fee,
+            )
+            .unwrap(),
+            spl_token_2022::instruction::initialize_mint(&spl_token_2022::id(), &mint.pubkey(), &admin, None, 6)
+                .unwrap(),
+        ];
+        self.process(&ixs, &[mint]).await.unwrap();
+    }
+
+    /// The fees the transfer fee of the mint withheld in the token account
+    pub async fn withheld_fees(&mut self, key: &Pubkey) -> u64 {
+        let acc = self.account(key).await.unwrap();
+        let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&acc.data).unwrap();
+        account.get_extension::<transfer_fee::TransferFeeAmount>().map_or(0, |fee| fee.withheld_amount.into())
+    }
+
+    /// The fees harvested to the selling mint from the token accounts closed
+    pub async fn mint_withheld_fees(&mut self) -> u64 {
+        let mint = self.selling_mint;
+        let acc = self.account(&mint).await.unwrap();
+        let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&acc.data).unwrap();
+        mint.get_extension::<transfer_fee::TransferFeeConfig>().unwrap().withheld_amount.into()
+    }
+
+    pub async fn create_ata(&mut self, owner: &Pubkey) -> Pubkey {
+        let mint = self.selling_mint;
+        self.create_ata_of(owner, &mint).await
+    }
+
+    pub async fn create_ata_of(&mut self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        let token_program = self.account(mint).await.expect("mint exists").owner;
+        let ix = create_associated_token_account(&self.ctx.payer.pubkey(), owner, mint, &token_program);
+        self.process(&[ix], &[]).await.unwrap();
+        get_associated_token_address_with_program_id(owner, mint, &token_program)
+    }
+
+    /// The associated token account of the owner for the selling tokens
+    pub fn ata(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.selling_mint, &self.token_program)
+    }
+
+    pub async fn mint_to(&mut self, to: &Pubkey, amount: u64) {
+        let mint = self.selling_mint;
+        self.mint_tokens_of(&mint, to, amount).await
+    }
+
+    /// Creates the mint of the governance tokens staked for the tiers, the admin mints them
+    pub async fn create_stake_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        self.create_mint(&mint).await;
+        mint.pubkey()
+    }
+
+    /// Gives the trader `amount` governance tokens in a new associated token account
+    pub async fn give_stake_tokens(&mut self, stake_mint: &Pubkey, trader: &Trader, amount: u64) -> Pubkey {
+        let ata = self.create_ata_of(&trader.signer.pubkey(), stake_mint).await;
+        self.mint_tokens_of(stake_mint, &ata, amount).await;
+        ata
+    }
+
+    pub async fn mint_tokens_of(&mut self, mint: &Pubkey, to: &Pubkey, amount: u64) {
+        let token_program = self.account(mint).await.expect("mint exists").owner;
+        let ix = spl_token_2022::instruction::mint_to(
+            &token_program,
+            mint,
+            to,
+            &self.admin.pubkey(),
+            &[],
+            amount,
+        ).unwrap();
+        let admin = clone_keypair(&self.admin);
+        self.process(&[ix], &[&admin]).await.unwrap();
+    }
+
+    /// Creates a user with lamports and an empty associated token account of the selling mint
+    pub async fn trader(&mut self, lamports: u64) -> Trader {
+        let signer = Keypair::new();
+        self.airdrop(&signer.pubkey(), lamports).await;
+        let ata = self.create_ata(&signer.pubkey()).await;
+        Trader { signer, ata }
+    }
+
+    pub async fn account(&mut self, key: &Pubkey) -> Option<Account> {
+        self.ctx.banks_client.get_account(*key).await.unwrap()
+    }
+
+    /// Replaces the data of the program account with the one of a legacy layout, keeping it rent-exempt
+    pub async fn downgrade(&mut self, key: &Pubkey, legacy_data: Vec<u8>) {
+        let mut account = self.account(key).await.expect("account exists");
+        account.data = legacy_data;
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        account.lamports = rent.minimum_balance(account.data.len());
+        self.ctx.set_account(key, &account.into());
+    }
+
+    /// Removes the account, as if it was never created
+    pub fn remove_account(&mut self, key: &Pubkey) {
+        self.ctx.set_account(key, &Account::default().into());
+    }
+
+    /// The lamports keeping an account of the given size rent-exempt
+    pub async fn rent(&mut self, space: usize) -> u64 {
+        self.ctx.banks_client.get_rent().await.unwrap().minimum_balance(space)
+    }
+
+    pub async fn lamports(&mut self, key: &Pubkey) -> u64 {
+        self.account(key).await.map(|acc| acc.lamports).unwrap_or(0)
+    }
+
+    /// Token balance of the account, zero if the account does not exist
+    pub async fn tokens(&mut self, key: &Pubkey) -> u64 {
+        match self.account(key).await {
+            Some(acc) => StateWithExtensions::<spl_token_2022::state::Account>::unpack(&acc.data).unwrap().base.amount,
+            None => 0,
+        }
+    }
+
+    pub async fn mint_supply(&mut self) -> u64 {
+        let mint = self.selling_mint;
+        let acc = self.account(&mint).await.unwrap();
+        StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&acc.data).unwrap().base.supply
+    }
+
+    pub async fn mint_authority(&mut self) -> Option<Pubkey> {
+        let mint = self.selling_mint;
+        let acc = self.account(&mint).await.unwrap();
+        StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&acc.data).unwrap().base.mint_authority.into()
+    }
+
+    pub async fn pool(&mut self) -> PoolAccount {
+        let pool = self.pool;
+        self.anchor_account(&pool).await.expect("pool exists")
+    }
+
+    pub async fn order(&mut self, key: &Pubkey) -> Option<Order> {
+        self.anchor_account(key).await
+    }
+
+    pub async fn buyer_account(&mut self, buyer: &Pubkey) -> Option<BuyerAccount> {
+        let address = self.buyer_account_address(buyer);
+        self.anchor_account(&address).await
+    }
+
+    pub async fn stake_account(&mut self, staker: &Pubkey) -> Option<StakeAccount> {
+        let address = self.stake_account_address(staker);
+        self.anchor_account(&address).await
+    }
+
+    pub async fn lottery(&mut self) -> Option<LotteryAccount> {
+        let address = self.lottery_address();
+        self.anchor_account(&address).await
+    }
+
+    pub async fn ticket_account(&mut self, owner: &Pubkey) -> Option<TicketAccount> {
+        let address = self.ticket_address(owner);
+        self.anchor_account(&address).await
+    }
+
+    pub async fn allocation_account(&mut self, owner: &Pubkey) -> Option<AllocationAccount> {
+        let address = self.allocation_address(owner);
+        self.anchor_account(&address).await
+    }
+
+    pub async fn auction(&mut self) -> Option<AuctionAccount> {
+        let address = self.auction_address();
+        self.anchor_account(&address).await
+    }
+
+    pub async fn bid_account(&mut self, bidder: &Pubkey) -> Option<BidAccount> {
+        let address = self.bid_address(bidder);
+        self.anchor_account(&address).await
+    }
+
+    /// The addresses of the active orders in all the pages of the registry, `None` after the termination
+    pub async fn registered_orders(&mut self) -> Option<Vec<Pubkey>> {
+        let mut orders = vec![];
+        for page in 0.. {
+            match self.registry_page(page).await {
+                Some(registry) => orders.extend(registry.orders),
+                None if page == 0 => return None,
+                None => break,
+            }
+        }
+        Some(orders)
+    }
+
+    pub async fn registry_page(&mut self, page: u32) -> Option<OrderRegistry> {
+        let address = self.registry_page_address(page);
+        self.anchor_account(&address).await
+    }
+
+    pub fn registry_page_address(&self, page: u32) -> Pubkey {
+        let seeds = [OrderRegistry::PDA_SEED, self.pool.as_ref(), &OrderRegistry::page_seed(page)];
+        Pubkey::find_program_address(&seeds, &token_sale::id()).0
+    }
+
+    /// The first page of the registry with room for an order, or the next one once they are all full
+    async fn registry_page_with_room(&mut self) -> u32 {
+        let mut page = 0;
+        while let Some(registry) = self.registry_page(page).await {
+            if registry.orders.len() < OrderRegistry::MAX_ORDERS {
+                break;
+            }
+            page += 1;
+        }
+        page
+    }
+
+    async fn order_registry_of(&mut self, order: &Pubkey) -> Pubkey {
+        let page = self.order(order).await.map_or(0, |order| order.registry_page);
+        self.registry_page_address(page)
+    }
+
+    async fn anchor_account<T: AccountDeserialize>(&mut self, key: &Pubkey) -> Option<T> {
+        self.account(key).await.map(|acc| T::try_deserialize(&mut acc.data.as_slice()).unwrap())
+    }
+
+    pub fn order_address(&self, seller: &Pubkey) -> Pubkey {
+        let seeds = [Order::PDA_SEED, self.pool.as_ref(), seller.as_ref()];
+        Pubkey::find_program_address(&seeds, &token_sale::id()).0
+    }
+
+    /// The order of the seller placed before the pool was in the seeds
+    pub fn legacy_order_address(&self, seller: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Order::PDA_SEED, seller.as_ref()], &token_sale::id())
+    }
+
+    /// The order of the seller, at its legacy address if it has none at the current one
+    async fn existing_order_address(&mut self, seller: &Pubkey) -> Pubkey {
+        let order = self.order_address(seller);
+        if self.account(&order).await.is_some() {
+            order
+        } else {
+            self.legacy_order_address(seller).0
+        }
+    }
+
+    pub fn stake_account_address(&self, staker: &Pubkey) -> Pubkey {
+        let seeds = [StakeAccount::PDA_SEED, self.pool.as_ref(), staker.as_ref()];
+        Pubkey::find_program_address(&seeds, &token_sale::id()).0
+    }
+
+    pub fn buyer_account_address(&self, buyer: &Pubkey) -> Pubkey {
+        let seeds = [BuyerAccount::PDA_SEED, self.pool.as_ref(), buyer.as_ref()];
+        Pubkey::find_program_address(&seeds, &token_sale::id()).0
+    }
+
+    pub fn lottery_address(&self) -> Pubkey {
+        Pubkey::find_program_address(&[LotteryAccount::PDA_SEED, self.pool.as_ref()], &token_sale::id()).0
+    }
+
+    pub fn ticket_address(&self, owner: &Pubkey) -> Pubkey {
+        let seeds = [TicketAccount::PDA_SEED, self.pool.as_ref(), owner.as_ref()];
+        Pubkey::find_program_address(&seeds, &token_sale::id()).0
+    }
+
+    pub fn allocation_address(&self, owner: &Pubkey) -> Pubkey {
+        let seeds = [AllocationAccount::PDA_SEED, self.pool.as_ref(), owner.as_ref()];
+        Pubkey::find_program_address(&seeds, &token_sale::id()).0
+    }
+
+    pub fn auction_address(&self) -> Pubkey {
+        Pubkey::find_program_address(&[AuctionAccount::PDA_SEED, self.pool.as_ref()], &token_sale::id()).0
+    }
+
+    pub fn bid_address(&self, bidder: &Pubkey) -> Pubkey {
+        let seeds = [BidAccount::PDA_SEED, self.pool.as_ref(), bidder.as_ref()];
+        Pubkey::find_program_address(&seeds, &token_sale::id()).0
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Instructions
+    // ---------------------------------------------------------------------------------------------
+
+    fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+        Instruction {
+            program_id: token_sale::id(),
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        }
+    }
+
+    pub async fn initialize(&mut self) -> Result<(), BanksClientError> {
+        let config = self.config.clone();
+        let ix = Self::instruction(
+            token_sale::accounts::Initialize {
+                pool_account: self.pool,
+                distribution_authority: self.admin.pubkey(),
+                tokens_for_distribution: self.admin_ata,
+                selling_mint: self.selling_mint,
+                vault_selling: self.vault_selling,
+                order_registry: self.order_registry,
+                unsold_tokens_destination: config.unsold_tokens_destination,
+                clock: sysvar::clock::id(),
+                token_program: self.token_program,
+                rent: sysvar::rent::id(),
+                associated_token_program: spl_associated_token_account::id(),
+                system_program: system_program::id(),
+            },
+            token_sale::instruction::Initialize {
+                sale_id: config.sale_id,
+                round_start_at: config.round_start_at,
+                end_at: config.end_at,
+                buying_duration: config.buying_duration,
+                trading_duration: config.trading_duration,
+                token_price: config.token_price,
+                price_units: config.price_units,
+                amount_to_sell: Tokens::new(config.amount_to_sell),
+                coeff_a: config.coeff_a,
+                coeff_b: config.coeff_b,
+                unsold_tokens_policy: config.unsold_tokens_policy,
+            },
+        );
+        let admin = clone_keypair(&self.admin);
+        self.process(&[ix], &[&admin]).await
+    }
+
+    pub async fn buy(&mut self, buyer: &Trader, amount: u64) -> Result<(), BanksClientError> {
+        self.buy_with_limits(buyer, amount, u64::MAX, None).await
+    }
+
+    pub async fn buy_with_limits(
+        &mut self,
+        buyer: &Trader,
+        amount: u64,
+        max_total_lamports: u64,
+        expected_round: Option<u32>,
+    ) -> Result<(), BanksClientError> {
+        let ix = self.buy_instruction(
+            buyer,
+            token_sale::instruction::Buy {
+                amount_to_buy: Tokens::new(amount),
+                max_total_lamports: Lamports::new(max_total_lamports),
+                expected_round,
+            },
+        );
+        self.process(&[ix], &[&buyer.signer]).await
+    }
+
+    pub async fn buy_with_lamports(&mut self, buyer: &Trader, max_lamports: u64) -> Result<(), BanksClientError> {
+        let ix = self.buy_instruction(
+            buyer,
+            token_sale::instruction::BuyWithLamports { max_lamports: Lamports::new(max_lamports) },
+        );
+        self.process(&[ix], &[&buyer.signer]).await
+    }
+
+    /// Buys through `relay_id()`, as another program invoking `buy` would
+    pub async fn buy_by_cpi(&mut self, buyer: &Trader, amount: u64) -> Result<(), BanksClientError> {
+        let mut ix = self.buy_instruction(
+            buyer,
+            token_sale::instruction::Buy {
+                amount_to_buy: Tokens::new(amount),
+                max_total_lamports: Lamports::new(u64::MAX),
+                expected_round: None,
+            },
+        );
+        ix.accounts.insert(0, AccountMeta::new_readonly(ix.program_id, false));
+        ix.program_id = relay_id();
+        self.process(&[ix], &[&buyer.signer]).await
+    }
+
+    fn buy_instruction(&self, buyer: &Trader, data: impl InstructionData) -> Instruction {
+        Self::instruction(
+            token_sale::accounts::BuyTokens {
+                pool_account: self.pool,
+                selling_mint: self.selling_mint,
+                vault_selling: self.vault_selling,
+                buyer: buyer.signer.pubkey(),
+                buyer_token_account: buyer.ata,
+                buyer_account: self.buyer_account_address(&buyer.signer.pubkey()),
+                stake_account: self.stake_account_address(&buyer.signer.pubkey()),
+                allocation_account: self.allocation_address(&buyer.signer.pubkey()),
+                instructions: sysvar::instructions::id(),
+                token_program: self.token_program,
+                system_program: system_program::id(),
+                clock: sysvar::clock::id(),
+            },
+            data,
+        )
+    }
+
+    pub async fn switch_to_trading(&mut self, operator: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::SwitchToTrading {
+                pool_account: self.pool,
+                operator: operator.pubkey(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::SwitchToTrading {},
+        );
+        self.process(&[ix], &[operator]).await
+    }
+
+    pub async fn switch_to_buying(&mut self, operator: &Keypair) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::SwitchToBuying {
+                pool_account: self.pool,
+                operator: operator.pubkey(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::SwitchToBuying {},
+        );
+        self.process(&[ix], &[operator]).await
+    }
+
+    /// Places the order in the first page of the registry with room and returns its address
+    pub async fn place_order(&mut self, seller: &Trader, amount: u64, price: u64) -> Result<Pubkey, BanksClientError> {
+        let registry_page = self.registry_page_with_room().await;
+        self.place_order_in_page(seller, amount, price, registry_page).await
+    }
+
+    pub async fn place_order_in_page(
+        &mut self,
+        seller: &Trader,
+        amount: u64,
+        price: u64,
+        registry_page: u32,
+    ) -> Result<Pubkey, BanksClientError> {
+        let order = self.order_address(&seller.signer.pubkey());
+        let ix = Self::instruction(
+            token_sale::accounts::PlaceOrder {
+                pool_account: self.pool,
+                selling_mint: self.selling_mint,
+                seller: seller.signer.pubkey(),
+                seller_token_account: seller.ata,
+                order,
+                order_token_vault: self.ata(&order),
+                order_registry: self.registry_page_address(registry_page),
+                token_program: self.token_program,
+                rent: sysvar::rent::id(),
+                associated_token_program: spl_associated_token_account::id(),
+                system_program: system_program::id(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::PlaceOrder {
+                amount_to_sell: Tokens::new(amount),
+                price_for_token: price,
+                registry_page,
+            },
+        );
+        self.process(&[ix], &[&seller.signer]).await.map(|_| order)
+    }
+
+    pub async fn redeem_order(&mut self, order: &Pubkey, buyer: &Trader, amount: u64) -> Result<(), BanksClientError> {
+        self.redeem_order_with_limits(order, buyer, amount, u64::MAX, None).await
+    }
+
+    pub async fn redeem_order_with_limits(
+        &mut self,
+        order: &Pubkey,
+        buyer: &Trader,
+        amount: u64,
+        max_price_per_token: u64,
+        expected_round: Option<u32>,
+    ) -> Result<(), BanksClientError> {
+        let order_owner = self.order(order).await.expect("order exists").owner;
+        let order_registry = self.order_registry_of(order).await;
+        let ix = Self::instruction(
+            token_sale::accounts::RedeemOrder {
+                pool_account: self.pool,
+                selling_mint: self.selling_mint,
+                buyer: buyer.signer.pubkey(),
+                buyer_token_account: buyer.ata,
+                order: *order,
+                order_owner,
+                order_token_vault: self.ata(order),
+                order_registry,
+                token_program: self.token_program,
+                clock: sysvar::clock::id(),
+                system_program: system_program::id(),
+            },
+            token_sale::instruction::RedeemOrder {
+                tokens_amount: Tokens::new(amount),
+                max_price_per_token,
+                expected_round,
+            },
+        );
+        self.process(&[ix], &[&buyer.signer]).await
+    }
+
+    pub async fn close_order(&mut self, seller: &Trader) -> Result<(), BanksClientError> {
+        let order = self.existing_order_address(&seller.signer.pubkey()).await;
+        let order_registry = self.order_registry_of(&order).await;
+        let ix = Self::instruction(
+            token_sale::accounts::CloseOrder {
+                pool_account: self.pool,
+                selling_mint: self.selling_mint,
+                order,
+                order_token_vault: self.ata(&order),
+                order_owner: seller.signer.pubkey(),
+                owner_token_vault: seller.ata,
+                order_registry,
+                token_program: self.token_program,
+                system_program: system_program::id(),
+            },
+            token_sale::instruction::CloseOrder {},
+        );
+        self.process(&[ix], &[&seller.signer]).await
+    }
+
+    pub async fn reclaim_order(&mut self, seller: &Pubkey, reclaimer: &Keypair) -> Result<(), BanksClientError> {
+        let order = self.existing_order_address(seller).await;
+        let order_registry = self.order_registry_of(&order).await;
+        let ix = Self::instruction(
+            token_sale::accounts::ReclaimOrder {
+                pool_account: self.pool,
+                selling_mint: self.selling_mint,
+                order,
+                order_token_vault: self.ata(&order),
+                order_owner: *seller,
+                owner_token_vault: self.ata(seller),
+                reclaimer: reclaimer.pubkey(),
+                order_registry,
+                token_program: self.token_program,
+                associated_token_program: spl_associated_token_account::id(),
+                rent: sysvar::rent::id(),
+                system_program: system_program::id(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::ReclaimOrder {},
+        );
+        self.process(&[ix], &[reclaimer]).await
+    }
+
+    pub async fn deposit_tokens(&mut self, amount: u64) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::DepositTokens {
+                pool_account: self.pool,
+                selling_mint: self.selling_mint,
+                vault_selling: self.vault_selling,
+                admin: self.admin.pubkey(),
+                admin_token_account: self.admin_ata,
+                token_program: self.token_program,
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::DepositTokens { amount: Tokens::new(amount) },
+        );
+        let admin = clone_keypair(&self.admin);
+        self.process(&[ix], &[&admin]).await
+    }
+
+    /// The admin is the mint authority of the selling mint and hands it over to the pool
+    pub async fn enable_minting(
+        &mut self,
+        max_supply: Option<u64>,
+        revoke_mint_authority: bool,
+    ) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::EnableMinting {
+                pool_account: self.pool,
+                selling_mint: self.selling_mint,
+                admin: self.admin.pubkey(),
+                mint_authority: self.admin.pubkey(),
+                token_program: self.token_program,
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::EnableMinting {
+                max_supply: max_supply.map(Tokens::new),
+                revoke_mint_authority,
+            },
+        );
+        let admin = clone_keypair(&self.admin);
+        self.process(&[ix], &[&admin]).await
+    }
+
+    pub async fn withdraw_unsold_tokens(&mut self, amount: u64, destination: &Pubkey) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::WithdrawUnsoldTokens {
+                pool_account: self.pool,
+                selling_mint: self.selling_mint,
+                vault_selling: self.vault_selling,
+                admin: self.admin.pubkey(),
+                destination_token_account: *destination,
+                token_program: self.token_program,
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::WithdrawUnsoldTokens { amount: Tokens::new(amount) },
+        );
+        let admin = clone_keypair(&self.admin);
+        self.process(&[ix], &[&admin]).await
+    }
+
+    pub async fn update_config(&mut self, admin: &Keypair, config: &SaleConfig) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::UpdateConfig {
+                pool_account: self.pool,
+                admin: admin.pubkey(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::UpdateConfig {
+                end_at: config.end_at,
+                buying_duration: config.buying_duration,
+                trading_duration: config.trading_duration,
+                coeff_a: config.coeff_a,
+                coeff_b: config.coeff_b,
+            },
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    pub async fn update_roles(
+        &mut self,
+        admin: &Keypair,
+        new_admin: Pubkey,
+        treasury: Pubkey,
+        operator: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::UpdateRoles { pool_account: self.pool, admin: admin.pubkey() },
+            token_sale::instruction::UpdateRoles { admin: new_admin, treasury, operator },
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    pub async fn set_buy_protection(
+        &mut self,
+        admin: &Keypair,
+        max_tokens_per_slot: u64,
+        buy_cooldown: u32,
+        reject_cpi: bool,
+        trusted_caller: Option<Pubkey>,
+    ) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::SetBuyProtection { pool_account: self.pool, admin: admin.pubkey() },
+            token_sale::instruction::SetBuyProtection {
+                max_tokens_per_slot: Tokens::new(max_tokens_per_slot),
+                buy_cooldown,
+                reject_cpi,
+                trusted_caller,
+            },
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    /// Sets the tiers given as `(min_stake, allocation)`
+    pub async fn set_tiers(
+        &mut self,
+        admin: &Keypair,
+        stake_mint: &Pubkey,
+        tiers: &[(u64, u64)],
+    ) -> Result<(), BanksClientError> {
+        let tiers = tiers
+            .iter()
+            .map(|&(min_stake, allocation)| Tier {
+                min_stake: Tokens::new(min_stake),
+                allocation: Tokens::new(allocation),
+            })
+            .collect();
+        let ix = Self::instruction(
+            token_sale::accounts::SetTiers {
+                pool_account: self.pool,
+                stake_mint: *stake_mint,
+                admin: admin.pubkey(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::SetTiers { tiers },
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    pub async fn stake(&mut self, staker: &Trader, stake_mint: &Pubkey, amount: u64) -> Result<(), BanksClientError> {
+        let stake_account = self.stake_account_address(&staker.signer.pubkey());
+        let ix = Self::instruction(
+            token_sale::accounts::Stake {
+                pool_account: self.pool,
+                stake_mint: *stake_mint,
+                stake_account,
+                stake_vault: get_associated_token_address(&stake_account, stake_mint),
+                staker: staker.signer.pubkey(),
+                staker_token_account: get_associated_token_address(&staker.signer.pubkey(), stake_mint),
+                token_program: spl_token::id(),
+                rent: sysvar::rent::id(),
+                associated_token_program: spl_associated_token_account::id(),
+                system_program: system_program::id(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::Stake { amount: Tokens::new(amount) },
+        );
+        self.process(&[ix], &[&staker.signer]).await
+    }
+
+    pub async fn unstake(&mut self, staker: &Trader, stake_mint: &Pubkey) -> Result<(), BanksClientError> {
+        let stake_account = self.stake_account_address(&staker.signer.pubkey());
+        let ix = Self::instruction(
+            token_sale::accounts::Unstake {
+                stake_account,
+                stake_mint: *stake_mint,
+                stake_vault: get_associated_token_address(&stake_account, stake_mint),
+                staker: staker.signer.pubkey(),
+                staker_token_account: get_associated_token_address(&staker.signer.pubkey(), stake_mint),
+                token_program: spl_token::id(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::Unstake {},
+        );
+        self.process(&[ix], &[&staker.signer]).await
+    }
+
+    pub async fn close_buyer_account(&mut self, buyer: &Trader) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::CloseBuyerAccount {
+                buyer_account: self.buyer_account_address(&buyer.signer.pubkey()),
+                pool_account: self.pool,
+                buyer: buyer.signer.pubkey(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::CloseBuyerAccount {},
+        );
+        self.process(&[ix], &[&buyer.signer]).await
+    }
+
+    /// Starts the lottery committed to the hash of the secret
+    pub async fn start_lottery(
+        &mut self,
+        admin: &Keypair,
+        registration_ends_at: i64,
+        winners: u32,
+        allocation: u64,
+        secret: &[u8; 32],
+    ) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::StartLottery {
+                pool_account: self.pool,
+                lottery_account: self.lottery_address(),
+                admin: admin.pubkey(),
+                system_program: system_program::id(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::StartLottery {
+                registration_ends_at,
+                winners,
+                allocation: Tokens::new(allocation),
+                secret_hash: token_sale::lottery::secret_hash(secret),
+            },
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    pub async fn register_ticket(&mut self, owner: &Trader) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::RegisterTicket {
+                pool_account: self.pool,
+                lottery_account: self.lottery_address(),
+                ticket_account: self.ticket_address(&owner.signer.pubkey()),
+                owner: owner.signer.pubkey(),
+                system_program: system_program::id(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::RegisterTicket {},
+        );
+        self.process(&[ix], &[&owner.signer]).await
+    }
+
+    /// Closes the registration, signed by the payer only as anyone could close it
+    pub async fn close_registration(&mut self) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::CloseRegistration {
+                lottery_account: self.lottery_address(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::CloseRegistration {},
+        );
+        self.process(&[ix], &[]).await
+    }
+
+    pub async fn abandon_lottery(&mut self) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::AbandonLottery {
+                pool_account: self.pool,
+                lottery_account: self.lottery_address(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::AbandonLottery {},
+        );
+        self.process(&[ix], &[]).await
+    }
+
+    pub async fn draw_lottery(&mut self, admin: &Keypair, secret: [u8; 32]) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::DrawLottery {
+                pool_account: self.pool,
+                lottery_account: self.lottery_address(),
+                admin: admin.pubkey(),
+                slot_hashes: sysvar::slot_hashes::id(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::DrawLottery { secret },
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    pub async fn claim_allocation(&mut self, owner: &Trader) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::ClaimAllocation {
+                pool_account: self.pool,
+                lottery_account: self.lottery_address(),
+                ticket_account: self.ticket_address(&owner.signer.pubkey()),
+                allocation_account: self.allocation_address(&owner.signer.pubkey()),
+                owner: owner.signer.pubkey(),
+                system_program: system_program::id(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::ClaimAllocation {},
+        );
+        self.process(&[ix], &[&owner.signer]).await
+    }
+
+    pub async fn start_auction(
+        &mut self,
+        operator: &Keypair,
+        commit_duration: u32,
+        reveal_duration: u32,
+        tokens: u64,
+    ) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::StartAuction {
+                pool_account: self.pool,
+                vault_selling: self.vault_selling,
+                auction_account: self.auction_address(),
+                operator: operator.pubkey(),
+                system_program: system_program::id(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::StartAuction { commit_duration, reveal_duration, tokens: Tokens::new(tokens) },
+        );
+        self.process(&[ix], &[operator]).await
+    }
+
+    /// Commits the bid of `tokens` at `price` sealed with the salt
+    pub async fn commit_bid(
+        &mut self,
+        bidder: &Trader,
+        tokens: u64,
+        price: u64,
+        salt: &[u8; 32],
+        deposit: u64,
+    ) -> Result<(), BanksClientError> {
+        let commitment = token_sale::auction::bid_commitment(&bidder.signer.pubkey(), Tokens::new(tokens), price, salt);
+        let ix = Self::instruction(
+            token_sale::accounts::CommitBid {
+                pool_account: self.pool,
+                bid_account: self.bid_address(&bidder.signer.pubkey()),
+                bidder: bidder.signer.pubkey(),
+                system_program: system_program::id(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::CommitBid { commitment, deposit: Lamports::new(deposit) },
+        );
+        self.process(&[ix], &[&bidder.signer]).await
+    }
+
+    pub async fn switch_to_reveal(&mut self) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::SwitchToReveal {
+                pool_account: self.pool,
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::SwitchToReveal {},
+        );
+        self.process(&[ix], &[]).await
+    }
+
+    pub async fn reveal_bid(
+        &mut self,
+        bidder: &Trader,
+        tokens: u64,
+        price: u64,
+        salt: [u8; 32],
+    ) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::RevealBid {
+                pool_account: self.pool,
+                auction_account: self.auction_address(),
+                bid_account: self.bid_address(&bidder.signer.pubkey()),
+                bidder: bidder.signer.pubkey(),
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::RevealBid { tokens: Tokens::new(tokens), price, salt },
+        );
+        self.process(&[ix], &[&bidder.signer]).await
+    }
+
+    /// Settles the bid, signed by the payer only as anyone could settle it
+    pub async fn settle_bid(&mut self, bidder: &Trader) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::SettleBid {
+                pool_account: self.pool,
+                selling_mint: self.selling_mint,
+                vault_selling: self.vault_selling,
+                auction_account: self.auction_address(),
+                bid_account: self.bid_address(&bidder.signer.pubkey()),
+                bidder: bidder.signer.pubkey(),
+                bidder_token_account: bidder.ata,
+                token_program: self.token_program,
+                clock: sysvar::clock::id(),
+            },
+            token_sale::instruction::SettleBid {},
+        );
+        self.process(&[ix], &[]).await
+    }
+
+    pub async fn set_paused(&mut self, admin: &Keypair, paused: bool) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::SetPaused { pool_account: self.pool, admin: admin.pubkey() },
+            token_sale::instruction::SetPaused { paused },
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    pub async fn set_unsold_tokens_policy(
+        &mut self,
+        admin: &Keypair,
+        unsold_tokens_policy: UnsoldTokensPolicy,
+        unsold_tokens_destination: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::SetUnsoldTokensPolicy {
+                pool_account: self.pool,
+                admin: admin.pubkey(),
+                unsold_tokens_destination,
+            },
+            token_sale::instruction::SetUnsoldTokensPolicy { unsold_tokens_policy },
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    pub async fn withdraw_lamports(&mut self, admin: &Keypair, treasury: &Pubkey) -> Result<(), BanksClientError> {
+        let ix = Self::instruction(
+            token_sale::accounts::WithdrawLamports {
+                pool_account: self.pool,
+                selling_mint: self.selling_mint,
+                admin: admin.pubkey(),
+                treasury: *treasury,
+                clock: sysvar::clock::id(),
+                token_program: self.token_program,
+                system_program: system_program::id(),
+                rent: sysvar::rent::id(),
+            },
+            token_sale::instruction::WithdrawLamports {},
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    pub async fn migrate_pool(&mut self, admin: &Keypair, orders: &[Pubkey]) -> Result<(), BanksClientError> {
+        let mut ix = Self::instruction(
+            token_sale::accounts::MigratePool {
+                pool_account: self.pool,
+                selling_mint: self.selling_mint,
+                vault_selling: self.vault_selling,
+                order_registry: self.order_registry,
+                admin: admin.pubkey(),
+                token_program: self.token_program,
+                system_program: system_program::id(),
+            },
+            token_sale::instruction::MigratePool { _sale_id: self.config.sale_id, registry_page: 0 },
+        );
+        ix.accounts.extend(orders.iter().map(|order| AccountMeta::new(*order, false)));
+        self.process(&[ix], &[admin]).await
+    }
+
+    /// Terminates the sale, the vault itself is passed as the receiver if the unsold tokens are burned.
+    /// The pages of the registry after the first one are passed to be closed.
+    pub async fn terminate(&mut self, unsold_tokens_receiver: Option<Pubkey>) -> Result<(), BanksClientError> {
+        let last_registry_page = self.pool().await.last_registry_page;
+        let mut ix = Self::instruction(
+            token_sale::accounts::Terminate {
+                pool_account: self.pool,
+                selling_mint: self.selling_mint,
+                vault_selling: self.vault_selling,
+                admin: self.admin.pubkey(),
+                unsold_tokens_receiver: unsold_tokens_receiver.unwrap_or(self.vault_selling),
+                order_registry: self.order_registry,
+                clock: sysvar::clock::id(),
+                token_program: self.token_program,
+                system_program: system_program::id(),
+            },
+            token_sale::instruction::Terminate {},
+        );
+        let pages = (1..=last_registry_page).map(|page| AccountMeta::new(self.registry_page_address(page), false));
+        ix.accounts.extend(pages);
+        let admin = clone_keypair(&self.admin);
+        self.process(&[ix], &[&admin]).await
+    }
+}
+
+pub fn clone_keypair(keypair: &Keypair) -> Keypair {
+    Keypair::from_bytes(&keypair.to_bytes()).unwrap()
+}
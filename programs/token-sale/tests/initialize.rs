@@ -0,0 +1,92 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use token_sale::error::ErrorCode;
+use token_sale::round::Round;
+
+#[tokio::test]
+async fn initializes_the_pool() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let pool = sale.pool().await;
+
+    assert_eq!(pool.admin, sale.admin.pubkey());
+    assert_eq!(pool.treasury, sale.admin.pubkey());
+    assert_eq!(pool.operator, sale.admin.pubkey());
+    assert_eq!(pool.selling_mint, sale.selling_mint);
+    assert_eq!(pool.vault_selling, sale.vault_selling);
+    assert!(pool.current_round == Round::Buying);
+    assert_eq!(pool.round_start_at, sale.config.round_start_at);
+    assert_eq!(pool.end_at, sale.config.end_at);
+    assert_eq!(pool.token_price, sale.config.token_price);
+    assert_eq!(u64::from(pool.total_supply), sale.config.amount_to_sell);
+    assert_eq!(pool.open_orders, 0);
+    assert!(!pool.paused);
+
+    let vault_selling = sale.vault_selling;
+    assert_eq!(sale.tokens(&vault_selling).await, sale.config.amount_to_sell);
+    assert_eq!(sale.registered_orders().await, Some(vec![]));
+}
+
+#[tokio::test]
+async fn rejects_zero_token_price() {
+    let mut sale = TestSale::new(SaleConfig { token_price: 0, ..SaleConfig::default() }).await;
+    assert_error(sale.initialize().await, ErrorCode::TokenPriceZero);
+
+    let mut sale = TestSale::new(SaleConfig { price_units: 0, ..SaleConfig::default() }).await;
+    assert_error(sale.initialize().await, ErrorCode::PriceUnitsZero);
+}
+
+#[tokio::test]
+async fn rejects_selling_more_than_distributed() {
+    let mut sale = TestSale::new(SaleConfig { amount_to_sell: 10_001, ..SaleConfig::default() }).await;
+    assert_error(sale.initialize().await, ErrorCode::NotEnoughTokensForSale);
+}
+
+#[tokio::test]
+async fn rejects_first_round_in_the_past() {
+    let mut sale = TestSale::new(SaleConfig { round_start_at: START - 1, ..SaleConfig::default() }).await;
+    assert_error(sale.initialize().await, ErrorCode::FirstRoundAlreadyStarted);
+}
+
+#[tokio::test]
+async fn rejects_ido_shorter_than_full_circle() {
+    let mut sale = TestSale::new(SaleConfig { end_at: START + 199, ..SaleConfig::default() }).await;
+    assert_error(sale.initialize().await, ErrorCode::EndsBeforeFullCircle);
+}
+
+#[tokio::test]
+async fn rejects_price_coefficients_giving_zero_price() {
+    let mut sale = TestSale::new(SaleConfig { coeff_a: 0.0, coeff_b: 0, ..SaleConfig::default() }).await;
+    assert_error(sale.initialize().await, ErrorCode::InvalidPriceCoefficients);
+
+    let mut sale = TestSale::new(SaleConfig { coeff_a: -1.0, ..SaleConfig::default() }).await;
+    assert_error(sale.initialize().await, ErrorCode::InvalidPriceCoefficients);
+}
+
+#[tokio::test]
+async fn runs_several_sales_of_the_same_mint() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let first_pool = sale.pool;
+    let admin_ata = sale.admin_ata;
+    sale.mint_to(&admin_ata, 5_000).await;
+
+    sale.next_sale(SaleConfig { sale_id: 1, amount_to_sell: 5_000, token_price: 2_000, ..SaleConfig::default() });
+    sale.initialize().await.unwrap();
+    assert!(sale.initialize().await.is_err());
+
+    assert_ne!(sale.pool, first_pool);
+    let pool = sale.pool().await;
+    assert_eq!(pool.sale_id, 1);
+    assert_eq!(pool.token_price, 2_000);
+    let vault_selling = sale.vault_selling;
+    assert_eq!(sale.tokens(&vault_selling).await, 5_000);
+
+    sale.warp_to(sale.config.round_start_at).await;
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.buy(&buyer, 10).await.unwrap();
+    assert_eq!(sale.tokens(&buyer.ata).await, 10);
+    assert!(sale.account(&first_pool).await.is_some());
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use token_sale::account::LotteryAccount;
+use token_sale::error::ErrorCode;
+use token_sale::lottery;
+
+const SECRET: [u8; 32] = [42; 32];
+
+#[tokio::test]
+async fn only_the_winners_buy() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let mut traders = vec![];
+    for _ in 0..4 {
+        traders.push(sale.trader(LAMPORTS_PER_SOL).await);
+    }
+    sale.start_lottery(&admin, START + 50, 2, 10, &SECRET).await.unwrap();
+    for trader in &traders {
+        sale.register_ticket(trader).await.unwrap();
+    }
+    assert_eq!(sale.ticket_account(&traders[3].signer.pubkey()).await.unwrap().number, 3);
+    assert_error(sale.buy(&traders[0], 1).await, ErrorCode::AllocationExceeded);
+    assert_error(sale.draw_lottery(&admin, SECRET).await, ErrorCode::RegistrationOpen);
+
+    sale.warp_to(START + 50).await;
+    let late = sale.trader(LAMPORTS_PER_SOL).await;
+    assert_error(sale.register_ticket(&late).await, ErrorCode::RegistrationOver);
+    sale.close_registration().await.unwrap();
+
+    // The hash of the slot the registration was closed in is only known in the following slots
+    assert_error(sale.draw_lottery(&admin, SECRET).await, ErrorCode::SlotHashUnavailable);
+    sale.next_slot().await;
+    assert_error(sale.draw_lottery(&admin, [7; 32]).await, ErrorCode::WrongSecret);
+    sale.draw_lottery(&admin, SECRET).await.unwrap();
+
+    // Anyone verifies the draw with the revealed secret
+    let drawn = sale.lottery().await.unwrap();
+    assert_eq!(drawn.seed, lottery::draw_seed(&SECRET, &drawn.slot_hash));
+    let winners: Vec<usize> = (0..4).filter(|number| drawn.is_winner(*number as u32)).collect();
+    assert_eq!(winners.len(), 2);
+
+    for (number, trader) in traders.iter().enumerate() {
+        if winners.contains(&number) {
+            sale.claim_allocation(trader).await.unwrap();
+            assert!(sale.ticket_account(&trader.signer.pubkey()).await.is_none());
+            let allocation = sale.allocation_account(&trader.signer.pubkey()).await.unwrap();
+            assert_eq!(u64::from(allocation.amount), 10);
+
+            sale.buy(trader, 10).await.unwrap();
+            assert_error(sale.buy(trader, 1).await, ErrorCode::AllocationExceeded);
+        } else {
+            assert_error(sale.claim_allocation(trader).await, ErrorCode::TicketLost);
+            assert_error(sale.buy(trader, 2).await, ErrorCode::AllocationExceeded);
+        }
+    }
+    assert_eq!(u64::from(sale.pool().await.tokens_sold), 20);
+}
+
+#[tokio::test]
+async fn validates_the_lottery() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let stranger = sale.trader(LAMPORTS_PER_SOL).await;
+    let end_at = sale.config.end_at;
+
+    let invalid = [(START, 1, 10), (end_at, 1, 10), (START + 50, 0, 10), (START + 50, 1, 0)];
+    for (registration_ends_at, winners, allocation) in invalid {
+        assert_error(
+            sale.start_lottery(&admin, registration_ends_at, winners, allocation, &SECRET).await,
+            ErrorCode::InvalidLottery,
+        );
+    }
+    assert_error(sale.start_lottery(&stranger.signer, START + 50, 1, 10, &SECRET).await, ErrorCode::OnlyAdmin);
+
+    sale.start_lottery(&admin, START + 50, 1, 10, &SECRET).await.unwrap();
+    assert_eq!(sale.pool().await.lottery, sale.lottery_address());
+    sale.register_ticket(&stranger).await.unwrap();
+    assert_error(sale.close_registration().await, ErrorCode::RegistrationOpen);
+
+    sale.warp_to(START + 50).await;
+    assert_error(sale.claim_allocation(&stranger).await, ErrorCode::LotteryNotDrawn);
+    sale.close_registration().await.unwrap();
+    sale.next_slot().await;
+    assert_error(sale.close_registration().await, ErrorCode::RegistrationOver);
+    assert_error(sale.draw_lottery(&stranger.signer, SECRET).await, ErrorCode::OnlyAdmin);
+
+    sale.draw_lottery(&admin, SECRET).await.unwrap();
+    sale.next_slot().await;
+    assert_error(sale.draw_lottery(&admin, SECRET).await, ErrorCode::LotteryAlreadyDrawn);
+
+    // The only ticket always wins
+    sale.claim_allocation(&stranger).await.unwrap();
+    sale.buy(&stranger, 10).await.unwrap();
+}
+
+#[tokio::test]
+async fn abandons_the_lottery_once_the_hash_of_the_draw_slot_expired() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let trader = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.start_lottery(&admin, START + 50, 1, 10, &SECRET).await.unwrap();
+    sale.register_ticket(&trader).await.unwrap();
+    sale.warp_to(START + 50).await;
+    sale.close_registration().await.unwrap();
+
+    sale.next_slot().await;
+    assert_error(sale.close_registration().await, ErrorCode::RegistrationOver);
+    assert_error(sale.abandon_lottery().await, ErrorCode::DrawNotTimedOut);
+
+    // The admin could not wait for another draw slot
+    sale.skip_slots(lottery::SLOT_HASHES_KEPT + 1).await;
+    assert_error(sale.draw_lottery(&admin, SECRET).await, ErrorCode::SlotHashUnavailable);
+    assert_error(sale.close_registration().await, ErrorCode::RegistrationOver);
+    sale.abandon_lottery().await.unwrap();
+    assert!(sale.lottery().await.unwrap().abandoned);
+    sale.buy(&trader, 20).await.unwrap();
+}
+
+#[tokio::test]
+async fn anyone_abandons_a_lottery_left_undrawn() {
+    let mut sale = TestSale::start(SaleConfig {
+        end_at: START + 100_000,
+        buying_duration: 10_000,
+        ..SaleConfig::default()
+    })
+    .await;
+    let admin = clone_keypair(&sale.admin);
+    let trader = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.start_lottery(&admin, START + 50, 1, 10, &SECRET).await.unwrap();
+    sale.register_ticket(&trader).await.unwrap();
+    // Nobody closes the registration
+    sale.warp_to(START + 50).await;
+    assert_error(sale.buy(&trader, 1).await, ErrorCode::AllocationExceeded);
+
+    sale.warp_to(START + 50 + LotteryAccount::DRAW_TIMEOUT - 1).await;
+    assert_error(sale.abandon_lottery().await, ErrorCode::DrawNotTimedOut);
+
+    sale.warp_to(START + 50 + LotteryAccount::DRAW_TIMEOUT).await;
+    sale.abandon_lottery().await.unwrap();
+    assert!(sale.lottery().await.unwrap().abandoned);
+    assert_eq!(sale.pool().await.lottery, Default::default());
+    assert_error(sale.draw_lottery(&admin, SECRET).await, ErrorCode::LotteryAbandoned);
+    assert_error(sale.abandon_lottery().await, ErrorCode::LotteryAbandoned);
+
+    // Everyone buys, with or without a ticket
+    let stranger = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.buy(&trader, 20).await.unwrap();
+    sale.buy(&stranger, 20).await.unwrap();
+}
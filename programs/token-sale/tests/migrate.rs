@@ -0,0 +1,182 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use anchor_lang::{AnchorSerialize, Discriminator};
+use common::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use token_sale::account::{Order, PoolAccount};
+use token_sale::error::ErrorCode;
+use token_sale::round::Round;
+use token_sale::unsold_tokens_policy::UnsoldTokensPolicy;
+
+/// The pool as the first version of the program wrote it
+#[derive(AnchorSerialize)]
+struct LegacyPool {
+    bump: u8,
+    owner: Pubkey,
+    selling_mint: Pubkey,
+    payment_mint: Pubkey,
+    vault_selling: Pubkey,
+    end_at: i64,
+    buying_duration: u32,
+    trading_duration: u32,
+    token_price: u64,
+    current_round: Round,
+    round_start_at: i64,
+    coeff_a: f32,
+    coeff_b: u32,
+}
+
+/// The order as the first version of the program wrote it, the fields that follow `bump` were added later
+const LEGACY_ORDER_SPACE: usize = 1 + 8 + 32 + 8 + 8 + 32 + 1;
+/// The offset of `Order::token_vault` in the account data
+const ORDER_VAULT_OFFSET: usize = 8 + 1 + 8 + 32 + 8 + 8;
+
+fn legacy_pool_data(pool: &PoolAccount) -> Vec<u8> {
+    let legacy = LegacyPool {
+        bump: pool.bump,
+        owner: pool.admin,
+        selling_mint: pool.selling_mint,
+        payment_mint: pool.payment_mint,
+        vault_selling: pool.vault_selling,
+        end_at: pool.end_at,
+        buying_duration: pool.buying_duration,
+        trading_duration: pool.trading_duration,
+        token_price: pool.token_price,
+        current_round: pool.current_round.clone(),
+        round_start_at: pool.round_start_at,
+        coeff_a: pool.coeff_a,
+        coeff_b: pool.coeff_b,
+    };
+    let mut data = PoolAccount::discriminator().to_vec();
+    data.extend(legacy.try_to_vec().unwrap());
+    assert_eq!(data.len(), 8 + 1 + 32 * 4 + 8 + 4 + 4 + 8 + 1 + 8 + 4 + 4);
+    data
+}
+
+/// Moves the order and its vault to the address of the first version, seeded by the seller only,
+/// and rewrites the order in the first layout
+async fn downgrade_order(sale: &mut TestSale, seller: &Pubkey) -> Pubkey {
+    let order = sale.order_address(seller);
+    let (legacy_order, bump) = sale.legacy_order_address(seller);
+    let vault = get_associated_token_address(&order, &sale.selling_mint);
+    let legacy_vault = get_associated_token_address(&legacy_order, &sale.selling_mint);
+
+    let mut vault_account = sale.account(&vault).await.unwrap();
+    // The owner of the token account
+    vault_account.data[32..64].copy_from_slice(legacy_order.as_ref());
+    sale.ctx.set_account(&legacy_vault, &vault_account.into());
+    sale.remove_account(&vault);
+
+    let order_account = sale.account(&order).await.unwrap();
+    let mut order_data = order_account.data.clone();
+    order_data.truncate(8 + LEGACY_ORDER_SPACE);
+    order_data[ORDER_VAULT_OFFSET..ORDER_VAULT_OFFSET + 32].copy_from_slice(legacy_vault.as_ref());
+    order_data[8 + LEGACY_ORDER_SPACE - 1] = bump;
+    sale.ctx.set_account(&legacy_order, &order_account.into());
+    sale.remove_account(&order);
+    sale.downgrade(&legacy_order, order_data).await;
+    legacy_order
+}
+
+/// Starts the sale with an order of each seller placed and rewrites the pool and the orders in the first layout,
+/// without an order registry
+async fn legacy_sale(sellers: usize) -> (TestSale, Vec<Trader>, Vec<Pubkey>) {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+    let mut traders = vec![];
+    for _ in 0..sellers {
+        let seller = sale.trader(LAMPORTS_PER_SOL).await;
+        sale.buy(&seller, 10).await.unwrap();
+        traders.push(seller);
+    }
+    sale.switch_to_trading(&operator).await.unwrap();
+
+    let mut orders = vec![];
+    for seller in &traders {
+        sale.place_order(seller, 4, 2_000).await.unwrap();
+        orders.push(downgrade_order(&mut sale, &seller.signer.pubkey()).await);
+    }
+
+    let pool = sale.pool;
+    let pool_data = legacy_pool_data(&sale.pool().await);
+    sale.downgrade(&pool, pool_data).await;
+    let order_registry = sale.order_registry;
+    sale.remove_account(&order_registry);
+    (sale, traders, orders)
+}
+
+#[tokio::test]
+async fn migrates_the_pool_and_its_orders() {
+    let (mut sale, sellers, orders) = legacy_sale(2).await;
+    let admin = clone_keypair(&sale.admin);
+    let pool = sale.pool;
+    let vault_tokens = sale.tokens(&sale.vault_selling.clone()).await;
+
+    sale.migrate_pool(&admin, &[]).await.unwrap();
+
+    let pool_account = sale.account(&pool).await.unwrap();
+    assert_eq!(pool_account.data.len(), 8 + PoolAccount::SPACE);
+    let migrated = sale.pool().await;
+    assert_eq!(migrated.version, PoolAccount::VERSION);
+    assert_eq!(migrated.admin, admin.pubkey());
+    assert_eq!(migrated.treasury, admin.pubkey());
+    assert_eq!(migrated.operator, admin.pubkey());
+    assert_eq!(migrated.current_round, Round::Trading);
+    assert_eq!(u64::from(migrated.total_supply), vault_tokens);
+    assert_eq!(migrated.price_units, 1);
+    assert_eq!(migrated.unsold_tokens_policy, UnsoldTokensPolicy::Burn);
+    assert_eq!(migrated.open_orders, 0);
+    assert_eq!(sale.registered_orders().await, Some(vec![]));
+
+    // The pool is up to date already, only the orders are migrated
+    sale.migrate_pool(&admin, &orders).await.unwrap();
+
+    for order in &orders {
+        let migrated = sale.order(order).await.unwrap();
+        assert_eq!(migrated.version, Order::VERSION);
+        assert_eq!(u64::from(migrated.token_amount), 4);
+        assert_eq!(migrated.pool, pool);
+        assert!(!migrated.pool_seeded);
+    }
+    assert_eq!(sale.pool().await.open_orders, 2);
+    assert_eq!(sale.registered_orders().await, Some(orders.clone()));
+
+    // A migrated order is not counted twice
+    sale.migrate_pool(&admin, &orders[..1]).await.unwrap();
+    assert_eq!(sale.pool().await.open_orders, 2);
+
+    // The migrated accounts are usable again, the orders sign with the seeds they were placed with
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.redeem_order(&orders[0], &buyer, 1).await.unwrap();
+    assert_eq!(sale.tokens(&buyer.ata).await, 1);
+    for seller in &sellers {
+        sale.close_order(seller).await.unwrap();
+    }
+    assert_eq!(sale.pool().await.open_orders, 0);
+    assert_eq!(sale.registered_orders().await, Some(vec![]));
+}
+
+#[tokio::test]
+async fn does_not_adopt_the_orders_of_another_mint() {
+    let (mut sale, _, orders) = legacy_sale(1).await;
+    let admin = clone_keypair(&sale.admin);
+    let order = orders[0];
+    // The vault of the order is not the one for the selling mint
+    let mut order_data = sale.account(&order).await.unwrap().data;
+    order_data[ORDER_VAULT_OFFSET..ORDER_VAULT_OFFSET + 32].copy_from_slice(Pubkey::new_unique().as_ref());
+    sale.downgrade(&order, order_data).await;
+
+    assert_error(sale.migrate_pool(&admin, &[order]).await, ErrorCode::OrderNotFoundInPool);
+}
+
+#[tokio::test]
+async fn only_admin_migrates() {
+    let (mut sale, _, _) = legacy_sale(0).await;
+    let stranger = sale.trader(LAMPORTS_PER_SOL).await;
+
+    assert_error(sale.migrate_pool(&stranger.signer, &[]).await, ErrorCode::OnlyAdmin);
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use token_sale::error::ErrorCode;
+
+/// A sale initialized without tokens, the pool mints them
+fn minting_config() -> SaleConfig {
+    SaleConfig { amount_to_sell: 0, ..SaleConfig::default() }
+}
+
+#[tokio::test]
+async fn mints_the_bought_tokens() {
+    let mut sale = TestSale::start(minting_config()).await;
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let pool = sale.pool;
+    sale.enable_minting(Some(150), false).await.unwrap();
+    assert_eq!(sale.mint_authority().await, Some(pool));
+
+    sale.buy(&buyer, 100).await.unwrap();
+
+    assert_eq!(sale.tokens(&buyer.ata).await, 100);
+    assert_eq!(sale.mint_supply().await, sale.config.admin_tokens + 100);
+    let pool_account = sale.pool().await;
+    assert_eq!(u64::from(pool_account.tokens_sold), 100);
+    assert_eq!(u64::from(pool_account.total_supply), 100);
+
+    assert_error(sale.buy(&buyer, 51).await, ErrorCode::InsufficientTokensInVault);
+    sale.buy(&buyer, 50).await.unwrap();
+}
+
+#[tokio::test]
+async fn hands_the_mint_authority_back_upon_termination() {
+    let mut sale = TestSale::start(minting_config()).await;
+    let admin = sale.admin.pubkey();
+    sale.enable_minting(None, false).await.unwrap();
+    sale.warp_to(sale.config.end_at).await;
+
+    sale.terminate(None).await.unwrap();
+
+    assert_eq!(sale.mint_authority().await, Some(admin));
+}
+
+#[tokio::test]
+async fn revokes_the_mint_authority_upon_termination() {
+    let mut sale = TestSale::start(minting_config()).await;
+    let buyer = sale.trader(100 * LAMPORTS_PER_SOL).await;
+    sale.enable_minting(None, true).await.unwrap();
+    sale.buy(&buyer, 50_000).await.unwrap();
+    sale.warp_to(sale.config.end_at).await;
+
+    sale.terminate(None).await.unwrap();
+
+    assert_eq!(sale.mint_authority().await, None);
+    assert_eq!(sale.mint_supply().await, sale.config.admin_tokens + 50_000);
+}
+
+#[tokio::test]
+async fn only_a_pool_without_tokens_mints() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+
+    assert_error(sale.enable_minting(None, false).await, ErrorCode::PoolHoldsTokens);
+
+    let mut sale = TestSale::start(minting_config()).await;
+    sale.enable_minting(None, false).await.unwrap();
+
+    sale.next_slot().await;
+    assert_error(sale.enable_minting(None, false).await, ErrorCode::MintingAlreadyEnabled);
+    assert_error(sale.deposit_tokens(10).await, ErrorCode::MintingOnDemand);
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+use token_sale::error::ErrorCode;
+use token_sale::round::Round;
+
+#[tokio::test]
+async fn switches_rounds_and_raises_the_price() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+
+    sale.warp_to(sale.config.round_start_at + 50).await;
+    sale.switch_to_trading(&operator).await.unwrap();
+    let pool = sale.pool().await;
+    assert!(pool.current_round == Round::Trading);
+    assert_eq!(pool.round_start_at, sale.config.round_start_at + 50);
+    assert_eq!(pool.token_price, sale.config.token_price);
+
+    sale.warp_to(pool.round_start_at + 50).await;
+    sale.switch_to_buying(&operator).await.unwrap();
+    let pool = sale.pool().await;
+    assert!(pool.current_round == Round::Buying);
+    assert_eq!(pool.round_start_at, sale.config.round_start_at + 100);
+    // 1_000 * 1.5 + 100
+    assert_eq!(pool.token_price, 1_600);
+}
+
+#[tokio::test]
+async fn only_operator_switches_rounds() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let stranger = Keypair::new();
+
+    assert_error(sale.switch_to_trading(&stranger).await, ErrorCode::OnlyOperator);
+
+    let operator = Keypair::new();
+    let admin = clone_keypair(&sale.admin);
+    let admin_key = admin.pubkey();
+    sale.update_roles(&admin, admin_key, admin_key, operator.pubkey()).await.unwrap();
+
+    assert_error(sale.switch_to_trading(&admin).await, ErrorCode::OnlyOperator);
+    sale.switch_to_trading(&operator).await.unwrap();
+}
+
+#[tokio::test]
+async fn does_not_buy_out_of_the_buying_round() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+
+    let buying_ends_at = sale.round_ends_at().await;
+    sale.warp_to(buying_ends_at).await;
+    assert_error(sale.buy(&buyer, 1).await, ErrorCode::BuyingOver);
+
+    sale.warp_to(buying_ends_at - 1).await;
+    sale.switch_to_trading(&operator).await.unwrap();
+    assert_error(sale.buy(&buyer, 1).await, ErrorCode::NotBuyingRound);
+}
+
+#[tokio::test]
+async fn does_not_trade_out_of_the_trading_round() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+    let seller = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.buy(&seller, 10).await.unwrap();
+
+    assert_error(sale.place_order(&seller, 5, 1_000).await.map(|_| ()), ErrorCode::NotTradingRound);
+
+    sale.switch_to_trading(&operator).await.unwrap();
+    let trading_ends_at = sale.round_ends_at().await;
+    sale.warp_to(trading_ends_at).await;
+    assert_error(sale.place_order(&seller, 5, 1_000).await.map(|_| ()), ErrorCode::TradingOver);
+}
+
+#[tokio::test]
+async fn does_not_switch_to_the_current_round() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+
+    assert_error(sale.switch_to_buying(&operator).await, ErrorCode::AlreadyBuying);
+
+    sale.switch_to_trading(&operator).await.unwrap();
+    sale.warp_to(START + 1).await;
+    assert_error(sale.switch_to_trading(&operator).await, ErrorCode::AlreadyTrading);
+}
+
+#[tokio::test]
+async fn does_not_switch_after_the_end_of_the_round() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+
+    let buying_ends_at = sale.round_ends_at().await;
+    sale.warp_to(buying_ends_at + 1).await;
+    assert_error(sale.switch_to_trading(&operator).await, ErrorCode::BuyingCannotBeStopped);
+
+    sale.warp_to(buying_ends_at).await;
+    sale.switch_to_trading(&operator).await.unwrap();
+
+    let trading_ends_at = sale.round_ends_at().await;
+    sale.warp_to(trading_ends_at + 1).await;
+    assert_error(sale.switch_to_buying(&operator).await, ErrorCode::TradingCannotBeStopped);
+}
+
+#[tokio::test]
+async fn does_not_switch_after_the_end_of_the_ido() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+
+    sale.warp_to(sale.config.end_at).await;
+    assert_error(sale.switch_to_trading(&operator).await, ErrorCode::IDOOver);
+    assert_error(sale.switch_to_buying(&operator).await, ErrorCode::IDOOver);
+}
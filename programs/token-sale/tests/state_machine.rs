@@ -0,0 +1,295 @@
+#![cfg(feature = "test-bpf")]
+
+//! Drives random sequences of instructions with random clock advances through the sale
+//! and checks the invariants after every step.
+//!
+//! A failing run prints its seed, which reproduces it: `FUZZ_SEED=<seed> cargo test-bpf --test state_machine`.
+//! `FUZZ_RUNS` and `FUZZ_STEPS` set how many sequences are run and how long they are.
+
+mod common;
+
+use common::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use token_sale::unsold_tokens_policy::UnsoldTokensPolicy;
+
+const TRADERS: usize = 3;
+
+/// xorshift64*, good enough to pick the actions and reproducible across platforms
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// A number in `[from, to]`
+    fn range(&mut self, from: u64, to: u64) -> u64 {
+        from + self.next() % (to - from + 1)
+    }
+}
+
+fn env_or(name: &str, default: u64) -> u64 {
+    std::env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+#[derive(Debug)]
+enum Action {
+    Initialize,
+    Buy { trader: usize, amount: u64 },
+    SwitchToTrading,
+    SwitchToBuying,
+    PlaceOrder { trader: usize, amount: u64, price: u64 },
+    RedeemOrder { buyer: usize, seller: usize, amount: u64 },
+    CloseOrder { trader: usize },
+    WithdrawLamports,
+    Terminate,
+    CloseBuyerAccount { trader: usize },
+    Wait { seconds: i64 },
+}
+
+impl Action {
+    fn random(rng: &mut Rng) -> Self {
+        let trader = rng.range(0, TRADERS as u64 - 1) as usize;
+        match rng.range(0, 12) {
+            0 => Action::Initialize,
+            1 | 2 => Action::Buy { trader, amount: rng.range(0, 300) },
+            3 => Action::SwitchToTrading,
+            4 => Action::SwitchToBuying,
+            5 => Action::PlaceOrder { trader, amount: rng.range(0, 100), price: rng.range(1, 5_000) },
+            6 => Action::RedeemOrder {
+                buyer: trader,
+                seller: rng.range(0, TRADERS as u64 - 1) as usize,
+                amount: rng.range(0, 50),
+            },
+            7 => Action::CloseOrder { trader },
+            8 => Action::WithdrawLamports,
+            9 => Action::Terminate,
+            10 => Action::CloseBuyerAccount { trader },
+            _ => Action::Wait { seconds: rng.range(1, 150) as i64 },
+        }
+    }
+}
+
+/// The sale with everyone who holds its tokens or lamports
+struct Run {
+    sale: TestSale,
+    treasury: Keypair,
+    traders: Vec<Trader>,
+    /// Tokens and lamports held by all the participants right after the initialization
+    initial_tokens: u64,
+    initial_lamports: u64,
+    terminated: bool,
+}
+
+impl Run {
+    async fn new(rng: &mut Rng) -> Option<Self> {
+        let buying_duration = rng.range(10, 200) as u32;
+        let trading_duration = rng.range(10, 200) as u32;
+        let config = SaleConfig {
+            round_start_at: START + rng.range(1, 50) as i64,
+            end_at: START + rng.range(400, 2_000) as i64,
+            buying_duration,
+            trading_duration,
+            token_price: rng.range(1, 10_000),
+            amount_to_sell: rng.range(1, 1_000),
+            coeff_a: rng.range(0, 30) as f32 / 10.0,
+            coeff_b: rng.range(0, 1_000) as u32,
+            unsold_tokens_policy: UnsoldTokensPolicy::Burn,
+            ..SaleConfig::default()
+        };
+
+        // Random parameters might be rejected, which is checked by the initialization tests
+        let mut sale = TestSale::new(config).await;
+        sale.initialize().await.ok()?;
+
+        // A separate treasury keeps the lamports withdrawn from the pool apart from the admin
+        let treasury = Keypair::new();
+        let admin = clone_keypair(&sale.admin);
+        sale.update_roles(&admin, admin.pubkey(), treasury.pubkey(), admin.pubkey()).await.unwrap();
+        sale.airdrop(&treasury.pubkey(), LAMPORTS_PER_SOL).await;
+        // A cooldown has the buyers pay for their buyer accounts
+        if rng.range(0, 1) == 1 {
+            sale.set_buy_protection(&admin, 0, rng.range(1, 60) as u32, false, None).await.unwrap();
+        }
+
+        let mut traders = Vec::with_capacity(TRADERS);
+        for _ in 0..TRADERS {
+            traders.push(sale.trader(100 * LAMPORTS_PER_SOL).await);
+        }
+
+        let mut run = Run { sale, treasury, traders, initial_tokens: 0, initial_lamports: 0, terminated: false };
+        run.initial_tokens = run.tokens().await;
+        run.initial_lamports = run.lamports().await;
+        Some(run)
+    }
+
+    fn order_address(&self, trader: usize) -> Pubkey {
+        self.sale.order_address(&self.traders[trader].signer.pubkey())
+    }
+
+    fn order_vault(&self, trader: usize) -> Pubkey {
+        self.sale.ata(&self.order_address(trader))
+    }
+
+    /// Every account tokens or lamports can be moved to or from.
+    /// The fees are paid by the bank payer, which is not among them.
+    fn participants(&self) -> Vec<Pubkey> {
+        let mut keys = vec![self.sale.admin.pubkey(), self.treasury.pubkey(), self.sale.pool, self.sale.vault_selling];
+        for trader in 0..TRADERS {
+            keys.push(self.traders[trader].signer.pubkey());
+            keys.push(self.traders[trader].ata);
+            keys.push(self.order_address(trader));
+            keys.push(self.order_vault(trader));
+            keys.push(self.sale.buyer_account_address(&self.traders[trader].signer.pubkey()));
+        }
+        keys
+    }
+
+    /// Tokens held by the pool, the orders and the traders
+    async fn tokens(&mut self) -> u64 {
+        let mut keys = vec![self.sale.vault_selling];
+        for trader in 0..TRADERS {
+            keys.push(self.traders[trader].ata);
+            keys.push(self.order_vault(trader));
+        }
+
+        let mut total = 0;
+        for key in keys {
+            if self.sale.account(&key).await.is_some() {
+                total += self.sale.tokens(&key).await;
+            }
+        }
+        total
+    }
+
+    async fn lamports(&mut self) -> u64 {
+        let mut total = 0;
+        for key in self.participants() {
+            total += self.sale.lamports(&key).await;
+        }
+        total
+    }
+
+    async fn apply(&mut self, action: &Action) {
+        let admin = clone_keypair(&self.sale.admin);
+        // Failing is fine, a random action is often not allowed at the moment; the invariants must hold anyway
+        let _ = match *action {
+            Action::Initialize => self.sale.initialize().await,
+            Action::Buy { trader, amount } => self.sale.buy(&self.traders[trader], amount).await,
+            Action::SwitchToTrading => self.sale.switch_to_trading(&admin).await,
+            Action::SwitchToBuying => self.sale.switch_to_buying(&admin).await,
+            Action::PlaceOrder { trader, amount, price } => {
+                self.sale.place_order(&self.traders[trader], amount, price).await.map(|_| ())
+            }
+            Action::RedeemOrder { buyer, seller, amount } => {
+                let order = self.order_address(seller);
+                if self.sale.order(&order).await.is_none() {
+                    return;
+                }
+                self.sale.redeem_order(&order, &self.traders[buyer], amount).await
+            }
+            Action::CloseOrder { trader } => self.sale.close_order(&self.traders[trader]).await,
+            Action::WithdrawLamports => self.sale.withdraw_lamports(&admin, &self.treasury.pubkey()).await,
+            Action::Terminate => {
+                let result = self.sale.terminate(None).await;
+                self.terminated = result.is_ok();
+                result
+            }
+            Action::CloseBuyerAccount { trader } => self.sale.close_buyer_account(&self.traders[trader]).await,
+            Action::Wait { seconds } => {
+                let now = self.sale.now().await;
+                self.sale.warp_to(now + seconds).await;
+                Ok(())
+            }
+        };
+    }
+
+    async fn check_invariants(&mut self, seed: u64, step: usize, action: &Action) {
+        let context = format!("seed {}, step {}, after {:?}", seed, step, action);
+
+        let lamports = self.lamports().await;
+        assert_eq!(lamports, self.initial_lamports, "lamports are not conserved: {}", context);
+
+        let tokens = self.tokens().await;
+        if self.terminated {
+            // Only the unsold tokens are burned, everything bought stays with the traders
+            let admin_ata = self.sale.admin_ata;
+            let admin_tokens = self.sale.tokens(&admin_ata).await;
+            assert_eq!(self.sale.mint_supply().await, admin_tokens + tokens, "tokens are not conserved: {}", context);
+            return;
+        }
+        assert_eq!(tokens, self.initial_tokens, "tokens are not conserved: {}", context);
+
+        let pool = self.sale.pool().await;
+        let vault_selling = self.sale.vault_selling;
+        let vault_selling = self.sale.tokens(&vault_selling).await;
+        assert_eq!(
+            vault_selling + u64::from(pool.tokens_sold),
+            u64::from(pool.total_supply),
+            "the pool supply does not add up: {}",
+            context
+        );
+
+        let mut open_orders = 0;
+        let mut active_orders = vec![];
+        for trader in 0..TRADERS {
+            let order_address = self.order_address(trader);
+            if let Some(order) = self.sale.order(&order_address).await {
+                open_orders += 1;
+                if !order.is_empty {
+                    active_orders.push(order_address);
+                }
+                let order_vault = self.sale.tokens(&order.token_vault).await;
+                assert_eq!(order_vault, u64::from(order.token_amount), "the order vault does not match: {}", context);
+                assert_eq!(order.is_empty, order_vault == 0, "{}", context);
+            }
+        }
+        assert_eq!(pool.open_orders, open_orders, "open orders are miscounted: {}", context);
+
+        let mut registered_orders = self.sale.registered_orders().await.expect("the registry exists");
+        registered_orders.sort();
+        active_orders.sort();
+        assert_eq!(registered_orders, active_orders, "the order registry does not match: {}", context);
+
+        let pool_address = self.sale.pool;
+        let pool_account = self.sale.account(&pool_address).await.unwrap();
+        let rent = self.sale.ctx.banks_client.get_rent().await.unwrap();
+        assert!(
+            rent.is_exempt(pool_account.lamports, pool_account.data.len()),
+            "the pool is not rent-exempt: {}",
+            context
+        );
+    }
+}
+
+#[tokio::test]
+async fn random_sequences_keep_the_invariants() {
+    let runs = env_or("FUZZ_RUNS", 8);
+    let steps = env_or("FUZZ_STEPS", 60) as usize;
+    let first_seed = env_or("FUZZ_SEED", 0x5eed);
+
+    for seed in first_seed..first_seed + runs {
+        let mut rng = Rng::new(seed);
+        let mut run = match Run::new(&mut rng).await {
+            Some(run) => run,
+            None => continue,
+        };
+
+        for step in 0..steps {
+            let action = Action::random(&mut rng);
+            run.apply(&action).await;
+            run.check_invariants(seed, step, &action).await;
+            if run.terminated {
+                break;
+            }
+        }
+    }
+}
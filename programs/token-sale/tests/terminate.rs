@@ -0,0 +1,142 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+use token_sale::error::ErrorCode;
+use token_sale::unsold_tokens_policy::UnsoldTokensPolicy;
+
+#[tokio::test]
+async fn burns_unsold_tokens() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let pool = sale.pool;
+    let vault_selling = sale.vault_selling;
+    sale.buy(&buyer, 100).await.unwrap();
+
+    assert_error(sale.terminate(None).await, ErrorCode::IDONotOver);
+
+    sale.warp_to(sale.config.end_at).await;
+    sale.terminate(None).await.unwrap();
+
+    assert_eq!(sale.mint_supply().await, 100);
+    assert!(sale.account(&pool).await.is_none());
+    assert!(sale.account(&vault_selling).await.is_none());
+    assert!(sale.registered_orders().await.is_none());
+}
+
+#[tokio::test]
+async fn returns_unsold_tokens_to_the_admin() {
+    let config = SaleConfig { unsold_tokens_policy: UnsoldTokensPolicy::ReturnToAdmin, ..SaleConfig::default() };
+    let mut sale = TestSale::start(config).await;
+    let stranger = sale.trader(LAMPORTS_PER_SOL).await;
+    let admin_ata = sale.admin_ata;
+    sale.warp_to(sale.config.end_at).await;
+
+    assert_error(sale.terminate(Some(stranger.ata)).await, ErrorCode::WrongUnsoldTokensReceiver);
+    sale.terminate(Some(admin_ata)).await.unwrap();
+
+    assert_eq!(sale.tokens(&admin_ata).await, sale.config.amount_to_sell);
+    assert_eq!(sale.mint_supply().await, sale.config.admin_tokens);
+}
+
+#[tokio::test]
+async fn sends_unsold_tokens_to_the_destination() {
+    let mut sale = TestSale::new(SaleConfig::default()).await;
+    let liquidity_pool = sale.create_ata(&Keypair::new().pubkey()).await;
+    sale.config.unsold_tokens_policy = UnsoldTokensPolicy::SendToDestination;
+    sale.config.unsold_tokens_destination = liquidity_pool;
+    sale.initialize().await.unwrap();
+    sale.warp_to(sale.config.end_at).await;
+
+    sale.terminate(Some(liquidity_pool)).await.unwrap();
+
+    assert_eq!(sale.tokens(&liquidity_pool).await, sale.config.amount_to_sell);
+}
+
+#[tokio::test]
+async fn checks_the_destination_of_unsold_tokens() {
+    let mut sale = TestSale::new(SaleConfig::default()).await;
+    let other_mint = sale.create_stake_mint().await;
+    let other_mint_account = sale.create_ata_of(&Keypair::new().pubkey(), &other_mint).await;
+    sale.config.unsold_tokens_policy = UnsoldTokensPolicy::SendToDestination;
+    assert_error(sale.initialize().await, ErrorCode::InvalidUnsoldTokensDestination);
+
+    sale.config.unsold_tokens_destination = other_mint_account;
+    assert_error(sale.initialize().await, ErrorCode::InvalidUnsoldTokensDestination);
+}
+
+#[tokio::test]
+async fn changes_the_destination_of_unsold_tokens() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let liquidity_pool = sale.create_ata(&Keypair::new().pubkey()).await;
+    let other_mint = sale.create_stake_mint().await;
+    let other_mint_account = sale.create_ata_of(&Keypair::new().pubkey(), &other_mint).await;
+    let policy = UnsoldTokensPolicy::SendToDestination;
+
+    assert_error(
+        sale.set_unsold_tokens_policy(&admin, policy.clone(), other_mint_account).await,
+        ErrorCode::InvalidUnsoldTokensDestination,
+    );
+    let stranger = Keypair::new();
+    assert_error(
+        sale.set_unsold_tokens_policy(&stranger, policy.clone(), liquidity_pool).await,
+        ErrorCode::OnlyAdmin,
+    );
+    sale.set_unsold_tokens_policy(&admin, policy, liquidity_pool).await.unwrap();
+    sale.warp_to(sale.config.end_at).await;
+
+    sale.terminate(Some(liquidity_pool)).await.unwrap();
+
+    assert_eq!(sale.tokens(&liquidity_pool).await, sale.config.amount_to_sell);
+}
+
+#[tokio::test]
+async fn does_not_terminate_with_open_orders() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+    let seller = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.buy(&seller, 10).await.unwrap();
+    sale.switch_to_trading(&operator).await.unwrap();
+    sale.place_order(&seller, 5, 1_000).await.unwrap();
+    sale.warp_to(sale.config.end_at).await;
+
+    assert_error(sale.terminate(None).await, ErrorCode::OrdersStillOpen);
+
+    sale.close_order(&seller).await.unwrap();
+    sale.terminate(None).await.unwrap();
+}
+
+#[tokio::test]
+async fn reclaims_orders_left_open_after_the_end() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+    let seller = sale.trader(LAMPORTS_PER_SOL).await;
+    let reclaimer = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.buy(&seller, 10).await.unwrap();
+    sale.switch_to_trading(&operator).await.unwrap();
+    sale.place_order(&seller, 5, 1_000).await.unwrap();
+
+    assert_error(sale.reclaim_order(&seller.signer.pubkey(), &reclaimer.signer).await, ErrorCode::IDONotOver);
+    sale.warp_to(sale.config.end_at).await;
+    sale.reclaim_order(&seller.signer.pubkey(), &reclaimer.signer).await.unwrap();
+
+    assert_eq!(sale.tokens(&seller.ata).await, 10);
+    assert_eq!(sale.pool().await.open_orders, 0);
+    let order = sale.order_address(&seller.signer.pubkey());
+    assert!(sale.account(&order).await.is_none());
+    sale.terminate(None).await.unwrap();
+}
+
+#[tokio::test]
+async fn only_admin_terminates() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = Keypair::new().pubkey();
+    let admin = clone_keypair(&sale.admin);
+    sale.update_roles(&admin, Keypair::new().pubkey(), admin.pubkey(), operator).await.unwrap();
+    sale.warp_to(sale.config.end_at).await;
+
+    assert_error(sale.terminate(None).await, ErrorCode::OnlyAdmin);
+}
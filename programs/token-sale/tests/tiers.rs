@@ -0,0 +1,92 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use solana_sdk::signature::Signer;
+use token_sale::error::ErrorCode;
+
+/// Starts a sale tiered by 100 staked tokens for 10 and 500 for 60, returns the stake mint
+async fn tiered_sale() -> (TestSale, Pubkey) {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let stake_mint = sale.create_stake_mint().await;
+    sale.set_tiers(&admin, &stake_mint, &[(100, 10), (500, 60)]).await.unwrap();
+    (sale, stake_mint)
+}
+
+#[tokio::test]
+async fn buys_within_the_allocation_of_the_tier() {
+    let (mut sale, stake_mint) = tiered_sale().await;
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let staker_tokens = sale.give_stake_tokens(&stake_mint, &buyer, 1_000).await;
+
+    assert_error(sale.buy(&buyer, 1).await, ErrorCode::AllocationExceeded);
+
+    sale.stake(&buyer, &stake_mint, 100).await.unwrap();
+    sale.buy(&buyer, 10).await.unwrap();
+    assert_error(sale.buy(&buyer, 2).await, ErrorCode::AllocationExceeded);
+
+    sale.stake(&buyer, &stake_mint, 400).await.unwrap();
+    let stake = sale.stake_account(&buyer.signer.pubkey()).await.unwrap();
+    assert_eq!(u64::from(stake.amount), 500);
+    assert_eq!(stake.unlock_at, sale.config.end_at);
+    assert_eq!(sale.tokens(&staker_tokens).await, 500);
+
+    // Only the tokens left in the allocation are bought for the lamports
+    sale.buy_with_lamports(&buyer, LAMPORTS_PER_SOL / 10).await.unwrap();
+    assert_eq!(sale.tokens(&buyer.ata).await, 60);
+    let record = sale.buyer_account(&buyer.signer.pubkey()).await.unwrap();
+    assert_eq!(u64::from(record.tokens_bought), 60);
+}
+
+#[tokio::test]
+async fn unstakes_after_the_end_of_the_sale() {
+    let (mut sale, stake_mint) = tiered_sale().await;
+    let staker = sale.trader(LAMPORTS_PER_SOL).await;
+    let staker_tokens = sale.give_stake_tokens(&stake_mint, &staker, 1_000).await;
+    sale.stake(&staker, &stake_mint, 100).await.unwrap();
+
+    assert_error(sale.unstake(&staker, &stake_mint).await, ErrorCode::StakeLocked);
+
+    // The stake outlives the pool
+    sale.warp_to(sale.config.end_at).await;
+    sale.terminate(None).await.unwrap();
+    sale.unstake(&staker, &stake_mint).await.unwrap();
+
+    assert_eq!(sale.tokens(&staker_tokens).await, 1_000);
+    assert!(sale.stake_account(&staker.signer.pubkey()).await.is_none());
+}
+
+#[tokio::test]
+async fn validates_the_tiers() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let admin = clone_keypair(&sale.admin);
+    let stake_mint = sale.create_stake_mint().await;
+    let staker = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.give_stake_tokens(&stake_mint, &staker, 1_000).await;
+
+    assert_error(sale.stake(&staker, &stake_mint, 100).await, ErrorCode::SaleNotTiered);
+
+    for tiers in [
+        &[(500, 10), (100, 60)][..],
+        &[(100, 60), (500, 10)][..],
+        &[(100, 0)][..],
+        &[(1, 1), (2, 2), (3, 3), (4, 4), (5, 5)][..],
+    ] {
+        assert_error(sale.set_tiers(&admin, &stake_mint, tiers).await, ErrorCode::InvalidTiers);
+    }
+    assert_error(sale.set_tiers(&admin, &stake_mint, &[]).await, ErrorCode::NoTiers);
+    assert_error(sale.set_tiers(&staker.signer, &stake_mint, &[(100, 10)]).await, ErrorCode::OnlyAdmin);
+
+    sale.set_tiers(&admin, &stake_mint, &[(100, 10)]).await.unwrap();
+    let pool = sale.pool().await;
+    assert_eq!(pool.stake_mint, Some(stake_mint));
+    assert_eq!(u64::from(pool.tiers[0].allocation), 10);
+    assert_eq!(u64::from(pool.tiers[1].allocation), 0);
+
+    let other_mint = sale.create_stake_mint().await;
+    assert_error(sale.set_tiers(&admin, &other_mint, &[(100, 10)]).await, ErrorCode::StakeMintChanged);
+    assert_error(sale.stake(&staker, &stake_mint, 0).await, ErrorCode::StakingToFewTokens);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use common::*;
+
+/// A sale of a Token-2022 mint withholding 1% of every transfer
+async fn fee_sale() -> TestSale {
+    TestSale::start(SaleConfig { transfer_fee: Some((100, 1_000_000)), ..SaleConfig::default() }).await
+}
+
+#[tokio::test]
+async fn records_the_tokens_received_net_of_the_transfer_fee() {
+    let mut sale = fee_sale().await;
+    let vault_selling = sale.vault_selling;
+
+    assert_eq!(u64::from(sale.pool().await.total_supply), 9_900);
+    assert_eq!(sale.tokens(&vault_selling).await, 9_900);
+    assert_eq!(sale.withheld_fees(&vault_selling).await, 100);
+}
+
+#[tokio::test]
+async fn withholds_the_transfer_fee_from_the_buyer() {
+    let mut sale = fee_sale().await;
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let vault_selling = sale.vault_selling;
+
+    sale.buy(&buyer, 200).await.unwrap();
+
+    assert_eq!(sale.tokens(&buyer.ata).await, 198);
+    assert_eq!(sale.withheld_fees(&buyer.ata).await, 2);
+    assert_eq!(sale.tokens(&vault_selling).await, 9_700);
+}
+
+#[tokio::test]
+async fn trades_orders_net_of_the_transfer_fee() {
+    let mut sale = fee_sale().await;
+    let operator = clone_keypair(&sale.admin);
+    let seller = sale.trader(LAMPORTS_PER_SOL).await;
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.buy(&seller, 200).await.unwrap();
+    sale.switch_to_trading(&operator).await.unwrap();
+
+    let order_address = sale.place_order(&seller, 100, 2_000).await.unwrap();
+    let order = sale.order(&order_address).await.unwrap();
+    assert_eq!(u64::from(order.token_amount), 99);
+    assert_eq!(sale.tokens(&order.token_vault).await, 99);
+
+    sale.redeem_order(&order_address, &buyer, 50).await.unwrap();
+    assert_eq!(sale.tokens(&buyer.ata).await, 49);
+    assert_eq!(u64::from(sale.order(&order_address).await.unwrap().token_amount), 49);
+
+    sale.close_order(&seller).await.unwrap();
+    assert!(sale.account(&order.token_vault).await.is_none());
+    assert_eq!(sale.tokens(&seller.ata).await, 98 + 48);
+    assert_eq!(sale.mint_withheld_fees().await, 1);
+}
+
+#[tokio::test]
+async fn harvests_the_withheld_fees_before_closing_the_vault() {
+    let mut sale = fee_sale().await;
+    let vault_selling = sale.vault_selling;
+    sale.warp_to(sale.config.end_at).await;
+
+    sale.terminate(None).await.unwrap();
+
+    assert!(sale.account(&vault_selling).await.is_none());
+    assert_eq!(sale.mint_withheld_fees().await, 100);
+    assert_eq!(sale.mint_supply().await, sale.config.admin_tokens - 9_900);
+}
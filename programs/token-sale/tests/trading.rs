@@ -0,0 +1,159 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use token_sale::account::OrderRegistry;
+use token_sale::error::ErrorCode;
+
+/// Starts the sale, sells 10 tokens to the seller and switches to the trading round
+async fn trading_sale() -> (TestSale, Trader) {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+    let seller = sale.trader(LAMPORTS_PER_SOL).await;
+    sale.buy(&seller, 10).await.unwrap();
+    sale.switch_to_trading(&operator).await.unwrap();
+    (sale, seller)
+}
+
+#[tokio::test]
+async fn places_an_order() {
+    let (mut sale, seller) = trading_sale().await;
+
+    let order_address = sale.place_order(&seller, 4, 2_000).await.unwrap();
+
+    let order = sale.order(&order_address).await.unwrap();
+    assert!(!order.is_empty);
+    assert_eq!(order.owner, seller.signer.pubkey());
+    assert_eq!(order.pool, sale.pool);
+    assert_eq!(u64::from(order.token_amount), 4);
+    assert_eq!(order.token_price, 2_000);
+    assert_eq!(sale.tokens(&order.token_vault).await, 4);
+    assert_eq!(sale.tokens(&seller.ata).await, 6);
+    assert_eq!(sale.pool().await.open_orders, 1);
+    assert_eq!(sale.registered_orders().await, Some(vec![order_address]));
+}
+
+#[tokio::test]
+async fn places_orders_in_several_sales_of_the_same_mint() {
+    let (mut sale, seller) = trading_sale().await;
+    let operator = clone_keypair(&sale.admin);
+    let first_order = sale.place_order(&seller, 4, 2_000).await.unwrap();
+    let admin_ata = sale.admin_ata;
+    sale.mint_to(&admin_ata, 5_000).await;
+
+    sale.next_sale(SaleConfig { sale_id: 1, amount_to_sell: 5_000, ..SaleConfig::default() });
+    sale.initialize().await.unwrap();
+    sale.buy(&seller, 10).await.unwrap();
+    sale.switch_to_trading(&operator).await.unwrap();
+    let second_order = sale.place_order(&seller, 4, 3_000).await.unwrap();
+
+    assert_ne!(first_order, second_order);
+    assert_eq!(sale.order(&second_order).await.unwrap().pool, sale.pool);
+    assert_eq!(sale.order(&first_order).await.unwrap().token_price, 2_000);
+    assert_eq!(sale.registered_orders().await, Some(vec![second_order]));
+    sale.close_order(&seller).await.unwrap();
+    assert!(sale.order(&first_order).await.is_some());
+}
+
+#[tokio::test]
+async fn lists_the_orders_past_a_full_registry_in_the_next_page() {
+    let mut sale = TestSale::start(SaleConfig::default()).await;
+    let operator = clone_keypair(&sale.admin);
+    let mut sellers = vec![];
+    for _ in 0..=OrderRegistry::MAX_ORDERS {
+        let seller = sale.trader(LAMPORTS_PER_SOL).await;
+        // A seller keeps some tokens after placing an order
+        sale.buy(&seller, 2).await.unwrap();
+        sellers.push(seller);
+    }
+    sale.switch_to_trading(&operator).await.unwrap();
+
+    let (last_seller, sellers) = sellers.split_last().unwrap();
+    for seller in sellers {
+        sale.place_order(seller, 1, 2_000).await.unwrap();
+    }
+
+    assert_error(sale.place_order_in_page(last_seller, 1, 2_000, 0).await.map(|_| ()), ErrorCode::OrderRegistryFull);
+    assert_error(
+        sale.place_order_in_page(last_seller, 1, 2_000, 2).await.map(|_| ()),
+        ErrorCode::InvalidRegistryPage,
+    );
+    let last_order = sale.place_order(last_seller, 1, 2_000).await.unwrap();
+
+    assert_eq!(sale.order(&last_order).await.unwrap().registry_page, 1);
+    assert_eq!(sale.registry_page(1).await.unwrap().orders, vec![last_order]);
+    assert_eq!(sale.registered_orders().await.unwrap().len(), OrderRegistry::MAX_ORDERS + 1);
+    assert_eq!(sale.pool().await.last_registry_page, 1);
+    assert_eq!(sale.pool().await.open_orders, OrderRegistry::MAX_ORDERS as u32 + 1);
+
+    sale.close_order(last_seller).await.unwrap();
+    assert_eq!(sale.registry_page(1).await.unwrap().orders, vec![]);
+}
+
+#[tokio::test]
+async fn does_not_place_an_empty_order() {
+    let (mut sale, seller) = trading_sale().await;
+
+    assert_error(sale.place_order(&seller, 0, 2_000).await.map(|_| ()), ErrorCode::SellingToFewTokens);
+}
+
+#[tokio::test]
+async fn redeems_an_order() {
+    let (mut sale, seller) = trading_sale().await;
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let order_address = sale.place_order(&seller, 4, 2_000).await.unwrap();
+    let seller_lamports_before = sale.lamports(&seller.signer.pubkey()).await;
+
+    assert_error(sale.redeem_order(&order_address, &buyer, 0).await, ErrorCode::BuyingToFewTokens);
+    assert_error(sale.redeem_order(&order_address, &buyer, 5).await, ErrorCode::InsufficientTokensInVault);
+
+    sale.redeem_order(&order_address, &buyer, 3).await.unwrap();
+    let order = sale.order(&order_address).await.unwrap();
+    assert!(!order.is_empty);
+    assert_eq!(u64::from(order.token_amount), 1);
+    assert_eq!(sale.registered_orders().await, Some(vec![order_address]));
+
+    sale.redeem_order(&order_address, &buyer, 1).await.unwrap();
+    let order = sale.order(&order_address).await.unwrap();
+    assert!(order.is_empty);
+    assert_eq!(sale.tokens(&buyer.ata).await, 4);
+    assert_eq!(sale.registered_orders().await, Some(vec![]));
+
+    let price = sale.pool().await.token_price;
+    assert_eq!(sale.lamports(&seller.signer.pubkey()).await, seller_lamports_before + 4 * price);
+}
+
+#[tokio::test]
+async fn does_not_redeem_above_the_limits() {
+    let (mut sale, seller) = trading_sale().await;
+    let buyer = sale.trader(LAMPORTS_PER_SOL).await;
+    let order_address = sale.place_order(&seller, 4, 2_000).await.unwrap();
+    let token_price = sale.pool().await.token_price;
+
+    assert_error(
+        sale.redeem_order_with_limits(&order_address, &buyer, 1, token_price - 1, None).await,
+        ErrorCode::MaxPriceExceeded,
+    );
+    assert_error(
+        sale.redeem_order_with_limits(&order_address, &buyer, 1, token_price, Some(0)).await,
+        ErrorCode::UnexpectedRound,
+    );
+    sale.redeem_order_with_limits(&order_address, &buyer, 1, token_price, Some(1)).await.unwrap();
+}
+
+#[tokio::test]
+async fn closes_an_order() {
+    let (mut sale, seller) = trading_sale().await;
+    let order_address = sale.place_order(&seller, 4, 2_000).await.unwrap();
+    let order = sale.order(&order_address).await.unwrap();
+
+    sale.close_order(&seller).await.unwrap();
+
+    assert!(sale.order(&order_address).await.is_none());
+    assert!(sale.account(&order.token_vault).await.is_none());
+    assert_eq!(sale.tokens(&seller.ata).await, 10);
+    assert_eq!(sale.pool().await.open_orders, 0);
+    assert_eq!(sale.registered_orders().await, Some(vec![]));
+}
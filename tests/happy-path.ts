@@ -1,7 +1,7 @@
 import * as anchor from "@project-serum/anchor";
 import { Program } from "@project-serum/anchor";
 import { TokenSale } from "../target/types/token_sale";
-import { sleepTill } from "./helpers/helpers";
+import { sleep, sleepTill } from "./helpers/helpers";
 import { Connection, LAMPORTS_PER_SOL } from '@solana/web3.js';
 import { Round } from "./types/round";
 import { createCtx, Ctx } from "./helpers/ctx";
@@ -30,10 +30,19 @@ describe("happy-path", () => {
         const firstTraderBuy = new anchor.BN(4),
             secondTraderBuy = new anchor.BN(10);
         const poolBalanceBefore = (await connection.getAccountInfo(ctx.accounts.pool.key)).lamports;
+        const boughtEvents = [];
+        const listener = program.addEventListener("TokensBought", (event) => boughtEvents.push(event));
 
         await RPC.buyTokens(ctx, ctx.traderFirst.signer, firstTraderBuy);
         await RPC.buyTokens(ctx, ctx.traderSecond.signer, secondTraderBuy);
 
+        await sleep(1000);
+        await program.removeEventListener(listener);
+        expect(boughtEvents.map((event) => `${event.buyer}:${event.amount.tokens}`)).to.have.members([
+            `${ctx.traderFirst.signer.publicKey}:${firstTraderBuy}`,
+            `${ctx.traderSecond.signer.publicKey}:${secondTraderBuy}`,
+        ]);
+
         // The amount of lamports the program has to have after the first sales
         const expectedAddedPoolBalance = ((firstTraderBuy.add(secondTraderBuy))
             .mul(new anchor.BN(ctx.initialTokenPrice)));
@@ -68,8 +77,11 @@ describe("happy-path", () => {
         await CheckCtx.order(ctx, placedOrderSecond.address, placedOrderSecond);
         await CheckCtx.tokenBalance(ctx, ctx.traderSecond.ata, secondTraderBalanceBefore.amount, -amountToSellSecond);
 
-        const orders = await RPC.getOrders(ctx);
-        expect(orders.length).to.be.eq(2);
+        const orders = await RPC.getPoolOrders(ctx);
+        expect(orders.map((order) => `${order.address}`)).to.have.members([
+            `${placedOrderFirst.address}`,
+            `${placedOrderSecond.address}`,
+        ]);
     });
 
     it("Buys tokens from other traders", async () => {
@@ -85,6 +97,8 @@ describe("happy-path", () => {
         await RPC.redeemOrder(ctx, orderAddress, ctx.traderThird.signer, halfOfAllTokens.add(new anchor.BN(isTokenAmountEven ? 0 : 1)));
 
         await CheckCtx.redeemedOrder(ctx, orderAddress, ctx.traderThird.signer.publicKey, orderTokens, orderTokens);
+        const activeOrders = await RPC.getPoolOrders(ctx);
+        expect(activeOrders.map((order) => `${order.address}`)).to.not.include(`${orderAddress}`);
 
         const expectedLamportsIncome = ctx.initialTokenPrice.mul(orderTokens);
         await CheckCtx.lamportsBalance(ctx, orderBefore.owner, orderOwnerAccountBefore.lamports, expectedLamportsIncome);
@@ -111,6 +125,7 @@ describe("happy-path", () => {
         await CheckCtx.closeOrder(ctx, order2.address, orderTokens2, orderSpace, ownerKey2, ownerTokens2, ownerLamports2);
 
         expect((await RPC.getOrders(ctx, { page: 1, perPage: 100, status: 'all'})).length).to.be.eq(0);
+        expect((await RPC.getPoolOrders(ctx)).length).to.be.eq(0);
     });
 
     it("Waits till the end of the current round and switches to the buying round", async () => {
@@ -39,6 +39,7 @@ export namespace CheckCtx {
         expect(`${order.tokenVault}`).to.be.eq(`${expectedOrder.tokenVault}`);
         expect(`${order.tokenPrice}`).to.be.eq(`${expectedOrder.tokenPrice}`);
         expect(`${order.tokenAmount.tokens}`).to.be.eq(`${expectedOrder.tokenAmount.tokens}`);
+        expect(`${order.pool}`).to.be.eq(`${ctx.accounts.pool.key}`);
 
         await tokenBalance(ctx, expectedOrder.tokenVault, 0, expectedOrder.tokenAmount.tokens);
     }
@@ -83,7 +84,10 @@ export namespace CheckCtx {
 
     export async function poolInitialState(ctx: Ctx) {
         const pool = await ctx.program.account.poolAccount.fetch(ctx.accounts.pool.key);
-        expect(`${pool.owner}`).to.be.eq(`${ctx.owner.publicKey}`);
+        expect(`${pool.admin}`).to.be.eq(`${ctx.owner.publicKey}`);
+        expect(`${pool.treasury}`).to.be.eq(`${ctx.owner.publicKey}`);
+        expect(`${pool.operator}`).to.be.eq(`${ctx.owner.publicKey}`);
+        expect(pool.paused).to.be.false;
         expect(`${pool.sellingMint}`).to.be.eq(`${ctx.sellingMint}`);
         expect(`${pool.bump}`).to.be.eq(`${ctx.accounts.pool.bump}`);
         expect(`${pool.vaultSelling}`).to.be.eq(`${ctx.vaultSelling}`);
@@ -93,6 +97,8 @@ export namespace CheckCtx {
         expect(`${pool.buyingDuration}`).to.be.eq(`${ctx.buyingDuration}`);
         expect(`${pool.tradingDuration}`).to.be.eq(`${ctx.tradingDuration}`);
         expect(`${pool.tokenPrice}`).to.be.eq(`${ctx.initialTokenPrice}`);
+        expect(`${pool.totalSupply.tokens}`).to.be.eq(`${ctx.amountForSale}`);
+        expect(`${pool.tokensSold.tokens}`).to.be.eq(`${0}`);
     }
 }
 
@@ -18,6 +18,7 @@ import {
     createUserWithLamports,
     createUserWithATA,
 } from "./helpers";
+import { UnsoldTokensPolicy } from "../types/unsold-tokens-policy";
 
 // This interface is passed to every RPC test functions
 export interface Ctx {
@@ -27,16 +28,24 @@ export interface Ctx {
     owner: Signer,
     // The mint of the tokens that are selling in the IDO
     sellingMint: PublicKey,
+    // Tells apart the sales of the same mint
+    saleId: anchor.BN,
     // Owner's ATA with tokens for IDO
     tokensForDistribution: TokenAccount,
     buyingDuration: number,
     tradingDuration: number,
     initialTokenPrice: anchor.BN,
+    // The minimal parts of the token the price is for
+    priceUnits: anchor.BN,
     amountForSale: anchor.BN,
     // The coefficients that define the value of the token in the next buying round
     // using the formula: nextTokenPrice = tokenPrice * coeffA + coeffB
     coeffA: number,
     coeffB: number,
+    // What happens with the unsold tokens upon termination
+    unsoldTokensPolicy: object,
+    // The token account for the unsold tokens with the SendToDestination policy
+    unsoldTokensDestination: PublicKey,
     // pool ATA for storing the IDO tokens
     vaultSelling: PublicKey,
     traderFirst: CtxTrader,
@@ -48,6 +57,8 @@ export interface Ctx {
     endAt: anchor.BN,
     accounts: {
         pool: CtxAccountPDA,
+        // Lists the active orders of the pool
+        orderRegistry: CtxAccountPDA,
     }
 }
 
@@ -83,11 +94,8 @@ export async function createCtx(connection: Connection, program: Program<TokenSa
         owner,
         tokensForDistributionAmount,
     );
-    const [poolPDA, poolBump] = await anchor.web3.PublicKey.findProgramAddress(
-        [sellingMint.toBuffer()],
-        program.programId
-    );
-    const vaultSelling = await getAssociatedTokenAddress(sellingMint, poolPDA, true);
+    const saleId = new anchor.BN(0);
+    const { vaultSelling, accounts } = await saleAccounts(program, sellingMint, saleId);
     const [user1, ata1] = await createUserWithATA(connection, sellingMint);
     const [user2, ata2] = await createUserWithATA(connection, sellingMint);
     const [user3, ata3] = await createUserWithATA(connection, sellingMint);
@@ -99,14 +107,18 @@ export async function createCtx(connection: Connection, program: Program<TokenSa
         program,
         owner,
         sellingMint,
+        saleId,
         tokensForDistribution,
         buyingDuration: 3,
         tradingDuration: 3,
         // 1 Token = 100_000_000 Lamports = 0.1 SOL
         initialTokenPrice: new anchor.BN(0.1 * LAMPORTS_PER_SOL),
+        priceUnits: new anchor.BN(1),
         amountForSale: new anchor.BN(10_000),
         coeffA: 1.2,
         coeffB: 0.01 * LAMPORTS_PER_SOL,
+        unsoldTokensPolicy: UnsoldTokensPolicy.Burn,
+        unsoldTokensDestination: PublicKey.default,
         vaultSelling,
         traderFirst: {
             signer: user1,
@@ -122,8 +134,51 @@ export async function createCtx(connection: Connection, program: Program<TokenSa
         },
         roundStartAt: new anchor.BN(now + 1),
         endAt: new anchor.BN(now + 12),
+        accounts,
+    }
+}
+
+// The context of another sale of the same mint, the owner gets the tokens for it
+export async function createNextSaleCtx(ctx: Ctx, saleId: anchor.BN): Promise<Ctx> {
+    await mintTo(
+        ctx.connection,
+        ctx.owner,
+        ctx.sellingMint,
+        ctx.tokensForDistribution.address,
+        ctx.owner,
+        Number(ctx.amountForSale),
+    );
+    const { vaultSelling, accounts } = await saleAccounts(ctx.program, ctx.sellingMint, saleId);
+    const now = Math.floor(Date.now() / 1000);
+
+    return {
+        ...ctx,
+        saleId,
+        vaultSelling,
+        roundStartAt: new anchor.BN(now + 1),
+        endAt: new anchor.BN(now + 12),
+        accounts,
+    };
+}
+
+// The first sale of a mint keeps the pool address derived from the mint alone
+async function saleAccounts(program: Program<TokenSale>, sellingMint: PublicKey, saleId: anchor.BN) {
+    const saleSeed = saleId.isZero() ? Buffer.alloc(0) : saleId.toArrayLike(Buffer, 'le', 8);
+    const [poolPDA, poolBump] = await anchor.web3.PublicKey.findProgramAddress(
+        [sellingMint.toBuffer(), saleSeed],
+        program.programId
+    );
+    const vaultSelling = await getAssociatedTokenAddress(sellingMint, poolPDA, true);
+    const [orderRegistryPDA, orderRegistryBump] = await anchor.web3.PublicKey.findProgramAddress(
+        [anchor.utils.bytes.utf8.encode("order_registry"), poolPDA.toBuffer()],
+        program.programId
+    );
+
+    return {
+        vaultSelling,
         accounts: {
             pool: { key: poolPDA, bump: poolBump },
-        }
-    }
+            orderRegistry: { key: orderRegistryPDA, bump: orderRegistryBump },
+        },
+    };
 }
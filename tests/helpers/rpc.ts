@@ -10,23 +10,31 @@ import { Ctx } from "./ctx";
 import { sha256 } from "js-sha256";
 import bs58 from 'bs58';
 
+// No slippage limit for the tests that do not check it
+const U64_MAX = new anchor.BN("18446744073709551615");
+
 export namespace RPC {
     export async function initialize(ctx: Ctx) {
         await ctx.program.methods.initialize(
+            ctx.saleId,
             ctx.roundStartAt,
             ctx.endAt,
             ctx.buyingDuration,
             ctx.tradingDuration,
             ctx.initialTokenPrice,
+            ctx.priceUnits,
             { tokens: ctx.amountForSale },
             ctx.coeffA,
             ctx.coeffB,
+            ctx.unsoldTokensPolicy,
         ).accounts({
             poolAccount: ctx.accounts.pool.key,
             distributionAuthority: ctx.owner.publicKey,
             tokensForDistribution: ctx.tokensForDistribution.address,
             sellingMint: ctx.sellingMint,
             vaultSelling: ctx.vaultSelling,
+            orderRegistry: ctx.accounts.orderRegistry.key,
+            unsoldTokensDestination: ctx.unsoldTokensDestination,
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -36,14 +44,44 @@ export namespace RPC {
 
     export async function buyTokens(ctx: Ctx, trader: Signer, tokensAmount: anchor.BN) {
         const ata = await getOrCreateAssociatedTokenAccount(ctx.connection, trader, ctx.sellingMint, trader.publicKey);
+        const [buyerAccount] = await anchor.web3.PublicKey.findProgramAddress(
+            [
+                anchor.utils.bytes.utf8.encode("buyer"),
+                ctx.accounts.pool.key.toBuffer(),
+                trader.publicKey.toBuffer(),
+            ],
+            ctx.program.programId
+        );
+        const [stakeAccount] = await anchor.web3.PublicKey.findProgramAddress(
+            [
+                anchor.utils.bytes.utf8.encode("stake"),
+                ctx.accounts.pool.key.toBuffer(),
+                trader.publicKey.toBuffer(),
+            ],
+            ctx.program.programId
+        );
+        const [allocationAccount] = await anchor.web3.PublicKey.findProgramAddress(
+            [
+                anchor.utils.bytes.utf8.encode("allocation"),
+                ctx.accounts.pool.key.toBuffer(),
+                trader.publicKey.toBuffer(),
+            ],
+            ctx.program.programId
+        );
         await ctx.program.methods.buy(
             { tokens: tokensAmount },
+            { lamports: U64_MAX },
+            null,
         ).accounts({
             poolAccount: ctx.accounts.pool.key,
             sellingMint: ctx.sellingMint,
             vaultSelling: ctx.vaultSelling,
             buyer: trader.publicKey,
             buyerTokenAccount: ata.address,
+            buyerAccount,
+            stakeAccount,
+            allocationAccount,
+            instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
             clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
@@ -54,8 +92,10 @@ export namespace RPC {
         await ctx.program.methods.switchToTrading()
             .accounts({
                 poolAccount: ctx.accounts.pool.key,
+                operator: ctx.owner.publicKey,
                 clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
             })
+            .signers([ctx.owner])
             .rpc();
     }
 
@@ -68,6 +108,7 @@ export namespace RPC {
         const [orderPDA, orderBump] = await anchor.web3.PublicKey.findProgramAddress(
             [
                 anchor.utils.bytes.utf8.encode("order"),
+                ctx.accounts.pool.key.toBuffer(),
                 seller.publicKey.toBuffer(),
             ],
             ctx.program.programId
@@ -86,6 +127,7 @@ export namespace RPC {
             .placeOrder(
                 { tokens: amountToSell },
                 priceForToken,
+                0,
             )
             .accounts({
                 poolAccount: ctx.accounts.pool.key,
@@ -94,6 +136,7 @@ export namespace RPC {
                 sellerTokenAccount: sellerTokenAccount.address,
                 order: orderPDA,
                 orderTokenVault,
+                orderRegistry: ctx.accounts.orderRegistry.key,
                 systemProgram: SystemProgram.programId,
                 tokenProgram: TOKEN_PROGRAM_ID,
                 associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -163,11 +206,33 @@ export namespace RPC {
         return ordersWithData;
     }
 
+    // Get the orders of the pool that still have tokens to sell with one read of the order registry
+    export async function getPoolOrders(ctx: Ctx): Promise<Order[]> {
+        const registry = await ctx.program.account.orderRegistry.fetch(ctx.accounts.orderRegistry.key);
+        const orderKeys = registry.orders as PublicKey[];
+
+        if (orderKeys.length === 0) {
+            return [];
+        }
+
+        return (await ctx.program.account.order.fetchMultiple(orderKeys))
+            .map((order: any, i) => {
+                return {
+                    address: orderKeys[i],
+                    bump: order.bump,
+                    tokenVault: order.tokenVault,
+                    owner: order.owner,
+                    tokenPrice: order.tokenPrice,
+                    tokenAmount: order.tokenAmount,
+                };
+            });
+    }
+
     export async function redeemOrder(ctx: Ctx, orderAddress: PublicKey, buyer: Signer, amountToBuy: anchor.BN) {
         const order = await ctx.program.account.order.fetch(orderAddress);
         const buyerTokenAccount: PublicKey = await getAssociatedTokenAddress(ctx.sellingMint, buyer.publicKey);
 
-        await ctx.program.methods.redeemOrder({ tokens: amountToBuy })
+        await ctx.program.methods.redeemOrder({ tokens: amountToBuy }, U64_MAX, null)
             .accounts({
                 poolAccount: ctx.accounts.pool.key,
                 sellingMint: ctx.sellingMint,
@@ -176,6 +241,7 @@ export namespace RPC {
                 order: orderAddress,
                 orderOwner: order.owner,
                 orderTokenVault: order.tokenVault,
+                orderRegistry: ctx.accounts.orderRegistry.key,
                 tokenProgram: TOKEN_PROGRAM_ID,
                 clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
                 systemProgram: SystemProgram.programId,
@@ -199,6 +265,7 @@ export namespace RPC {
                 orderTokenVault: orderTokenVault,
                 orderOwner: ownerSigner.publicKey,
                 ownerTokenVault: ownerTokenAccount,
+                orderRegistry: ctx.accounts.orderRegistry.key,
             })
             .signers([ownerSigner])
             .rpc();
@@ -208,8 +275,10 @@ export namespace RPC {
         await ctx.program.methods.switchToBuying()
             .accounts({
                 poolAccount: ctx.accounts.pool.key,
+                operator: ctx.owner.publicKey,
                 clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
             })
+            .signers([ctx.owner])
             .rpc();
     }
 
@@ -218,7 +287,8 @@ export namespace RPC {
             .accounts({
                 poolAccount: ctx.accounts.pool.key,
                 sellingMint: ctx.sellingMint,
-                owner: ctx.owner.publicKey,
+                admin: ctx.owner.publicKey,
+                treasury: ctx.owner.publicKey,
                 tokenProgram: TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
                 clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
@@ -227,13 +297,16 @@ export namespace RPC {
             .rpc();
     }
 
-    export async function terminate(ctx: Ctx) {
+    // The unsold tokens receiver is ignored by the Burn policy, so the vault itself is passed by default
+    export async function terminate(ctx: Ctx, unsoldTokensReceiver: PublicKey = ctx.vaultSelling) {
         await ctx.program.methods.terminate()
             .accounts({
                 poolAccount: ctx.accounts.pool.key,
                 sellingMint: ctx.sellingMint,
                 vaultSelling: ctx.vaultSelling,
-                owner: ctx.owner.publicKey,
+                admin: ctx.owner.publicKey,
+                unsoldTokensReceiver,
+                orderRegistry: ctx.accounts.orderRegistry.key,
                 tokenProgram: TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
                 clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
@@ -241,6 +314,135 @@ export namespace RPC {
             .signers([ctx.owner])
             .rpc();
     }
+
+    export async function depositTokens(ctx: Ctx, amount: anchor.BN) {
+        await ctx.program.methods.depositTokens({ tokens: amount })
+            .accounts({
+                poolAccount: ctx.accounts.pool.key,
+                sellingMint: ctx.sellingMint,
+                vaultSelling: ctx.vaultSelling,
+                admin: ctx.owner.publicKey,
+                adminTokenAccount: ctx.tokensForDistribution.address,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+            })
+            .signers([ctx.owner])
+            .rpc();
+    }
+
+    export async function withdrawUnsoldTokens(ctx: Ctx, amount: anchor.BN, destination: PublicKey) {
+        await ctx.program.methods.withdrawUnsoldTokens({ tokens: amount })
+            .accounts({
+                poolAccount: ctx.accounts.pool.key,
+                sellingMint: ctx.sellingMint,
+                vaultSelling: ctx.vaultSelling,
+                admin: ctx.owner.publicKey,
+                destinationTokenAccount: destination,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+            })
+            .signers([ctx.owner])
+            .rpc();
+    }
+
+    export async function updateConfig(ctx: Ctx, config: SaleConfig) {
+        await ctx.program.methods.updateConfig(
+            config.endAt,
+            config.buyingDuration,
+            config.tradingDuration,
+            config.coeffA,
+            config.coeffB,
+        ).accounts({
+            poolAccount: ctx.accounts.pool.key,
+            admin: ctx.owner.publicKey,
+            clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+        }).signers([ctx.owner]).rpc();
+    }
+
+    export async function updateRoles(ctx: Ctx, admin: Signer, roles: Roles) {
+        await ctx.program.methods.updateRoles(roles.admin, roles.treasury, roles.operator)
+            .accounts({
+                poolAccount: ctx.accounts.pool.key,
+                admin: admin.publicKey,
+            })
+            .signers([admin])
+            .rpc();
+    }
+
+    export async function setPaused(ctx: Ctx, admin: Signer, paused: boolean) {
+        await ctx.program.methods.setPaused(paused)
+            .accounts({
+                poolAccount: ctx.accounts.pool.key,
+                admin: admin.publicKey,
+            })
+            .signers([admin])
+            .rpc();
+    }
+
+    export async function quoteBuy(ctx: Ctx, amount: anchor.BN): Promise<any> {
+        const response = await ctx.program.methods.quoteBuy({ tokens: amount })
+            .accounts({
+                poolAccount: ctx.accounts.pool.key,
+                vaultSelling: ctx.vaultSelling,
+            })
+            .simulate();
+
+        return returnData(ctx, "BuyQuote", response.raw);
+    }
+
+    export async function quoteRedeem(ctx: Ctx, orderAddress: PublicKey, amount: anchor.BN): Promise<any> {
+        const order = await ctx.program.account.order.fetch(orderAddress);
+        const response = await ctx.program.methods.quoteRedeem({ tokens: amount })
+            .accounts({
+                poolAccount: ctx.accounts.pool.key,
+                order: orderAddress,
+                tokenVault: order.tokenVault,
+            })
+            .simulate();
+
+        return returnData(ctx, "RedeemQuote", response.raw);
+    }
+
+    export async function poolStatus(ctx: Ctx): Promise<any> {
+        const response = await ctx.program.methods.poolStatus()
+            .accounts({
+                poolAccount: ctx.accounts.pool.key,
+                vaultSelling: ctx.vaultSelling,
+                clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+            })
+            .simulate();
+
+        return returnData(ctx, "PoolStatus", response.raw);
+    }
+
+    // Decodes the value a view instruction passed to set_return_data from the simulation logs
+    function returnData(ctx: Ctx, typeName: string, logs: readonly string[]): any {
+        const prefix = `Program return: ${ctx.program.programId.toBase58()} `;
+        const log = logs.find((line) => line.startsWith(prefix));
+        if (!log) {
+            throw new Error(`No return data in the logs of ${typeName}`);
+        }
+
+        // The runtime trims the trailing zeros of the return data
+        const data = Buffer.alloc(1024);
+        Buffer.from(log.slice(prefix.length), 'base64').copy(data);
+
+        return ctx.program.coder.types.decode(typeName, data);
+    }
+}
+
+export interface SaleConfig {
+    endAt: anchor.BN,
+    buyingDuration: number,
+    tradingDuration: number,
+    coeffA: number,
+    coeffB: number,
+}
+
+export interface Roles {
+    admin: PublicKey,
+    treasury: PublicKey,
+    operator: PublicKey,
 }
 
 export interface Order {
@@ -0,0 +1,54 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { TokenSale } from "../target/types/token_sale";
+import { Connection, Keypair } from '@solana/web3.js';
+import { createCtx, Ctx } from "./helpers/ctx";
+import { RPC } from "./helpers/rpc";
+import { expect } from "chai";
+
+describe("roles", () => {
+    anchor.setProvider(anchor.AnchorProvider.env());
+
+    const program = anchor.workspace.TokenSale as Program<TokenSale>;
+    const connection = new Connection("http://localhost:8899", 'recent');
+    let ctx: Ctx;
+
+    before(async () => {
+        ctx = await createCtx(connection, program);
+        await RPC.initialize(ctx);
+    });
+
+    it("Does not let anyone but the admin pause the sale", async () => {
+        await RPC.setPaused(ctx, ctx.traderFirst.signer, true)
+            .then(() => expect.fail("The sale is paused by a trader"))
+            .catch((err) => expect(`${err}`).to.contain("OnlyAdmin"));
+    });
+
+    it("Rejects buying while the sale is paused", async () => {
+        await RPC.setPaused(ctx, ctx.owner, true);
+
+        await RPC.buyTokens(ctx, ctx.traderFirst.signer, new anchor.BN(1))
+            .then(() => expect.fail("Tokens are bought while the sale is paused"))
+            .catch((err) => expect(`${err}`).to.contain("SalePaused"));
+
+        await RPC.setPaused(ctx, ctx.owner, false);
+        await RPC.buyTokens(ctx, ctx.traderFirst.signer, new anchor.BN(1));
+    });
+
+    it("Hands the roles over", async () => {
+        const treasury = Keypair.generate().publicKey;
+        const operator = Keypair.generate().publicKey;
+
+        await RPC.updateRoles(ctx, ctx.owner, { admin: ctx.traderSecond.signer.publicKey, treasury, operator });
+
+        const pool = await program.account.poolAccount.fetch(ctx.accounts.pool.key);
+        expect(`${pool.admin}`).to.be.eq(`${ctx.traderSecond.signer.publicKey}`);
+        expect(`${pool.treasury}`).to.be.eq(`${treasury}`);
+        expect(`${pool.operator}`).to.be.eq(`${operator}`);
+
+        // The previous admin has no power anymore
+        await RPC.setPaused(ctx, ctx.owner, true)
+            .then(() => expect.fail("The sale is paused by the previous admin"))
+            .catch((err) => expect(`${err}`).to.contain("OnlyAdmin"));
+    });
+});
@@ -0,0 +1,40 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { TokenSale } from "../target/types/token_sale";
+import { Connection } from '@solana/web3.js';
+import { createCtx, createNextSaleCtx, Ctx } from "./helpers/ctx";
+import { RPC } from "./helpers/rpc";
+import { CheckCtx } from "./helpers/check";
+import { expect } from "chai";
+
+describe("sales", () => {
+    anchor.setProvider(anchor.AnchorProvider.env());
+
+    const program = anchor.workspace.TokenSale as Program<TokenSale>;
+    const connection = new Connection("http://localhost:8899", 'recent');
+    let firstSale: Ctx;
+
+    before(async () => {
+        firstSale = await createCtx(connection, program);
+        await RPC.initialize(firstSale);
+    });
+
+    it("Runs another sale of the same mint next to the first one", async () => {
+        const secondSale = await createNextSaleCtx(firstSale, new anchor.BN(1));
+
+        await RPC.initialize(secondSale);
+
+        expect(`${secondSale.accounts.pool.key}`).to.not.be.eq(`${firstSale.accounts.pool.key}`);
+        const pool = await program.account.poolAccount.fetch(secondSale.accounts.pool.key);
+        expect(pool.saleId.toNumber()).to.be.eq(1);
+        await CheckCtx.tokenBalance(secondSale, secondSale.vaultSelling, secondSale.amountForSale, 0);
+        await CheckCtx.tokenBalance(firstSale, firstSale.vaultSelling, firstSale.amountForSale, 0);
+    });
+
+    // The system program refuses to create the pool that already exists
+    it("Does not initialize the same sale twice", async () => {
+        await RPC.initialize(firstSale)
+            .then(() => expect.fail("The sale is initialized twice"))
+            .catch((err) => expect(`${err}`).to.contain("custom program error: 0x0"));
+    });
+});
@@ -0,0 +1,52 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { TokenSale } from "../target/types/token_sale";
+import { Connection } from '@solana/web3.js';
+import { mintTo } from "@solana/spl-token";
+import { createCtx, Ctx } from "./helpers/ctx";
+import { RPC } from "./helpers/rpc";
+import { CheckCtx } from "./helpers/check";
+import { sleepTill } from "./helpers/helpers";
+import { expect } from "chai";
+
+describe("supply", () => {
+    anchor.setProvider(anchor.AnchorProvider.env());
+
+    const program = anchor.workspace.TokenSale as Program<TokenSale>;
+    const connection = new Connection("http://localhost:8899", 'recent');
+    let ctx: Ctx;
+
+    before(async () => {
+        ctx = await createCtx(connection, program);
+        await RPC.initialize(ctx);
+    });
+
+    it("Deposits an additional tranche of tokens", async () => {
+        const tranche = new anchor.BN(500);
+        await mintTo(connection, ctx.owner, ctx.sellingMint, ctx.tokensForDistribution.address, ctx.owner, Number(tranche));
+
+        await RPC.depositTokens(ctx, tranche);
+
+        await CheckCtx.tokenBalance(ctx, ctx.vaultSelling, ctx.amountForSale, tranche);
+        const pool = await program.account.poolAccount.fetch(ctx.accounts.pool.key);
+        expect(`${pool.totalSupply.tokens}`).to.be.eq(`${ctx.amountForSale.add(tranche)}`);
+    });
+
+    it("Does not withdraw unsold tokens during the buying round", async () => {
+        await RPC.withdrawUnsoldTokens(ctx, new anchor.BN(1), ctx.tokensForDistribution.address)
+            .then(() => expect.fail("Unsold tokens are withdrawn during the buying round"))
+            .catch((err) => expect(`${err}`).to.contain("BuyingRunning"));
+    });
+
+    it("Withdraws unsold tokens after the buying round", async () => {
+        await sleepTill((Number(ctx.roundStartAt) + ctx.buyingDuration + 1) * 1000);
+        const poolBefore = await program.account.poolAccount.fetch(ctx.accounts.pool.key);
+        const amount = new anchor.BN(300);
+
+        await RPC.withdrawUnsoldTokens(ctx, amount, ctx.tokensForDistribution.address);
+
+        await CheckCtx.tokenBalance(ctx, ctx.tokensForDistribution.address, 0, amount);
+        const pool = await program.account.poolAccount.fetch(ctx.accounts.pool.key);
+        expect(`${pool.totalSupply.tokens}`).to.be.eq(`${poolBefore.totalSupply.tokens.sub(amount)}`);
+    });
+});
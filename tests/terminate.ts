@@ -0,0 +1,57 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { TokenSale } from "../target/types/token_sale";
+import { Connection } from '@solana/web3.js';
+import { getAccount as getTokenAccount, getMint } from "@solana/spl-token";
+import { createCtx, Ctx } from "./helpers/ctx";
+import { RPC } from "./helpers/rpc";
+import { CheckCtx } from "./helpers/check";
+import { sleepTill } from "./helpers/helpers";
+import { UnsoldTokensPolicy } from "./types/unsold-tokens-policy";
+import { expect } from "chai";
+
+describe("terminate", () => {
+    anchor.setProvider(anchor.AnchorProvider.env());
+
+    const program = anchor.workspace.TokenSale as Program<TokenSale>;
+    const connection = new Connection("http://localhost:8899", 'recent');
+
+    it("Returns the unsold tokens to the admin", async () => {
+        const ctx: Ctx = await createCtx(connection, program);
+        ctx.unsoldTokensPolicy = UnsoldTokensPolicy.ReturnToAdmin;
+        await RPC.initialize(ctx);
+        await RPC.buyTokens(ctx, ctx.traderFirst.signer, new anchor.BN(10));
+        await sleepTill((Number(ctx.endAt) + 1) * 1000);
+
+        const unsoldTokens = (await getTokenAccount(connection, ctx.vaultSelling)).amount;
+        const mintSupplyBefore = (await getMint(connection, ctx.sellingMint)).supply;
+
+        await RPC.terminate(ctx, ctx.traderSecond.ata)
+            .then(() => expect.fail("The unsold tokens are sent to a trader"))
+            .catch((err) => expect(`${err}`).to.contain("WrongUnsoldTokensReceiver"));
+
+        await RPC.terminate(ctx, ctx.tokensForDistribution.address);
+
+        await CheckCtx.tokenBalance(ctx, ctx.tokensForDistribution.address, 0, unsoldTokens);
+        expect((await getMint(connection, ctx.sellingMint)).supply).to.be.eq(mintSupplyBefore);
+    });
+
+    it("Does not terminate while there are open orders", async () => {
+        const ctx: Ctx = await createCtx(connection, program);
+        await RPC.initialize(ctx);
+        await RPC.buyTokens(ctx, ctx.traderFirst.signer, new anchor.BN(10));
+        await sleepTill((Number(ctx.roundStartAt) + ctx.buyingDuration) * 1000);
+        await RPC.switchToTrading(ctx);
+        const order = await RPC.placeOrder(ctx, ctx.traderFirst.signer, new anchor.BN(5), ctx.initialTokenPrice);
+        expect((await program.account.poolAccount.fetch(ctx.accounts.pool.key)).openOrders).to.be.eq(1);
+        await sleepTill((Number(ctx.endAt) + 1) * 1000);
+
+        await RPC.terminate(ctx)
+            .then(() => expect.fail("The IDO is terminated with an open order"))
+            .catch((err) => expect(`${err}`).to.contain("OrdersStillOpen"));
+
+        await RPC.closeOrder(ctx, order.address, order.tokenVault, ctx.traderFirst.signer, ctx.traderFirst.ata);
+        expect((await program.account.poolAccount.fetch(ctx.accounts.pool.key)).openOrders).to.be.eq(0);
+        await RPC.terminate(ctx);
+    });
+});
@@ -1,4 +1,6 @@
 export const Round = {
     Buying: { buying: {} },
     Trading: { trading: {} },
+    Commit: { commit: {} },
+    Reveal: { reveal: {} },
 }
@@ -0,0 +1,5 @@
+export const UnsoldTokensPolicy = {
+    Burn: { burn: {} },
+    ReturnToAdmin: { returnToAdmin: {} },
+    SendToDestination: { sendToDestination: {} },
+}
@@ -0,0 +1,54 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { TokenSale } from "../target/types/token_sale";
+import { Connection } from '@solana/web3.js';
+import { createCtx, Ctx } from "./helpers/ctx";
+import { RPC, SaleConfig } from "./helpers/rpc";
+import { sleepTill } from "./helpers/helpers";
+import { expect } from "chai";
+
+describe("update-config", () => {
+    anchor.setProvider(anchor.AnchorProvider.env());
+
+    const program = anchor.workspace.TokenSale as Program<TokenSale>;
+    const connection = new Connection("http://localhost:8899", 'recent');
+    let ctx: Ctx;
+    let config: SaleConfig;
+
+    before(async () => {
+        ctx = await createCtx(connection, program);
+        await RPC.initialize(ctx);
+        config = {
+            endAt: ctx.endAt,
+            buyingDuration: ctx.buyingDuration,
+            tradingDuration: ctx.tradingDuration,
+            coeffA: ctx.coeffA,
+            coeffB: ctx.coeffB,
+        };
+        // Wait for the first buying round to start
+        await sleepTill((Number(ctx.roundStartAt) + 1) * 1000);
+    });
+
+    it("Extends the IDO and changes the future rounds", async () => {
+        config = { ...config, endAt: ctx.endAt.addn(10), tradingDuration: 5, coeffA: 1.5, coeffB: 0 };
+        await RPC.updateConfig(ctx, config);
+
+        const pool = await program.account.poolAccount.fetch(ctx.accounts.pool.key);
+        expect(`${pool.endAt}`).to.be.eq(`${config.endAt}`);
+        expect(pool.tradingDuration).to.be.eq(5);
+        expect(pool.coeffA).to.be.eq(1.5);
+        expect(pool.coeffB).to.be.eq(0);
+    });
+
+    it("Does not change the duration of the running round", async () => {
+        await RPC.updateConfig(ctx, { ...config, buyingDuration: ctx.buyingDuration + 1 })
+            .then(() => expect.fail("The running round is prolonged"))
+            .catch((err) => expect(`${err}`).to.contain("RunningRoundDurationLocked"));
+    });
+
+    it("Does not bring the end of the IDO closer", async () => {
+        await RPC.updateConfig(ctx, { ...config, endAt: config.endAt.subn(1) })
+            .then(() => expect.fail("The end of the IDO is brought closer"))
+            .catch((err) => expect(`${err}`).to.contain("EndAtCannotBeReduced"));
+    });
+});
@@ -0,0 +1,59 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { TokenSale } from "../target/types/token_sale";
+import { Connection } from '@solana/web3.js';
+import { createCtx, Ctx } from "./helpers/ctx";
+import { RPC } from "./helpers/rpc";
+import { sleepTill } from "./helpers/helpers";
+import { Round } from "./types/round";
+import { expect } from "chai";
+
+describe("views", () => {
+    anchor.setProvider(anchor.AnchorProvider.env());
+
+    const program = anchor.workspace.TokenSale as Program<TokenSale>;
+    const connection = new Connection("http://localhost:8899", 'recent');
+    let ctx: Ctx;
+
+    before(async () => {
+        ctx = await createCtx(connection, program);
+        await RPC.initialize(ctx);
+    });
+
+    it("Reports the pool status", async () => {
+        const status = await RPC.poolStatus(ctx);
+
+        expect(status.currentRound).to.be.deep.eq(Round.Buying);
+        expect(status.paused).to.be.false;
+        expect(`${status.tokenPrice}`).to.be.eq(`${ctx.initialTokenPrice}`);
+        expect(`${status.priceUnits}`).to.be.eq(`${ctx.priceUnits}`);
+        expect(`${status.tokensInVault.tokens}`).to.be.eq(`${ctx.amountForSale}`);
+        expect(`${status.tokensSold.tokens}`).to.be.eq("0");
+        // Not limited until the admin sets the buy protection
+        expect(`${status.tokensLeftInSlot.tokens}`).to.be.eq("18446744073709551615");
+        expect(status.openOrders).to.be.eq(0);
+        expect(status.idoOver).to.be.false;
+    });
+
+    it("Quotes a purchase without sending a transaction", async () => {
+        const amount = new anchor.BN(7);
+        const quote = await RPC.quoteBuy(ctx, amount);
+
+        expect(`${quote.amount.tokens}`).to.be.eq(`${amount}`);
+        expect(`${quote.lamports.lamports}`).to.be.eq(`${amount.mul(ctx.initialTokenPrice)}`);
+        expect(`${quote.tokensInVault.tokens}`).to.be.eq(`${ctx.amountForSale}`);
+    });
+
+    it("Quotes the redemption of an order", async () => {
+        const amount = new anchor.BN(5), price = new anchor.BN(3);
+        await RPC.buyTokens(ctx, ctx.traderFirst.signer, amount);
+        await sleepTill((Number(ctx.roundStartAt) + ctx.buyingDuration + 1) * 1000);
+        await RPC.switchToTrading(ctx);
+        const order = await RPC.placeOrder(ctx, ctx.traderFirst.signer, amount, price);
+
+        const quote = await RPC.quoteRedeem(ctx, order.address, new anchor.BN(2));
+
+        expect(`${quote.lamports.lamports}`).to.be.eq(`${price.muln(2)}`);
+        expect(`${quote.tokensInOrder.tokens}`).to.be.eq(`${amount}`);
+    });
+});